@@ -7,5 +7,6 @@ pub mod p2pk;
 
 pub use network::{prefix_byte, AddressType, Network};
 pub use p2pk::{
-    encode_p2pk, encode_p2pk_mainnet, encode_p2pk_testnet, CHECKSUM_LEN, P2PK_ADDRESS_BYTES,
+    encode_p2pk, encode_p2pk_mainnet, encode_p2pk_testnet, p2pk_address_bytes, CHECKSUM_LEN,
+    P2PK_ADDRESS_BYTES,
 };
@@ -22,6 +22,11 @@ pub const CHECKSUM_LEN: usize = 4;
 ///
 /// The public key must be 33 bytes (compressed SEC1 format).
 pub fn encode_p2pk(pubkey: &[u8; 33], network: Network) -> String {
+    base58::encode(&p2pk_address_bytes(pubkey, network))
+}
+
+/// Raw address bytes before Base58: prefix || pubkey || checksum.
+pub fn p2pk_address_bytes(pubkey: &[u8; 33], network: Network) -> [u8; P2PK_ADDRESS_BYTES] {
     let prefix = prefix_byte(network, AddressType::P2PK);
 
     // Build prefix || content for checksum
@@ -38,8 +43,7 @@ pub fn encode_p2pk(pubkey: &[u8; 33], network: Network) -> String {
     address_bytes[0] = prefix;
     address_bytes[1..34].copy_from_slice(pubkey);
     address_bytes[34..38].copy_from_slice(checksum);
-
-    base58::encode(&address_bytes)
+    address_bytes
 }
 
 /// Encode a compressed public key as a mainnet P2PK address.
@@ -5,6 +5,7 @@
 
 #![forbid(unsafe_code)]
 
+use erg_vanity_core::Zeroize;
 use erg_vanity_crypto::hmac::hmac_sha512;
 use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
 use erg_vanity_crypto::secp256k1::scalar::Scalar;
@@ -23,7 +24,7 @@ pub enum Bip32Error {
     InvalidSeedLength,
 }
 
-/// Extended private key (private key + chain code).
+/// Extended private key (private key + chain code), wiped when dropped.
 #[derive(Clone)]
 pub struct ExtendedPrivateKey {
     /// 32-byte private key
@@ -41,22 +42,22 @@ impl ExtendedPrivateKey {
             return Err(Bip32Error::InvalidSeedLength);
         }
 
-        let hmac = hmac_sha512(b"Bitcoin seed", seed);
+        let mut hmac = hmac_sha512(b"Bitcoin seed", seed);
         let (il, ir) = hmac.split_at(32);
+        let master = Self {
+            key: il.try_into().unwrap(),
+            chain_code: ir.try_into().unwrap(),
+        };
+        hmac.zeroize();
 
         // Check IL is valid (< n and != 0)
-        let key: [u8; 32] = il.try_into().unwrap();
-        if Scalar::from_bytes(&key).is_none() {
+        if Scalar::from_bytes(&master.key).is_none() {
             return Err(Bip32Error::InvalidChildKey);
         }
-        if key == [0u8; 32] {
+        if master.key == [0u8; 32] {
             return Err(Bip32Error::ZeroKey);
         }
-
-        let mut chain_code = [0u8; 32];
-        chain_code.copy_from_slice(ir);
-
-        Ok(Self { key, chain_code })
+        Ok(master)
     }
 
     /// Derive hardened child key at given index.
@@ -70,10 +71,13 @@ impl ExtendedPrivateKey {
     ///
     /// For hardened (index >= 0x80000000): Data = 0x00 || key || index
     /// For normal (index < 0x80000000): Data = compressed_pubkey || index
+    ///
+    /// Constant time in the key: the parent pubkey comes from
+    /// `PublicKey::from_private_key_ct` and the addition mod n is branch-free.
     pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
         let parent_scalar = Scalar::from_bytes(&self.key).ok_or(Bip32Error::InvalidChildKey)?;
 
-        let data: [u8; 37] = if index >= HARDENED {
+        let mut data: [u8; 37] = if index >= HARDENED {
             // Hardened derivation: 0x00 || key || index
             let mut d = [0u8; 37];
             d[0] = 0x00;
@@ -82,38 +86,49 @@ impl ExtendedPrivateKey {
             d
         } else {
             // Normal derivation: compressed_pubkey || index
-            let pubkey = PublicKey::from_private_key(&parent_scalar).ok_or(Bip32Error::ZeroKey)?;
+            let pubkey =
+                PublicKey::from_private_key_ct(&parent_scalar).ok_or(Bip32Error::ZeroKey)?;
             let mut d = [0u8; 37];
             d[0..33].copy_from_slice(pubkey.as_bytes());
             d[33..37].copy_from_slice(&index.to_be_bytes());
             d
         };
 
-        let hmac = hmac_sha512(&self.chain_code, &data);
-        let (il, ir) = hmac.split_at(32);
-
-        // Parse IL as scalar
-        let il_bytes: [u8; 32] = il.try_into().unwrap();
-        let il_scalar = Scalar::from_bytes(&il_bytes).ok_or(Bip32Error::InvalidChildKey)?;
-
-        if il_scalar.is_zero() {
-            return Err(Bip32Error::InvalidChildKey);
-        }
-
-        // Child key = IL + parent_key (mod n)
-        let child_scalar = il_scalar.add(&parent_scalar);
+        let child = self.child_from_data(&data);
+        data.zeroize();
+        child
+    }
 
-        if child_scalar.is_zero() {
-            return Err(Bip32Error::ZeroKey);
+    /// Derive a normal (non-hardened) child using a precomputed parent pubkey.
+    ///
+    /// Same result as `derive_child(index)`, minus the parent k·G. Callers deriving
+    /// many siblings compute `parent_pubkey` once. Hardened indices fall back to
+    /// `derive_child`.
+    pub fn derive_normal_child(
+        &self,
+        parent_pubkey: &[u8; 33],
+        index: u32,
+    ) -> Result<Self, Bip32Error> {
+        if index >= HARDENED {
+            return self.derive_child(index);
         }
+        let mut data = [0u8; 37];
+        data[0..33].copy_from_slice(parent_pubkey);
+        data[33..37].copy_from_slice(&index.to_be_bytes());
+        self.child_from_data(&data)
+    }
 
-        let mut chain_code = [0u8; 32];
-        chain_code.copy_from_slice(ir);
+    fn child_from_data(&self, data: &[u8; 37]) -> Result<Self, Bip32Error> {
+        let mut hmac = hmac_sha512(&self.chain_code, data);
+        let (il, ir) = hmac.split_at(32);
 
-        Ok(Self {
-            key: child_scalar.to_bytes(),
-            chain_code,
-        })
+        // Child key = IL + parent_key (mod n)
+        let child = add_tweak(il.try_into().unwrap(), &self.key).map(|key| Self {
+            key,
+            chain_code: ir.try_into().unwrap(),
+        });
+        hmac.zeroize();
+        child
     }
 
     /// Derive key at BIP32 path.
@@ -142,6 +157,74 @@ impl ExtendedPrivateKey {
     pub fn private_key_scalar(&self) -> Option<Scalar> {
         Scalar::from_bytes(&self.key)
     }
+
+    /// Compressed public key for this private key.
+    ///
+    /// Variable time (comb table); the search calls this once per seed. Keys that
+    /// are kept go through `derive_child`, which uses the constant-time path.
+    pub fn public_key(&self) -> Result<PublicKey, Bip32Error> {
+        let scalar = self
+            .private_key_scalar()
+            .ok_or(Bip32Error::InvalidChildKey)?;
+        PublicKey::from_private_key(&scalar).ok_or(Bip32Error::ZeroKey)
+    }
+}
+
+impl Zeroize for ExtendedPrivateKey {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.chain_code.zeroize();
+    }
+}
+
+impl Drop for ExtendedPrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// IL + parent key (mod n), rejecting IL >= n, IL == 0 and a zero result.
+#[cfg(not(feature = "audited"))]
+fn add_tweak(il: &[u8; 32], parent: &[u8; 32]) -> Result<[u8; 32], Bip32Error> {
+    native_add_tweak(il, parent)
+}
+
+/// From-scratch `add_tweak`. The audited build keeps it for the tests against `k256`.
+#[cfg(any(not(feature = "audited"), test))]
+fn native_add_tweak(il: &[u8; 32], parent: &[u8; 32]) -> Result<[u8; 32], Bip32Error> {
+    let il_scalar = Scalar::from_bytes(il).ok_or(Bip32Error::InvalidChildKey)?;
+    if il_scalar.is_zero() {
+        return Err(Bip32Error::InvalidChildKey);
+    }
+    let parent_scalar = Scalar::from_bytes(parent).ok_or(Bip32Error::InvalidChildKey)?;
+
+    let child_scalar = il_scalar.add(&parent_scalar);
+    if child_scalar.is_zero() {
+        return Err(Bip32Error::ZeroKey);
+    }
+    Ok(child_scalar.to_bytes())
+}
+
+/// Audited build: the same addition with `k256` scalars.
+#[cfg(feature = "audited")]
+fn add_tweak(il: &[u8; 32], parent: &[u8; 32]) -> Result<[u8; 32], Bip32Error> {
+    use k256::elliptic_curve::ff::PrimeField;
+
+    let parse = |bytes: &[u8; 32]| {
+        Option::<k256::Scalar>::from(k256::Scalar::from_repr((*bytes).into()))
+            .ok_or(Bip32Error::InvalidChildKey)
+    };
+    let il_scalar = parse(il)?;
+    if bool::from(il_scalar.is_zero()) {
+        return Err(Bip32Error::InvalidChildKey);
+    }
+    let parent_scalar = parse(parent)?;
+
+    let child_scalar = il_scalar + parent_scalar;
+    if bool::from(child_scalar.is_zero()) {
+        return Err(Bip32Error::ZeroKey);
+    }
+    Ok(child_scalar.to_bytes().into())
 }
 
 #[cfg(test)]
@@ -295,6 +378,53 @@ mod tests {
         assert_ne!(master.private_key(), child.private_key());
     }
 
+    #[test]
+    fn test_normal_child_with_cached_pubkey_matches() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let parent = ExtendedPrivateKey::from_seed(&seed)
+            .unwrap()
+            .derive_hardened(0)
+            .unwrap();
+        let pubkey = parent.public_key().unwrap();
+        for index in [0, 1, 99] {
+            let direct = parent.derive_child(index).unwrap();
+            let cached = parent
+                .derive_normal_child(pubkey.as_bytes(), index)
+                .unwrap();
+            assert_eq!(direct.private_key(), cached.private_key());
+            assert_eq!(direct.chain_code(), cached.chain_code());
+        }
+    }
+
+    #[test]
+    fn test_add_tweak_edges() {
+        // n - 1 and n, big-endian
+        let mut n_minus_1 =
+            hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364140")
+                .unwrap();
+        let max: [u8; 32] = n_minus_1.clone().try_into().unwrap();
+        n_minus_1[31] += 1;
+        let n: [u8; 32] = n_minus_1.try_into().unwrap();
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+
+        for add in [add_tweak, native_add_tweak] {
+            assert_eq!(add(&one, &one), Ok(two));
+            assert_eq!(add(&max, &two), Ok(one));
+            assert_eq!(add(&[0u8; 32], &one), Err(Bip32Error::InvalidChildKey));
+            assert_eq!(add(&n, &one), Err(Bip32Error::InvalidChildKey));
+            assert_eq!(add(&max, &one), Err(Bip32Error::ZeroKey));
+        }
+
+        let il = hex::decode("deadbeefcafebabedeadbeefcafebabedeadbeefcafebabedeadbeefcafebabe")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(add_tweak(&il, &max), native_add_tweak(&il, &max));
+    }
+
     #[test]
     fn test_invalid_seed_length() {
         let short_seed = [0u8; 8];
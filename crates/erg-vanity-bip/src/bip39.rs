@@ -5,7 +5,7 @@
 
 #![forbid(unsafe_code)]
 
-use erg_vanity_core::WORDLIST;
+use erg_vanity_core::{Secret, Zeroize, WORDLIST};
 use erg_vanity_crypto::{pbkdf2, sha256};
 use unicode_normalization::UnicodeNormalization;
 
@@ -117,14 +117,11 @@ pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, &'static str> {
 /// Both mnemonic and passphrase are NFKD normalized per BIP39 spec.
 /// Salt = "mnemonic" + passphrase
 /// Iterations = 2048
-/// Output = 64 bytes
-pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> [u8; 64] {
+/// Output = 64 bytes, wiped when dropped
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> Secret<[u8; 64]> {
     // NFKD normalize mnemonic and passphrase
-    let mnemonic_normalized: String = mnemonic.nfkd().collect();
-    let passphrase_normalized: String = passphrase.nfkd().collect();
-
-    // Salt = "mnemonic" + passphrase
-    let salt = format!("mnemonic{}", passphrase_normalized);
+    let mnemonic_normalized = Secret::new(mnemonic.nfkd().collect::<String>());
+    let salt = bip39_salt(passphrase);
 
     // PBKDF2-HMAC-SHA512 with 2048 iterations
     let mut seed = [0u8; 64];
@@ -135,7 +132,35 @@ pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> [u8; 64] {
         &mut seed,
     );
 
-    seed
+    let out = Secret::new(seed);
+    seed.zeroize();
+    out
+}
+
+/// Salt = "mnemonic" + NFKD(passphrase).
+fn bip39_salt(passphrase: &str) -> Secret<String> {
+    let mut salt = String::from("mnemonic");
+    salt.extend(passphrase.nfkd());
+    Secret::new(salt)
+}
+
+/// [`mnemonic_to_seed`] for `L` mnemonics at once, sharing one passphrase.
+///
+/// Runs the PBKDF2 lanes in lockstep; use `L` = 2, 4 or 8.
+pub fn mnemonics_to_seeds<const L: usize>(
+    mnemonics: [&str; L],
+    passphrase: &str,
+) -> [Secret<[u8; 64]>; L] {
+    let normalized: [Secret<String>; L] = mnemonics.map(|m| Secret::new(m.nfkd().collect()));
+    let salt = bip39_salt(passphrase);
+    let mut seeds: [[u8; 64]; L] = pbkdf2::derive_lanes(
+        std::array::from_fn(|l| normalized[l].as_bytes()),
+        salt.as_bytes(),
+        2048,
+    );
+    let out = std::array::from_fn(|l| Secret::new(seeds[l]));
+    seeds.zeroize();
+    out
 }
 
 /// Validate mnemonic checksum.
@@ -240,12 +265,23 @@ mod tests {
                         abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, "");
         assert_eq!(
-            to_hex(&seed),
+            to_hex(&seed[..]),
             "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc1\
              9a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"
         );
     }
 
+    #[test]
+    fn test_mnemonics_to_seeds_matches_single() {
+        let first = "abandon abandon abandon abandon abandon abandon \
+                     abandon abandon abandon abandon abandon about";
+        let second = "legal winner thank year wave sausage worth useful \
+                      legal winner thank yellow";
+        let [a, b] = mnemonics_to_seeds([first, second], "TREZOR");
+        assert_eq!(a, mnemonic_to_seed(first, "TREZOR"));
+        assert_eq!(b, mnemonic_to_seed(second, "TREZOR"));
+    }
+
     #[test]
     fn test_mnemonic_to_seed_with_passphrase() {
         // BIP39 test vector with "TREZOR" passphrase
@@ -253,7 +289,7 @@ mod tests {
                         abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, "TREZOR");
         assert_eq!(
-            to_hex(&seed),
+            to_hex(&seed[..]),
             "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
              1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
         );
@@ -31,6 +31,26 @@ pub fn derive_ergo_key(
     master.derive_path(&path)
 }
 
+/// Derive the external chain key m/44'/429'/account'/0.
+///
+/// Address keys are its normal children; derive them with
+/// `ExtendedPrivateKey::derive_normal_child` to skip the repeated hardened steps.
+///
+/// The search runs this once per seed, so the account pubkey for the last step
+/// comes from the variable-time `public_key`. `derive_ergo_key` is constant time.
+pub fn derive_ergo_external_chain(
+    master: &ExtendedPrivateKey,
+    account: u32,
+) -> Result<ExtendedPrivateKey, Bip32Error> {
+    let account_key = master.derive_path(&[
+        HARDENED | PURPOSE,
+        HARDENED | ERGO_COIN_TYPE,
+        HARDENED | account,
+    ])?;
+    let account_pubkey = account_key.public_key()?.to_bytes();
+    account_key.derive_normal_child(&account_pubkey, 0)
+}
+
 /// Derive first Ergo key at m/44'/429'/0'/0/0.
 pub fn derive_ergo_first_key(
     master: &ExtendedPrivateKey,
@@ -49,7 +69,7 @@ mod tests {
         let mnemonic = "abandon abandon abandon abandon abandon abandon \
                         abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, "");
-        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
 
         // Derive m/44'/429'/0'/0/0
         let ergo_key = derive_ergo_first_key(&master).unwrap();
@@ -68,7 +88,7 @@ mod tests {
         let mnemonic = "abandon abandon abandon abandon abandon abandon \
                         abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, "");
-        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
 
         let key0 = derive_ergo_key(&master, 0, 0, 0).unwrap();
         let key1 = derive_ergo_key(&master, 1, 0, 0).unwrap();
@@ -82,7 +102,7 @@ mod tests {
         let mnemonic = "abandon abandon abandon abandon abandon abandon \
                         abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, "");
-        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
 
         let key0 = derive_ergo_key(&master, 0, 0, 0).unwrap();
         let key1 = derive_ergo_key(&master, 0, 0, 1).unwrap();
@@ -91,6 +111,22 @@ mod tests {
         assert_ne!(key0.private_key(), key1.private_key());
     }
 
+    #[test]
+    fn test_external_chain_children_match_full_path() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon about";
+        let seed = mnemonic_to_seed(mnemonic, "");
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
+
+        let chain = derive_ergo_external_chain(&master, 0).unwrap();
+        let pubkey = chain.public_key().unwrap();
+        for index in 0..3 {
+            let full = derive_ergo_key(&master, 0, 0, index).unwrap();
+            let child = chain.derive_normal_child(pubkey.as_bytes(), index).unwrap();
+            assert_eq!(full.private_key(), child.private_key());
+        }
+    }
+
     #[test]
     fn test_full_pipeline() {
         // Test the full pipeline: mnemonic → seed → master → ergo key
@@ -101,7 +137,7 @@ mod tests {
         let seed = mnemonic_to_seed(mnemonic, "");
 
         // 2. Seed to master key
-        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
 
         // 3. Master to Ergo key at m/44'/429'/0'/0/0
         let ergo_key = derive_ergo_first_key(&master).unwrap();
@@ -0,0 +1,26 @@
+//! `ExtendedPrivateKey` wipes its key and chain code when dropped.
+//!
+//! The crate forbids `unsafe`, so this lives outside it: the only way to look
+//! at a value's bytes after its destructor runs is `drop_in_place` on storage
+//! the test still owns.
+
+use erg_vanity_bip::bip32::ExtendedPrivateKey;
+use std::mem::{size_of, MaybeUninit};
+
+#[test]
+fn drop_wipes_key_and_chain_code() {
+    let seed = [0x5Au8; 64];
+    let key = ExtendedPrivateKey::from_seed(&seed).unwrap();
+    assert_ne!(key.private_key(), &[0u8; 32]);
+    assert_ne!(key.chain_code(), &[0u8; 32]);
+
+    let mut slot = MaybeUninit::new(key);
+    // SAFETY: `slot` holds an initialized key and is not used as one again.
+    // Its storage stays valid, so reading it back as plain bytes is sound.
+    let left = unsafe {
+        slot.assume_init_drop();
+        std::slice::from_raw_parts(slot.as_ptr().cast::<u8>(), size_of::<ExtendedPrivateKey>())
+            .to_vec()
+    };
+    assert!(left.iter().all(|&b| b == 0));
+}
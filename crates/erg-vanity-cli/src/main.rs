@@ -1,10 +1,15 @@
 use clap::Parser;
-use erg_vanity_cpu::MatchType;
+use erg_vanity_cpu::bench::CpuBenchConfig;
+use erg_vanity_cpu::{MatchType, NEVER_MATCH_PREFIX};
+use erg_vanity_engine::calibration::cpu_key;
 use erg_vanity_engine::{
-    estimate_pattern, format_rate, format_time, guess_rate_for, list_gpu_device_hints,
-    list_gpu_devices, run_search, Backend, SearchEvent, SearchRequest,
+    estimate_pattern, estimate_patterns, format_rate, format_time, guess_rate_for,
+    list_opencl_device_hints, load_blocklist, record_measurements, run_search, Backend,
+    Calibration, CalibrationDb, CalibrationSource, CpuLimits, DeviceKind, Exclusion, GpuDuty,
+    RateGuess, SearchEvent, SearchRequest,
 };
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
@@ -20,7 +25,11 @@ struct Args {
     #[arg(long = "list-devices", default_value_t = false)]
     list_devices: bool,
 
-    /// Device indices (e.g. 0,1), "all", "cpu", or "auto" (GPU if present, else CPU)
+    /// Delete cached OpenCL kernel binaries and exit
+    #[arg(long = "clear-kernel-cache", default_value_t = false)]
+    clear_kernel_cache: bool,
+
+    /// Device indices (e.g. 0,1), "all" GPUs, "opencl" (GPUs plus OpenCL CPU/accelerator devices), "cpu", or "auto" (GPU if present, else CPU)
     #[arg(long = "devices", default_value = "auto")]
     devices: String,
 
@@ -40,9 +49,26 @@ struct Args {
     #[arg(short = 'i', long = "ignore-case", default_value_t = false)]
     ignore_case: bool,
 
-    /// Maximum number of matches to find before stopping
-    #[arg(short = 'n', long = "max-results", alias = "num", default_value_t = 1)]
-    max_results: usize,
+    /// Reject hits whose address contains any of these substrings (comma-separated)
+    #[arg(long = "exclude", value_delimiter = ',')]
+    exclude: Vec<String>,
+
+    /// Blocklist file: one rule per line (`literal:<address>`, `contains:<text>`,
+    /// `run:<N>[:<chars>]`, or bare text)
+    #[arg(long = "exclude-file")]
+    exclude_file: Option<PathBuf>,
+
+    /// Reject hits with N or more consecutive look-alike characters (1, i, j, L)
+    #[arg(long = "exclude-run", value_parser = clap::value_parser!(u32).range(2..))]
+    exclude_run: Option<u32>,
+
+    /// Maximum number of matches to find before stopping [default: 1]
+    #[arg(short = 'n', long = "max-results", alias = "num")]
+    max_results: Option<usize>,
+
+    /// Matches to find for each pattern; a pattern that has them stops being searched
+    #[arg(long = "per-pattern", conflicts_with = "max_results")]
+    per_pattern: Option<usize>,
 
     /// BIP44 address indices per seed (m/44'/429'/0'/0/{0..N-1})
     #[arg(long = "index", default_value_t = 1)]
@@ -56,6 +82,26 @@ struct Args {
     #[arg(long = "batch-size")]
     batch_size: Option<usize>,
 
+    /// Resize GPU batches to keep each kernel under this many ms (e.g. 50 for a GPU driving a display)
+    #[arg(long = "kernel-latency-ms")]
+    kernel_latency_ms: Option<u64>,
+
+    /// CPU search threads [default: one per core]
+    #[arg(long = "cpu-threads")]
+    cpu_threads: Option<usize>,
+
+    /// Lower CPU search thread priority to this nice value, 0-19 (Linux)
+    #[arg(long = "cpu-nice")]
+    cpu_nice: Option<i32>,
+
+    /// Percent of time the CPU search runs, 1-100; it sleeps the rest
+    #[arg(long = "cpu-duty")]
+    cpu_duty: Option<u8>,
+
+    /// Percent of time each GPU runs, e.g. 60, or per device 0:60,1:80; idle the rest
+    #[arg(long = "gpu-duty")]
+    gpu_duty: Option<String>,
+
     /// Estimate difficulty and exit
     #[arg(long = "estimate", default_value_t = false)]
     estimate: bool,
@@ -64,19 +110,35 @@ struct Args {
     #[arg(long = "no-gui", default_value_t = false)]
     no_gui: bool,
 
-    /// Run GPU microbenchmark and exit
+    /// Run the GPU microbenchmark (or per-stage CPU bench with --devices cpu) and exit
     #[arg(long = "bench", default_value_t = false)]
     bench: bool,
 
-    /// Number of benchmark iterations
-    #[arg(long = "bench-iters", default_value_t = 100)]
-    bench_iters: u32,
+    /// Run a short timed search on the selected devices and save their measured rates
+    #[arg(long = "calibrate", default_value_t = false)]
+    calibrate: bool,
+
+    /// Sweep GPU batch and work-group sizes, save the fastest per device, and exit
+    #[arg(long = "autotune", default_value_t = false)]
+    autotune: bool,
+
+    /// Do not write measured rates from searches or --bench to the calibration file
+    #[arg(long = "no-save-rates", default_value_t = false)]
+    no_save_rates: bool,
 
-    /// Warmup iterations before timing
-    #[arg(long = "bench-warmup", default_value_t = 5)]
-    bench_warmup: u32,
+    /// Length of the --calibrate run (seconds)
+    #[arg(long = "calibrate-secs", default_value_t = 20)]
+    calibrate_secs: u64,
 
-    /// Batch size for benchmark
+    /// Number of benchmark iterations [default: 100 GPU, 10 CPU]
+    #[arg(long = "bench-iters")]
+    bench_iters: Option<u32>,
+
+    /// Warmup iterations before timing [default: 5 GPU, 1 CPU]
+    #[arg(long = "bench-warmup")]
+    bench_warmup: Option<u32>,
+
+    /// Batch size for benchmark [default: 262144 GPU, 2048 CPU]
     #[arg(long = "bench-batch-size")]
     bench_batch_size: Option<usize>,
 
@@ -102,6 +164,14 @@ fn match_type(args: &Args) -> Result<MatchType, String> {
     }
 }
 
+fn cpu_limits(args: &Args) -> CpuLimits {
+    CpuLimits {
+        threads: args.cpu_threads,
+        nice: args.cpu_nice,
+        duty_percent: args.cpu_duty,
+    }
+}
+
 fn collect_patterns(args: &Args) -> Vec<String> {
     let mut out = Vec::new();
     for p in &args.patterns {
@@ -119,6 +189,22 @@ fn collect_patterns(args: &Args) -> Vec<String> {
     out
 }
 
+fn collect_exclusions(args: &Args) -> Result<Vec<Exclusion>, String> {
+    let mut out = Vec::new();
+    for rule in &args.exclude {
+        if !rule.trim().is_empty() {
+            out.push(Exclusion::parse(rule)?);
+        }
+    }
+    if let Some(ref path) = args.exclude_file {
+        out.extend(load_blocklist(path)?);
+    }
+    if let Some(n) = args.exclude_run {
+        out.push(Exclusion::confusable_run(n as usize));
+    }
+    Ok(out)
+}
+
 fn parse_backend(devices_arg: &str) -> Result<Backend, String> {
     let normalized = devices_arg.trim().to_ascii_lowercase();
     if normalized == "auto" || normalized.is_empty() {
@@ -132,6 +218,9 @@ fn parse_backend(devices_arg: &str) -> Result<Backend, String> {
             devices: Vec::new(),
         });
     }
+    if normalized == "opencl" {
+        return Ok(Backend::OpenCl);
+    }
     let mut parsed = Vec::new();
     for part in devices_arg.split(',') {
         let trimmed = part.trim();
@@ -139,7 +228,7 @@ fn parse_backend(devices_arg: &str) -> Result<Backend, String> {
             continue;
         }
         let idx: usize = trimmed.parse().map_err(|_| {
-            format!("invalid device index '{trimmed}': expected integer, 'all', or 'cpu'")
+            format!("invalid device index '{trimmed}': expected integer, 'all', 'opencl', or 'cpu'")
         })?;
         parsed.push(idx);
     }
@@ -151,6 +240,65 @@ fn parse_backend(devices_arg: &str) -> Result<Backend, String> {
     Ok(Backend::Gpu { devices: parsed })
 }
 
+/// OpenCL device indices for `--devices`; `auto` and `all` mean every GPU,
+/// `opencl` every device of any kind.
+fn gpu_device_indices(devices_arg: &str) -> Result<Vec<usize>, String> {
+    let indices = match parse_backend(devices_arg)? {
+        Backend::Gpu { devices } if !devices.is_empty() => devices,
+        Backend::OpenCl => erg_vanity_gpu::context::GpuContext::enumerate_all_devices()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|i| i.global_idx)
+            .collect(),
+        Backend::Gpu { .. } | Backend::Auto => {
+            erg_vanity_gpu::context::GpuContext::enumerate_devices()
+                .map_err(|e| e.to_string())?
+                .iter()
+                .map(|i| i.global_idx)
+                .collect()
+        }
+        Backend::Cpu => return Err("this mode needs a GPU; --devices cpu is not supported".into()),
+    };
+    if indices.is_empty() {
+        return Err("no OpenCL GPU devices found".into());
+    }
+    Ok(indices)
+}
+
+fn run_autotune(device_indices: &[usize], args: &Args) {
+    let cfg = erg_vanity_gpu::tune::TuneConfig {
+        num_indices: args.num_indices,
+        batch_sizes: args.batch_size.into_iter().collect(),
+        ..Default::default()
+    };
+    let mut results = Vec::new();
+    for &device_index in device_indices {
+        println!("Autotuning device {device_index}...");
+        let tuned = erg_vanity_gpu::tune::autotune_device(device_index, &cfg, |t| {
+            println!(
+                "  batch {:>8}  seed {:>4}  search {:>4}  {:>10.0} seeds/s",
+                t.batch_size,
+                t.seed_local,
+                t.search_local,
+                t.seeds_per_sec()
+            );
+        });
+        match tuned {
+            Ok(r) => results.push(r),
+            Err(e) => {
+                eprintln!("Error autotuning device {device_index}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    erg_vanity_gpu::tune::print_tune_results(&results);
+    let profiles = results.into_iter().map(|r| r.best).collect();
+    match erg_vanity_gpu::tune::record_profiles(profiles) {
+        Ok(path) => println!("\nSaved tuning to {}", path.display()),
+        Err(e) => eprintln!("Warning: could not save tuning: {e}"),
+    }
+}
+
 fn print_hit(hit: &erg_vanity_engine::Hit, originals: &[String], match_num: usize) {
     let pattern = originals
         .get(hit.pattern_index as usize)
@@ -162,19 +310,62 @@ fn print_hit(hit: &erg_vanity_engine::Hit, originals: &[String], match_num: usiz
     println!("Address:  {}", hit.address);
     println!("Pattern:  {pattern}");
     println!("Path:     m/44'/429'/0'/0/{}", hit.address_index);
-    println!("Mnemonic: {}", hit.mnemonic);
-    println!("Entropy:  {}", hex::encode(hit.entropy));
+    println!("Mnemonic: {}", hit.mnemonic.as_str());
+    println!("Entropy:  {}", hex::encode(&hit.entropy[..]));
 }
 
-fn run_estimate(
-    patterns: &[String],
-    match_type: MatchType,
-    ignore_case: bool,
-    backend: &Backend,
-    num_indices: u32,
-) {
-    let devices = list_gpu_device_hints().unwrap_or_default();
-    let guess = guess_rate_for(&devices, backend, match_type, num_indices);
+fn format_pattern_counts(patterns: &[String], counts: &[usize], quota: usize) -> String {
+    let parts: Vec<String> = patterns
+        .iter()
+        .zip(counts)
+        .map(|(p, n)| format!("{p} {n}/{quota}"))
+        .collect();
+    format!(" {{{}}}", parts.join(" · "))
+}
+
+fn run_cpu_bench(args: &Args) {
+    let defaults = CpuBenchConfig::default();
+    let cfg = CpuBenchConfig {
+        batch_size: args.bench_batch_size.unwrap_or(defaults.batch_size),
+        num_indices: args.bench_num_indices.unwrap_or(args.num_indices),
+        iters: args.bench_iters.unwrap_or(defaults.iters),
+        warmup: args.bench_warmup.unwrap_or(defaults.warmup),
+    };
+    let stats = match erg_vanity_cpu::bench::run_cpu_bench(&cfg) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    };
+    erg_vanity_cpu::bench::print_cpu_bench_results(&stats, &cfg);
+    if args.no_save_rates {
+        return;
+    }
+    let (device, driver) = cpu_key();
+    let record = Calibration::new(
+        &device,
+        &driver,
+        cfg.num_indices,
+        stats.seeds_per_sec(&cfg),
+        CalibrationSource::Bench,
+    );
+    match record_measurements(vec![record]) {
+        Ok(path) => println!("\nSaved bench rate to {}", path.display()),
+        Err(e) => eprintln!("Warning: could not save calibration: {e}"),
+    }
+}
+
+fn run_estimate(patterns: &[String], match_type: MatchType, backend: &Backend, args: &Args) {
+    let ignore_case = args.ignore_case;
+    let num_indices = args.num_indices;
+    let max_results = args.max_results.unwrap_or(1);
+    let per_pattern = args.per_pattern;
+    let limits = cpu_limits(args);
+    let devices = list_opencl_device_hints().unwrap_or_default();
+    let calibration = CalibrationDb::load_default();
+    let guess =
+        guess_rate_for(&devices, backend, match_type, num_indices, &calibration).throttled(&limits);
     println!("Difficulty Estimation");
     println!("====================");
     if !matches!(match_type, MatchType::Prefix) {
@@ -185,6 +376,9 @@ fn run_estimate(
         format_rate(guess.addr_per_sec),
         guess.note()
     );
+    if !guess.is_gpu && !limits.describe().is_empty() {
+        println!("  Throttled by CPU limits: {}", limits.describe());
+    }
     if guess.is_gpu && num_indices > 1 {
         println!(
             "  {} seeds/s × {} BIP44 slots (addr/s, not a timer guarantee)",
@@ -203,12 +397,155 @@ fn run_estimate(
                 est.invalid_chars.iter().collect::<String>()
             );
         } else {
+            let eta = guess.percentile_times(&est);
             println!(
-                "  ~{} attempts · {} ({})",
+                "  ~{} attempts · {} on average ({})",
                 format_rate(est.attempts_needed),
                 format_time(est.attempts_needed / guess.addr_per_sec.max(1.0)),
                 guess.note()
             );
+            println!(
+                "  50%: {} · 90%: {} · 99%: {}",
+                format_time(eta.p50),
+                format_time(eta.p90),
+                format_time(eta.p99)
+            );
+        }
+    }
+    if patterns.len() > 1 || max_results > 1 || per_pattern.is_some() {
+        print_combined_estimate(
+            patterns,
+            match_type,
+            ignore_case,
+            &guess,
+            max_results,
+            per_pattern,
+        );
+    }
+}
+
+fn print_combined_estimate(
+    patterns: &[String],
+    match_type: MatchType,
+    ignore_case: bool,
+    guess: &RateGuess,
+    max_results: usize,
+    per_pattern: Option<usize>,
+) {
+    let combined = estimate_patterns(patterns, match_type, ignore_case);
+    if !combined.attempts_needed.is_finite() {
+        return;
+    }
+    let rate = guess.addr_per_sec.max(1.0);
+    let line = |label: &str, attempts: &dyn Fn(f64) -> f64| {
+        println!(
+            "  {label}: 50% {} · 90% {} · 99% {}",
+            format_time(attempts(0.5) / rate),
+            format_time(attempts(0.9) / rate),
+            format_time(attempts(0.99) / rate)
+        );
+    };
+    println!("\nAll patterns (any match counts):");
+    println!(
+        "  ~{} attempts per hit · {} on average",
+        format_rate(combined.attempts_needed),
+        format_time(combined.attempts_needed / rate)
+    );
+    line("First hit", &|q| combined.attempts_for(1, q));
+    match per_pattern {
+        Some(quota) => line(&format!("{quota} per pattern"), &|q| {
+            combined.attempts_for_quota(quota, q)
+        }),
+        None if max_results > 1 => line(&format!("{max_results} hits"), &|q| {
+            combined.attempts_for(max_results, q)
+        }),
+        None => {}
+    }
+    if patterns.len() < 2 {
+        return;
+    }
+    let dominant = combined.dominant(0.9);
+    let names: Vec<String> = dominant
+        .iter()
+        .map(|&i| format!("{} ({:.0}%)", patterns[i], combined.shares[i] * 100.0))
+        .collect();
+    println!("  Most hits come from: {}", names.join(", "));
+    if dominant.len() < patterns.len() {
+        println!("  The other patterns barely change the time to a hit.");
+    }
+    if per_pattern.is_some() {
+        if let Some(slowest) = (0..patterns.len())
+            .max_by(|&a, &b| combined.pattern_attempts[a].total_cmp(&combined.pattern_attempts[b]))
+        {
+            println!(
+                "  With --per-pattern the run waits on {}.",
+                patterns[slowest]
+            );
+        }
+    }
+}
+
+/// Kernel build and the untimed first batch need headroom on top of the engine's minimum.
+const MIN_CALIBRATE_SECS: u64 = 5;
+
+fn run_calibrate(backend: Backend, num_indices: u32, batch_size: Option<usize>, secs: u64) {
+    let secs = secs.max(MIN_CALIBRATE_SECS);
+    let req = SearchRequest {
+        patterns: vec![NEVER_MATCH_PREFIX.into()],
+        match_type: MatchType::Prefix,
+        ignore_case: false,
+        max_results: 1,
+        num_indices,
+        duration: Some(Duration::from_secs(secs)),
+        backend,
+        batch_size,
+        kernel_latency: None,
+        per_pattern: None,
+        exclude: Vec::new(),
+        cpu_limits: CpuLimits::default(),
+        gpu_duty: GpuDuty::default(),
+        save_rates: true,
+    };
+    if let Err(e) = req.validate() {
+        eprintln!("Error: {e}");
+        std::process::exit(2);
+    }
+    eprintln!("Calibrating for {secs}s (--index {num_indices})…");
+    let (tx, rx) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = std::thread::spawn(move || run_search(req, tx, stop));
+    let mut saved = 0usize;
+    let mut exit_err: Option<String> = None;
+    while let Ok(ev) = rx.recv() {
+        match ev {
+            SearchEvent::Progress { checked, rate, .. } => {
+                eprint!("\rChecked: {checked} ({rate:.0} addr/s)   ");
+                let _ = io::stderr().flush();
+            }
+            SearchEvent::Calibrated {
+                device,
+                seeds_per_sec,
+            } => {
+                if saved == 0 {
+                    eprintln!();
+                }
+                saved += 1;
+                println!("{device}: {} seeds/s", format_rate(seeds_per_sec));
+            }
+            SearchEvent::Error { message } => exit_err = Some(message),
+            _ => {}
+        }
+    }
+    let _ = handle.join();
+    if let Some(err) = exit_err {
+        eprintln!("\nCalibration failed: {err}");
+        std::process::exit(1);
+    }
+    match CalibrationDb::default_path() {
+        Some(path) if saved > 0 => println!("Saved to {}", path.display()),
+        _ => {
+            eprintln!("\nNo rate was saved (run too short or calibration file not writable)");
+            std::process::exit(1);
         }
     }
 }
@@ -216,12 +553,36 @@ fn run_estimate(
 fn main() {
     let args = Args::parse();
 
+    if args.clear_kernel_cache {
+        let Some(cache) = erg_vanity_gpu::cache::KernelCache::open_default() else {
+            println!("Kernel cache is disabled.");
+            return;
+        };
+        match cache.clear() {
+            Ok(n) => println!(
+                "Removed {n} cached kernel(s) from {}",
+                cache.dir().display()
+            ),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     if args.list_devices {
-        match list_gpu_devices() {
-            Ok(list) if list.is_empty() => println!("No OpenCL GPU devices found."),
+        match list_opencl_device_hints() {
+            Ok(list) if list.is_empty() => println!("No OpenCL devices found."),
             Ok(list) => {
-                for line in list {
-                    println!("{line}");
+                for device in &list {
+                    println!("{}", device.display_line());
+                }
+                if list.iter().any(|d| d.kind != DeviceKind::Gpu) {
+                    println!(
+                        "CPU and accelerator devices are used only when picked by index \
+                         or with --devices opencl."
+                    );
                 }
             }
             Err(err) => {
@@ -232,39 +593,36 @@ fn main() {
         return;
     }
 
-    if args.bench {
-        let backend = match parse_backend(&args.devices) {
-            Ok(Backend::Gpu { devices }) => devices,
-            Ok(Backend::Auto) => Vec::new(),
-            Ok(Backend::Cpu) => {
-                eprintln!("Error: --bench requires GPU devices");
-                std::process::exit(2);
-            }
+    if args.autotune {
+        let device_indices = match gpu_device_indices(&args.devices) {
+            Ok(d) => d,
             Err(err) => {
                 eprintln!("Error: {err}");
                 std::process::exit(2);
             }
         };
-        let device_indices = if backend.is_empty() {
-            match erg_vanity_gpu::context::GpuContext::enumerate_devices() {
-                Ok(d) => d.iter().map(|i| i.global_idx).collect(),
-                Err(e) => {
-                    eprintln!("Error: {e}");
-                    std::process::exit(2);
-                }
+        run_autotune(&device_indices, &args);
+        return;
+    }
+
+    if args.bench {
+        if matches!(parse_backend(&args.devices), Ok(Backend::Cpu)) {
+            run_cpu_bench(&args);
+            return;
+        }
+        let device_indices = match gpu_device_indices(&args.devices) {
+            Ok(d) => d,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(2);
             }
-        } else {
-            backend
         };
-        if device_indices.is_empty() {
-            eprintln!("Error: no OpenCL GPU devices found");
-            std::process::exit(2);
-        }
+        let defaults = erg_vanity_gpu::bench::BenchConfig::default();
         let cfg = erg_vanity_gpu::bench::BenchConfig {
-            batch_size: args.bench_batch_size.unwrap_or(1 << 18),
+            batch_size: args.bench_batch_size.unwrap_or(defaults.batch_size),
             num_indices: args.bench_num_indices.unwrap_or(args.num_indices),
-            iters: args.bench_iters,
-            warmup: args.bench_warmup,
+            iters: args.bench_iters.unwrap_or(defaults.iters),
+            warmup: args.bench_warmup.unwrap_or(defaults.warmup),
             validate: args.bench_validate,
         };
         let mut results = Vec::new();
@@ -278,6 +636,42 @@ fn main() {
             }
         }
         erg_vanity_gpu::bench::print_bench_results(&results, &cfg);
+        if args.no_save_rates {
+            return;
+        }
+        let records = results
+            .iter()
+            .map(|s| {
+                Calibration::new(
+                    &s.device_info.device_name,
+                    &s.device_info.driver_version,
+                    cfg.num_indices,
+                    s.seeds_per_sec(&cfg),
+                    CalibrationSource::Bench,
+                )
+            })
+            .collect();
+        match record_measurements(records) {
+            Ok(path) => println!("\nSaved bench rates to {}", path.display()),
+            Err(e) => eprintln!("Warning: could not save calibration: {e}"),
+        }
+        return;
+    }
+
+    if args.calibrate {
+        let backend = match parse_backend(&args.devices) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(2);
+            }
+        };
+        run_calibrate(
+            backend,
+            args.num_indices,
+            args.batch_size,
+            args.calibrate_secs,
+        );
         return;
     }
 
@@ -303,13 +697,7 @@ fn main() {
                 std::process::exit(2);
             }
         };
-        run_estimate(
-            &patterns,
-            match_type,
-            args.ignore_case,
-            &backend,
-            args.num_indices,
-        );
+        run_estimate(&patterns, match_type, &backend, &args);
         return;
     }
 
@@ -329,15 +717,38 @@ fn main() {
         }
     };
 
+    let exclude = match collect_exclusions(&args) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let gpu_duty = match args.gpu_duty.as_deref().map(GpuDuty::parse) {
+        Some(Ok(d)) => d,
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+        None => GpuDuty::default(),
+    };
+
     let req = SearchRequest {
         patterns: patterns.clone(),
         match_type,
         ignore_case: args.ignore_case,
-        max_results: args.max_results,
+        max_results: args.max_results.unwrap_or(1),
         num_indices: args.num_indices,
         duration: args.duration_secs.map(Duration::from_secs),
         backend,
         batch_size: args.batch_size,
+        kernel_latency: args.kernel_latency_ms.map(Duration::from_millis),
+        per_pattern: args.per_pattern,
+        exclude,
+        cpu_limits: cpu_limits(&args),
+        gpu_duty,
+        save_rates: !args.no_save_rates,
     };
 
     if let Err(e) = req.validate() {
@@ -357,10 +768,30 @@ fn main() {
         args.num_indices,
         args.num_indices - 1
     );
-    eprintln!("Target matches: {}", args.max_results);
+    match req.per_pattern {
+        Some(quota) => eprintln!(
+            "Target matches: {quota} per pattern ({} total)",
+            req.target()
+        ),
+        None => eprintln!("Target matches: {}", req.max_results),
+    }
+    if !req.exclude.is_empty() {
+        eprintln!("Exclusion rules: {}", req.exclude.len());
+    }
     if let Some(secs) = args.duration_secs {
         eprintln!("Duration limit: {secs}s");
     }
+    let limits = req.cpu_limits.describe();
+    if !limits.is_empty() {
+        eprintln!("CPU limits: {limits} (CPU rate shown after throttling)");
+    }
+    let gpu_duty = req.gpu_duty.describe();
+    if !gpu_duty.is_empty() {
+        eprintln!("GPU duty: {gpu_duty} (GPU rate shown after throttling)");
+    }
+    if cfg!(feature = "audited") {
+        eprintln!("Crypto: audited crates (sha2, hmac, pbkdf2, blake2, bs58, k256)");
+    }
     eprintln!();
 
     let stop = Arc::new(AtomicBool::new(false));
@@ -372,6 +803,8 @@ fn main() {
         eprintln!("\nCtrl+C received, stopping…");
     });
 
+    let target = req.target();
+    let quota = req.per_pattern;
     let (tx, rx) = mpsc::channel();
     let stop_t = Arc::clone(&stop);
     let handle = std::thread::spawn(move || run_search(req, tx, stop_t));
@@ -388,13 +821,39 @@ fn main() {
                 checked,
                 rate,
                 found,
+                rejected,
+                per_pattern,
+                odds,
             } => {
-                eprint!(
-                    "\rChecked: {checked} ({rate:.0} addr/s) [{found}/{}]   ",
-                    args.max_results
-                );
+                let rejected = if rejected > 0 {
+                    format!(" ({rejected} rejected)")
+                } else {
+                    String::new()
+                };
+                let counts = match quota {
+                    Some(q) => format_pattern_counts(&patterns, &per_pattern, q),
+                    None => String::new(),
+                };
+                let odds = if rate > 0.0 && found < target {
+                    format!(
+                        " {:.0}% by now, 90%: {}",
+                        odds.probability * 100.0,
+                        format_time(odds.remaining.p90)
+                    )
+                } else {
+                    String::new()
+                };
+                eprint!("\rChecked: {checked} ({rate:.0} addr/s) [{found}/{target}]{counts}{rejected}{odds}   ");
                 let _ = io::stderr().flush();
             }
+            SearchEvent::Retired { pattern_index } => {
+                let pattern = patterns
+                    .get(pattern_index as usize)
+                    .map(|s| s.as_str())
+                    .unwrap_or("<unknown>");
+                eprintln!();
+                eprintln!("Pattern {pattern} met its quota; retired");
+            }
             SearchEvent::Dropped { count, reason } => {
                 eprintln!();
                 match reason {
@@ -404,12 +863,23 @@ fn main() {
                     ),
                 }
             }
+            SearchEvent::Calibrated {
+                device,
+                seeds_per_sec,
+            } => {
+                eprintln!();
+                eprintln!(
+                    "Recorded {} seeds/s for {device}",
+                    format_rate(seeds_per_sec)
+                );
+            }
             SearchEvent::Error { message } => {
                 exit_err = Some(message);
             }
             SearchEvent::Done {
                 checked,
                 found,
+                rejected,
                 elapsed,
             } => {
                 eprintln!();
@@ -417,6 +887,9 @@ fn main() {
                     "Found {found} match(es) in {:.1}s ({checked} addresses checked)",
                     elapsed.as_secs_f64()
                 );
+                if rejected > 0 {
+                    eprintln!("Rejected {rejected} hit(s) matching the blocklist");
+                }
             }
         }
     }
@@ -443,6 +916,11 @@ mod tests {
         assert!(matches!(parse_backend("cpu").unwrap(), Backend::Cpu));
     }
 
+    #[test]
+    fn backend_opencl() {
+        assert!(matches!(parse_backend("OpenCL").unwrap(), Backend::OpenCl));
+    }
+
     #[test]
     fn backend_devices() {
         let Backend::Gpu { devices } = parse_backend("0,2,2").unwrap() else {
@@ -451,6 +929,15 @@ mod tests {
         assert_eq!(devices, vec![0, 2]);
     }
 
+    #[test]
+    fn pattern_counts_show_quota() {
+        let patterns = vec!["9err".to_string(), "9ego".to_string()];
+        assert_eq!(
+            format_pattern_counts(&patterns, &[2, 0], 2),
+            " {9err 2/2 · 9ego 0/2}"
+        );
+    }
+
     #[test]
     fn estimate_rate_labels_match_constants() {
         let cpu = format_rate(CPU_ASSUMED_RATE);
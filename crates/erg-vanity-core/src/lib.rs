@@ -3,9 +3,13 @@
 #![forbid(unsafe_code)]
 
 mod error;
+mod secret;
 mod wordlist;
 
 pub use error::Error;
+pub use secret::{Secret, SecretBytes};
 pub use wordlist::WORDLIST;
 
+pub use zeroize::Zeroize;
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,309 @@
+//! Zeroize-on-drop wrapper for mnemonics, entropy, seeds and keys.
+
+#[cfg(target_os = "linux")]
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+#[cfg(target_os = "linux")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use zeroize::Zeroize;
+
+/// Secret value, boxed so it never moves and is wiped on drop.
+///
+/// `Debug` prints `<redacted>`. There is no `DerefMut`: a `String` that grew
+/// would leave its old buffer behind unwiped. Values built with
+/// [`Secret::locked`] are also pinned in RAM (`mlock`) on Linux when the
+/// memlock limit allows it; elsewhere, or past the limit, they are only wiped.
+/// Secrets that share a page share its lock.
+pub struct Secret<T: Zeroize + SecretBytes> {
+    value: Box<T>,
+    #[cfg(target_os = "linux")]
+    lock: Option<PageLock>,
+}
+
+/// Where a secret keeps its bytes, for `mlock`.
+pub trait SecretBytes {
+    fn secret_bytes(&self) -> &[u8];
+}
+
+impl SecretBytes for String {
+    fn secret_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> SecretBytes for [u8; N] {
+    fn secret_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<T: Zeroize + SecretBytes> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Box::new(value),
+            #[cfg(target_os = "linux")]
+            lock: None,
+        }
+    }
+
+    /// Like `new`, and keeps the value out of swap where the OS permits.
+    pub fn locked(value: T) -> Self {
+        let mut secret = Self::new(value);
+        secret.lock();
+        secret
+    }
+
+    /// True if the value is pinned in RAM.
+    pub fn is_locked(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            self.lock.is_some()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn lock(&mut self) {
+        self.lock = PageLock::acquire(self.value.secret_bytes());
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn lock(&mut self) {}
+}
+
+/// Secrets holding each locked page. `mlock` works on whole pages and does not
+/// nest, so one `munlock` would release a page for every secret on it.
+#[cfg(target_os = "linux")]
+static LOCKED_PAGES: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+#[cfg(target_os = "linux")]
+fn locked_pages() -> MutexGuard<'static, BTreeMap<usize, usize>> {
+    LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// One secret's hold on the pages under its bytes. A page is unlocked when the
+/// last hold on it is dropped.
+#[cfg(target_os = "linux")]
+struct PageLock {
+    first: usize,
+    pages: usize,
+}
+
+#[cfg(target_os = "linux")]
+impl PageLock {
+    /// None for empty bytes, or when a page cannot be locked (EPERM / ENOMEM
+    /// past RLIMIT_MEMLOCK): the secret falls back to wipe-only.
+    fn acquire(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            return None;
+        }
+        let size = region::page::size();
+        let start = bytes.as_ptr() as usize;
+        let first = start - start % size;
+        let pages = (start + bytes.len() - first).div_ceil(size);
+
+        let mut counts = locked_pages();
+        for i in 0..pages {
+            let page = first + i * size;
+            if !counts.contains_key(&page) {
+                match region::lock(page as *const u8, size) {
+                    // Unlocked in `release` instead, once no secret holds the page.
+                    Ok(guard) => std::mem::forget(guard),
+                    Err(_) => {
+                        Self::release(&mut counts, first, i, size);
+                        return None;
+                    }
+                }
+            }
+            *counts.entry(page).or_insert(0) += 1;
+        }
+        Some(Self { first, pages })
+    }
+
+    fn release(counts: &mut BTreeMap<usize, usize>, first: usize, pages: usize, size: usize) {
+        for page in (0..pages).map(|i| first + i * size) {
+            if let Some(count) = counts.get_mut(&page) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&page);
+                    let _ = region::unlock(page as *const u8, size);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for PageLock {
+    fn drop(&mut self) {
+        let size = region::page::size();
+        Self::release(&mut locked_pages(), self.first, self.pages, size);
+    }
+}
+
+impl<T: Zeroize + SecretBytes> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Zeroize + SecretBytes> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Zeroize + SecretBytes + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        let mut copy = Self::new((*self.value).clone());
+        if self.is_locked() {
+            copy.lock();
+        }
+        copy
+    }
+}
+
+impl<T: Zeroize + SecretBytes + PartialEq> PartialEq for Secret<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Zeroize + SecretBytes + Eq> Eq for Secret<T> {}
+
+impl<T: Zeroize + SecretBytes> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl<T: Zeroize + SecretBytes> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+        // Unlock only after the wipe, so the bytes never reach swap.
+        #[cfg(target_os = "linux")]
+        drop(self.lock.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    /// Records whether `zeroize` ran.
+    struct Probe(Rc<Cell<bool>>, [u8; 8]);
+
+    impl Zeroize for Probe {
+        fn zeroize(&mut self) {
+            self.1.zeroize();
+            self.0.set(true);
+        }
+    }
+
+    impl SecretBytes for Probe {
+        fn secret_bytes(&self) -> &[u8] {
+            &self.1
+        }
+    }
+
+    #[test]
+    fn drop_wipes_value() {
+        let wiped = Rc::new(Cell::new(false));
+        let secret = Secret::locked(Probe(Rc::clone(&wiped), [0xAA; 8]));
+        assert_eq!(secret.1, [0xAA; 8]);
+        assert!(!wiped.get());
+        drop(secret);
+        assert!(wiped.get());
+    }
+
+    /// Records, when wiped, the bytes left behind and whether their page was
+    /// still locked.
+    struct Wiped<T> {
+        value: T,
+        seen: Rc<RefCell<Option<Seen>>>,
+    }
+
+    type Seen = (Vec<u8>, bool);
+
+    impl<T: Zeroize + SecretBytes> Zeroize for Wiped<T> {
+        fn zeroize(&mut self) {
+            let locked = page_locked(self.value.secret_bytes());
+            self.value.zeroize();
+            *self.seen.borrow_mut() = Some((self.value.secret_bytes().to_vec(), locked));
+        }
+    }
+
+    impl<T: SecretBytes> SecretBytes for Wiped<T> {
+        fn secret_bytes(&self) -> &[u8] {
+            self.value.secret_bytes()
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn page_locked(bytes: &[u8]) -> bool {
+        let size = region::page::size();
+        locked_pages().contains_key(&(bytes.as_ptr() as usize / size * size))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn page_locked(_: &[u8]) -> bool {
+        false
+    }
+
+    /// Drop a locked secret; returns what `Wiped` saw and whether it was locked.
+    fn drop_locked<T: Zeroize + SecretBytes>(value: T) -> (Vec<u8>, bool, bool) {
+        let seen = Rc::default();
+        let secret = Secret::locked(Wiped {
+            value,
+            seen: Rc::clone(&seen),
+        });
+        let locked = secret.is_locked();
+        drop(secret);
+        let (left, locked_at_wipe) = seen.take().expect("drop did not wipe");
+        (left, locked_at_wipe, locked)
+    }
+
+    #[test]
+    fn drop_wipes_string_and_array_before_unlocking() {
+        let (left, locked_at_wipe, locked) = drop_locked(String::from("abandon ability able"));
+        assert!(left.is_empty());
+        assert_eq!(locked_at_wipe, locked);
+
+        let (left, locked_at_wipe, locked) = drop_locked([0x5Au8; 32]);
+        assert_eq!(left, [0u8; 32]);
+        assert_eq!(locked_at_wipe, locked);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn shared_page_stays_locked_until_last_secret_drops() {
+        let bytes = [0x5Au8; 64];
+        let (Some(first), Some(second)) = (
+            PageLock::acquire(&bytes[..32]),
+            PageLock::acquire(&bytes[32..]),
+        ) else {
+            return; // past RLIMIT_MEMLOCK: nothing is locked
+        };
+        drop(first);
+        assert!(page_locked(&bytes[32..]));
+        drop(second);
+        assert!(!page_locked(&bytes));
+    }
+
+    #[test]
+    fn debug_is_redacted_and_clone_keeps_value() {
+        let secret = Secret::locked(String::from("abandon ability able"));
+        assert_eq!(format!("{secret:?}"), "<redacted>");
+        let copy = secret.clone();
+        assert_eq!(*copy, "abandon ability able");
+        assert_eq!(copy.is_locked(), secret.is_locked());
+    }
+}
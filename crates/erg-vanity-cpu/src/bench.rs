@@ -0,0 +1,399 @@
+//! CPU benchmark runner.
+//!
+//! Times each stage of the search pipeline on its own, across all rayon threads,
+//! then a real `search_counter_range` run for the end-to-end rate. A separate
+//! single-threaded pass compares the scalar multiplications: k·P by
+//! double-and-add and by GLV/wNAF, and k·G by the comb table the search uses.
+
+#![forbid(unsafe_code)]
+
+use crate::matcher::{first_match, Pattern, NEVER_MATCH_PREFIX};
+use crate::parallel::{chunk_seeds, search_counter_range, SEED_LANES};
+use erg_vanity_address::{p2pk_address_bytes, Network, P2PK_ADDRESS_BYTES};
+use erg_vanity_bip::bip32::ExtendedPrivateKey;
+use erg_vanity_bip::bip39::{entropy_to_mnemonic, mnemonic_to_seed, mnemonics_to_seeds};
+use erg_vanity_bip::bip44::derive_ergo_external_chain;
+use erg_vanity_core::Secret;
+use erg_vanity_crypto::base58;
+use erg_vanity_crypto::entropy::from_salt_counter;
+use erg_vanity_crypto::secp256k1::point::Point;
+use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
+use erg_vanity_crypto::secp256k1::scalar::Scalar;
+use rayon::prelude::*;
+use std::hint::black_box;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+/// Scalars per timed iteration of the multiplication comparison.
+const MUL_SAMPLES: usize = 64;
+
+/// CPU benchmark configuration.
+#[derive(Debug, Clone)]
+pub struct CpuBenchConfig {
+    /// Seeds per iteration.
+    pub batch_size: usize,
+    /// Number of BIP44 address indices to check per seed.
+    pub num_indices: u32,
+    /// Number of timed iterations.
+    pub iters: u32,
+    /// Number of warmup iterations before timing.
+    pub warmup: u32,
+}
+
+impl Default for CpuBenchConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 2048,
+            num_indices: 1,
+            iters: 10,
+            warmup: 1,
+        }
+    }
+}
+
+/// Whether a stage runs once per seed or once per address index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageUnit {
+    Seed,
+    Addr,
+}
+
+/// Wall time of one stage over all timed iterations.
+#[derive(Debug, Clone)]
+pub struct StageStats {
+    pub name: &'static str,
+    pub unit: StageUnit,
+    pub total_ns: u64,
+}
+
+/// Results of a CPU benchmark.
+#[derive(Debug, Clone)]
+pub struct CpuBenchStats {
+    /// Rayon threads used.
+    pub threads: usize,
+    /// Stages in pipeline order.
+    pub stages: Vec<StageStats>,
+    /// Wall time of `search_counter_range` over the same number of seeds.
+    pub end_to_end_ns: u64,
+    /// Scalar multiplication comparison.
+    pub mul: MulStats,
+}
+
+/// Single-threaded time of each scalar multiplication over the same scalars.
+#[derive(Debug, Clone, Default)]
+pub struct MulStats {
+    /// Multiplications timed per method.
+    pub samples: u64,
+    /// k·P by `Point::mul_double_and_add`.
+    pub double_and_add_ns: u64,
+    /// k·P by `Point::mul` (GLV/wNAF).
+    pub glv_ns: u64,
+    /// k·G by `Point::mul_generator` (comb table), as in the search.
+    pub comb_ns: u64,
+}
+
+impl CpuBenchStats {
+    /// Sum of the separately timed stages in nanoseconds.
+    pub fn total_ns(&self) -> u64 {
+        self.stages.iter().map(|s| s.total_ns).sum()
+    }
+
+    /// End-to-end seeds/s.
+    pub fn seeds_per_sec(&self, cfg: &CpuBenchConfig) -> f64 {
+        let seeds = (cfg.iters as u64) * (cfg.batch_size as u64);
+        seeds as f64 / (self.end_to_end_ns.max(1) as f64 / 1e9)
+    }
+
+    /// End-to-end addresses/s at `cfg.num_indices`.
+    pub fn addrs_per_sec(&self, cfg: &CpuBenchConfig) -> f64 {
+        self.seeds_per_sec(cfg) * cfg.num_indices.max(1) as f64
+    }
+}
+
+#[derive(Default)]
+struct Timings {
+    ns: [u64; STAGES.len()],
+    end_to_end_ns: u64,
+    mul: MulStats,
+}
+
+const STAGES: [(&str, StageUnit); 8] = [
+    ("mnemonic", StageUnit::Seed),
+    ("PBKDF2", StageUnit::Seed),
+    ("BIP32 hard", StageUnit::Seed),
+    ("BIP32 child", StageUnit::Addr),
+    ("k·G", StageUnit::Addr),
+    ("Blake2b", StageUnit::Addr),
+    ("Base58", StageUnit::Addr),
+    ("match", StageUnit::Addr),
+];
+
+/// Run the CPU benchmark on the global rayon pool.
+pub fn run_cpu_bench(cfg: &CpuBenchConfig) -> Result<CpuBenchStats, String> {
+    if cfg.batch_size == 0 || cfg.iters == 0 || cfg.num_indices == 0 {
+        return Err("batch size, iterations and indices must be at least 1".into());
+    }
+    let patterns = [Pattern::prefix(NEVER_MATCH_PREFIX)];
+    let mut salt = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut salt);
+
+    let mut timings = Timings::default();
+    let mut counter = 0u64;
+    for i in 0..cfg.warmup + cfg.iters {
+        let mut iter = Timings::default();
+        run_stages(cfg, &patterns, &salt, counter, &mut iter)?;
+
+        let stop = AtomicBool::new(false);
+        let t = Instant::now();
+        let hit = search_counter_range(
+            &patterns,
+            Network::Mainnet,
+            cfg.num_indices,
+            &salt,
+            counter,
+            cfg.batch_size as u64,
+            &stop,
+        );
+        iter.end_to_end_ns = t.elapsed().as_nanos() as u64;
+        black_box(hit.is_some());
+        counter += cfg.batch_size as u64;
+
+        run_mul(&salt, counter, &mut iter.mul);
+
+        if i >= cfg.warmup {
+            for (total, ns) in timings.ns.iter_mut().zip(iter.ns) {
+                *total += ns;
+            }
+            timings.end_to_end_ns += iter.end_to_end_ns;
+            timings.mul.samples += iter.mul.samples;
+            timings.mul.double_and_add_ns += iter.mul.double_and_add_ns;
+            timings.mul.glv_ns += iter.mul.glv_ns;
+            timings.mul.comb_ns += iter.mul.comb_ns;
+        }
+    }
+
+    Ok(CpuBenchStats {
+        threads: rayon::current_num_threads(),
+        stages: STAGES
+            .iter()
+            .zip(timings.ns)
+            .map(|(&(name, unit), total_ns)| StageStats {
+                name,
+                unit,
+                total_ns,
+            })
+            .collect(),
+        end_to_end_ns: timings.end_to_end_ns,
+        mul: timings.mul,
+    })
+}
+
+/// Time `MUL_SAMPLES` multiplications with each method on one thread. The
+/// base point P is a random multiple of G, so no method sees a precomputed base.
+fn run_mul(salt: &[u8; 32], counter: u64, out: &mut MulStats) {
+    let scalar = |i: u64| Scalar::from_bytes(&from_salt_counter(salt, counter + i));
+    let scalars: Vec<Scalar> = (0..MUL_SAMPLES as u64).filter_map(scalar).collect();
+    let base = Point::mul_generator(&scalar(u64::MAX - counter).unwrap_or(Scalar::ONE));
+
+    let t = Instant::now();
+    for k in &scalars {
+        black_box(base.mul_double_and_add(black_box(k)));
+    }
+    out.double_and_add_ns = t.elapsed().as_nanos() as u64;
+
+    let t = Instant::now();
+    for k in &scalars {
+        black_box(base.mul(black_box(k)));
+    }
+    out.glv_ns = t.elapsed().as_nanos() as u64;
+
+    let t = Instant::now();
+    for k in &scalars {
+        black_box(Point::mul_generator(black_box(k)));
+    }
+    out.comb_ns = t.elapsed().as_nanos() as u64;
+
+    out.samples = scalars.len() as u64;
+}
+
+/// One pass of every stage over `cfg.batch_size` seeds; each stage feeds the next.
+fn run_stages(
+    cfg: &CpuBenchConfig,
+    patterns: &[Pattern],
+    salt: &[u8; 32],
+    counter: u64,
+    out: &mut Timings,
+) -> Result<(), String> {
+    let n = cfg.batch_size;
+    let indices = cfg.num_indices;
+    let mut timed = |stage: usize, t: Instant| out.ns[stage] += t.elapsed().as_nanos() as u64;
+
+    let t = Instant::now();
+    let mnemonics: Vec<String> = (0..n as u64)
+        .into_par_iter()
+        .map(|i| entropy_to_mnemonic(&from_salt_counter(salt, counter + i)))
+        .collect::<Result<_, _>>()?;
+    timed(0, t);
+
+    let t = Instant::now();
+    let seeds: Vec<Secret<[u8; 64]>> = mnemonics
+        .par_chunks(SEED_LANES)
+        .flat_map_iter(|group| -> Vec<Secret<[u8; 64]>> {
+            // A short tail group runs single-lane, as the search does.
+            if group.len() < SEED_LANES {
+                return group.iter().map(|m| mnemonic_to_seed(m, "")).collect();
+            }
+            let lanes: [&str; SEED_LANES] = std::array::from_fn(|l| group[l].as_str());
+            mnemonics_to_seeds(lanes, "").into()
+        })
+        .collect();
+    timed(1, t);
+
+    let t = Instant::now();
+    let chains: Vec<(ExtendedPrivateKey, [u8; 33])> = seeds
+        .par_iter()
+        .map(|seed| {
+            let master =
+                ExtendedPrivateKey::from_seed(&seed[..]).map_err(|_| "invalid master key")?;
+            let chain = derive_ergo_external_chain(&master, 0).map_err(|_| "derivation failed")?;
+            let pubkey = chain.public_key().map_err(|_| "invalid public key")?;
+            Ok((chain, pubkey.to_bytes()))
+        })
+        .collect::<Result<_, &str>>()?;
+    timed(2, t);
+
+    let t = Instant::now();
+    let scalars: Vec<Scalar> = chains
+        .par_iter()
+        .flat_map_iter(|(chain, pubkey)| {
+            (0..indices).map(move |i| {
+                chain
+                    .derive_normal_child(pubkey, i)
+                    .ok()
+                    .and_then(|k| k.private_key_scalar())
+                    .unwrap_or(Scalar::ZERO)
+            })
+        })
+        .collect();
+    timed(3, t);
+
+    let t = Instant::now();
+    let pubkeys: Vec<[u8; 33]> = scalars
+        .par_chunks(chunk_seeds(n as u64) * indices as usize)
+        .flat_map_iter(|chunk| {
+            PublicKey::batch_from_private_keys(chunk)
+                .into_iter()
+                .map(|pk| pk.map_or([0u8; 33], |pk| pk.to_bytes()))
+        })
+        .collect();
+    timed(4, t);
+
+    let t = Instant::now();
+    let raw: Vec<[u8; P2PK_ADDRESS_BYTES]> = pubkeys
+        .par_iter()
+        .map(|pk| p2pk_address_bytes(pk, Network::Mainnet))
+        .collect();
+    timed(5, t);
+
+    let t = Instant::now();
+    let addresses: Vec<String> = raw.par_iter().map(|b| base58::encode(b)).collect();
+    timed(6, t);
+
+    let t = Instant::now();
+    let hits = addresses
+        .par_iter()
+        .filter(|a| first_match(patterns, a).is_some())
+        .count();
+    timed(7, t);
+    black_box(hits);
+    Ok(())
+}
+
+/// Print CPU benchmark results in the same layout as the GPU microbench.
+pub fn print_cpu_bench_results(stats: &CpuBenchStats, cfg: &CpuBenchConfig) {
+    println!(
+        "\nCPU bench (wall time, {} threads), batch={}, iters={}, num_indices={}\n",
+        stats.threads, cfg.batch_size, cfg.iters, cfg.num_indices
+    );
+
+    let total = stats.total_ns() as f64;
+    let seeds = (cfg.iters as u64) * (cfg.batch_size as u64);
+    let addrs = seeds * (cfg.num_indices as u64);
+
+    let mut sorted: Vec<&StageStats> = stats.stages.iter().collect();
+    sorted.sort_by_key(|s| std::cmp::Reverse(s.total_ns));
+
+    for s in sorted {
+        let ms = s.total_ns as f64 / 1_000_000.0;
+        let pct = if total > 0.0 {
+            s.total_ns as f64 / total * 100.0
+        } else {
+            0.0
+        };
+        let avg_ms = s.total_ns as f64 / cfg.iters.max(1) as f64 / 1_000_000.0;
+        let (per_unit_ns, unit_name) = match s.unit {
+            StageUnit::Seed => (s.total_ns as f64 / seeds as f64, "seed"),
+            StageUnit::Addr => (s.total_ns as f64 / addrs as f64, "addr"),
+        };
+        println!(
+            "{:<12} {:>8.1} ms ({:>5.1}%)  avg {:>8.3} ms  {:>6.0} ns/{}",
+            format!("{}:", s.name),
+            ms,
+            pct,
+            avg_ms,
+            per_unit_ns,
+            unit_name
+        );
+    }
+
+    println!("{:<12} {:>8.1} ms", "TOTAL:", total / 1_000_000.0);
+    println!(
+        "{:<12} {:>8.1} ms  {:.0} seeds/s  {:.0} addr/s (--index {})",
+        "END-TO-END:",
+        stats.end_to_end_ns as f64 / 1_000_000.0,
+        stats.seeds_per_sec(cfg),
+        stats.addrs_per_sec(cfg),
+        cfg.num_indices
+    );
+
+    let mul = &stats.mul;
+    let per_call = |ns: u64| ns as f64 / mul.samples.max(1) as f64;
+    println!(
+        "\nScalar multiplication (1 thread, {} samples):",
+        mul.samples
+    );
+    for (name, ns) in [
+        ("k·P d&a:", mul.double_and_add_ns),
+        ("k·P GLV:", mul.glv_ns),
+        ("k·G comb:", mul.comb_ns),
+    ] {
+        println!(
+            "{:<12} {:>8.0} ns/mul  {:>5.2}x vs d&a",
+            name,
+            per_call(ns),
+            mul.double_and_add_ns as f64 / ns.max(1) as f64
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_reports_every_stage() {
+        let cfg = CpuBenchConfig {
+            batch_size: 6,
+            num_indices: 2,
+            iters: 1,
+            warmup: 0,
+        };
+        let stats = run_cpu_bench(&cfg).unwrap();
+        assert_eq!(stats.stages.len(), 8);
+        assert!(stats.stages.iter().all(|s| s.total_ns > 0));
+        assert!(stats.end_to_end_ns > 0);
+        assert!(stats.addrs_per_sec(&cfg) > stats.seeds_per_sec(&cfg));
+        assert!(stats.mul.samples > 0);
+        assert!(stats.mul.glv_ns > 0 && stats.mul.double_and_add_ns > 0);
+    }
+}
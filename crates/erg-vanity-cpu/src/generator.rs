@@ -6,21 +6,24 @@
 
 use erg_vanity_address::{encode_p2pk, Network};
 use erg_vanity_bip::bip32::ExtendedPrivateKey;
-use erg_vanity_bip::bip39::{entropy_to_mnemonic, mnemonic_to_seed};
-use erg_vanity_bip::bip44::derive_ergo_key;
+use erg_vanity_bip::bip39::{entropy_to_mnemonic, mnemonic_to_seed, mnemonics_to_seeds};
+use erg_vanity_bip::bip44::{derive_ergo_external_chain, derive_ergo_key};
+use erg_vanity_core::Secret;
 use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
+use erg_vanity_crypto::secp256k1::scalar::Scalar;
 use rand::{CryptoRng, RngCore};
 use std::fmt;
 
-/// Result of successful address generation.
+/// Result of successful address generation. Secrets are wiped on drop and
+/// kept out of swap where permitted.
 #[derive(Clone)]
 pub struct GeneratedAddress {
     /// The generated Ergo address
     pub address: String,
     /// The BIP39 mnemonic (24 words for 256-bit entropy)
-    pub mnemonic: String,
+    pub mnemonic: Secret<String>,
     /// The raw 32-byte private key at m/44'/429'/0'/0/{address_index}
-    pub private_key: [u8; 32],
+    pub private_key: Secret<[u8; 32]>,
     /// BIP44 address index used for this address.
     pub address_index: u32,
 }
@@ -60,32 +63,156 @@ pub fn generate_address_from_entropy(
 
 /// Generate an Ergo address from entropy at m/44'/429'/0'/0/{address_index}.
 ///
-/// Entropy must be 16, 20, 24, 28, or 32 bytes.
+/// Entropy must be 16, 20, 24, 28, or 32 bytes. For several indices on one seed,
+/// build an [`ExternalChain`] once instead.
 pub fn generate_address_from_entropy_at(
     entropy: &[u8],
     network: Network,
     address_index: u32,
 ) -> Result<GeneratedAddress, &'static str> {
-    let mnemonic = entropy_to_mnemonic(entropy)?;
+    let mnemonic = Secret::locked(entropy_to_mnemonic(entropy)?);
     let seed = mnemonic_to_seed(&mnemonic, "");
-    let master = ExtendedPrivateKey::from_seed(&seed).map_err(|_| "invalid master key")?;
-    let ergo_key =
-        derive_ergo_key(&master, 0, 0, address_index).map_err(|_| "derivation failed")?;
+    generate_kept(mnemonic, &seed, address_index, network)
+}
 
-    let scalar = ergo_key
+/// Derive a kept key from its seed on constant-time paths only: `derive_ergo_key`
+/// for every step and `PublicKey::from_private_key_ct` for the address.
+fn generate_kept(
+    mnemonic: Secret<String>,
+    seed: &[u8; 64],
+    address_index: u32,
+    network: Network,
+) -> Result<GeneratedAddress, &'static str> {
+    let master = ExtendedPrivateKey::from_seed(seed).map_err(|_| "invalid master key")?;
+    let key = derive_ergo_key(&master, 0, 0, address_index).map_err(|_| "derivation failed")?;
+    let scalar = key
         .private_key_scalar()
         .ok_or("invalid private key scalar")?;
-    let pubkey = PublicKey::from_private_key(&scalar).ok_or("invalid public key")?;
-    let address = encode_p2pk(pubkey.as_bytes(), network);
-
+    let pubkey = PublicKey::from_private_key_ct(&scalar).ok_or("invalid public key")?;
     Ok(GeneratedAddress {
-        address,
+        address: encode_p2pk(pubkey.as_bytes(), network),
         mnemonic,
-        private_key: *ergo_key.private_key(),
+        private_key: Secret::locked(*key.private_key()),
         address_index,
     })
 }
 
+/// Per-seed derivation state: mnemonic, PBKDF2 seed and the m/44'/429'/0'/0 key
+/// with its pubkey, all computed once. Each address index then costs one
+/// normal child derivation and one k·G.
+#[derive(Clone)]
+pub struct ExternalChain {
+    mnemonic: Secret<String>,
+    chain: ExtendedPrivateKey,
+    chain_pubkey: [u8; 33],
+}
+
+impl ExternalChain {
+    /// Entropy must be 16, 20, 24, 28, or 32 bytes.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, &'static str> {
+        let mnemonic = Secret::new(entropy_to_mnemonic(entropy)?);
+        let seed = mnemonic_to_seed(&mnemonic, "");
+        Self::from_seed(mnemonic, &seed)
+    }
+
+    /// Build `L` chains with their PBKDF2 runs batched in SIMD lanes.
+    pub fn from_entropy_lanes<const L: usize>(
+        entropies: &[[u8; 32]; L],
+    ) -> Result<[Self; L], &'static str> {
+        let mut mnemonics = Vec::with_capacity(L);
+        for entropy in entropies {
+            mnemonics.push(Secret::new(entropy_to_mnemonic(entropy)?));
+        }
+        let seeds: [Secret<[u8; 64]>; L] =
+            mnemonics_to_seeds(std::array::from_fn(|l| mnemonics[l].as_str()), "");
+        let mut chains = Vec::with_capacity(L);
+        for (mnemonic, seed) in mnemonics.into_iter().zip(&seeds) {
+            chains.push(Self::from_seed(mnemonic, seed)?);
+        }
+        Ok(chains
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one chain per lane")))
+    }
+
+    fn from_seed(mnemonic: Secret<String>, seed: &[u8; 64]) -> Result<Self, &'static str> {
+        let master = ExtendedPrivateKey::from_seed(seed).map_err(|_| "invalid master key")?;
+        let chain = derive_ergo_external_chain(&master, 0).map_err(|_| "derivation failed")?;
+        let chain_pubkey = chain
+            .public_key()
+            .map_err(|_| "invalid public key")?
+            .to_bytes();
+        Ok(Self {
+            mnemonic,
+            chain,
+            chain_pubkey,
+        })
+    }
+
+    pub fn mnemonic(&self) -> &str {
+        &self.mnemonic
+    }
+
+    /// Address at m/44'/429'/0'/0/{address_index}, without copying the mnemonic.
+    pub fn address_at(&self, address_index: u32, network: Network) -> Result<String, &'static str> {
+        let scalar = self
+            .derive_key(address_index)?
+            .private_key_scalar()
+            .ok_or("invalid private key scalar")?;
+        let pubkey = PublicKey::from_private_key(&scalar).ok_or("invalid public key")?;
+        Ok(encode_p2pk(pubkey.as_bytes(), network))
+    }
+
+    /// Full result at m/44'/429'/0'/0/{address_index}.
+    ///
+    /// This is the hit path. The cached chain pubkey came from variable-time
+    /// k·G, so the kept key is re-derived from the mnemonic in constant time
+    /// (one more PBKDF2), as the GPU hit check does.
+    pub fn generate_at(
+        &self,
+        address_index: u32,
+        network: Network,
+    ) -> Result<GeneratedAddress, &'static str> {
+        let seed = mnemonic_to_seed(&self.mnemonic, "");
+        let mnemonic = Secret::locked(self.mnemonic.to_string());
+        generate_kept(mnemonic, &seed, address_index, network)
+    }
+
+    fn derive_key(&self, address_index: u32) -> Result<ExtendedPrivateKey, &'static str> {
+        self.chain
+            .derive_normal_child(&self.chain_pubkey, address_index)
+            .map_err(|_| "derivation failed")
+    }
+}
+
+/// Addresses for indices `0..num_indices` of every chain, chain-major.
+///
+/// All pubkeys share one field inversion (`PublicKey::batch_from_private_keys`).
+/// Entries are None where `address_at` would fail.
+pub fn batch_addresses(
+    chains: &[ExternalChain],
+    num_indices: u32,
+    network: Network,
+) -> Vec<Option<String>> {
+    let scalars: Vec<Option<Scalar>> = chains
+        .iter()
+        .flat_map(|chain| {
+            (0..num_indices).map(move |index| {
+                chain
+                    .chain
+                    .derive_normal_child(&chain.chain_pubkey, index)
+                    .ok()?
+                    .private_key_scalar()
+            })
+        })
+        .collect();
+    // Failed derivations stand in as zero, which has no pubkey.
+    let keys: Vec<Scalar> = scalars.iter().map(|s| s.unwrap_or(Scalar::ZERO)).collect();
+    PublicKey::batch_from_private_keys(&keys)
+        .into_iter()
+        .map(|pubkey| Some(encode_p2pk(pubkey?.as_bytes(), network)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,6 +232,60 @@ mod tests {
         assert_eq!(result1.private_key, result2.private_key);
     }
 
+    #[test]
+    fn test_external_chain_matches_full_path() {
+        use erg_vanity_bip::bip44::derive_ergo_key;
+
+        let entropy = [0x5au8; 32];
+        let chain = ExternalChain::from_entropy(&entropy).unwrap();
+        let seed = mnemonic_to_seed(chain.mnemonic(), "");
+        let master = ExtendedPrivateKey::from_seed(&seed[..]).unwrap();
+        for index in [0, 1, 7] {
+            let full = derive_ergo_key(&master, 0, 0, index).unwrap();
+            let scalar = full.private_key_scalar().unwrap();
+            let pubkey = PublicKey::from_private_key(&scalar).unwrap();
+            let generated = chain.generate_at(index, Network::Mainnet).unwrap();
+            assert_eq!(*generated.private_key, *full.private_key());
+            assert_eq!(
+                generated.address,
+                encode_p2pk(pubkey.as_bytes(), Network::Mainnet)
+            );
+            assert_eq!(
+                chain.address_at(index, Network::Mainnet).unwrap(),
+                generated.address
+            );
+        }
+    }
+
+    #[test]
+    fn test_external_chain_lanes_match_single() {
+        let entropies = [[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+        let chains = ExternalChain::from_entropy_lanes(&entropies).unwrap();
+        for (entropy, chain) in entropies.iter().zip(&chains) {
+            let single = ExternalChain::from_entropy(entropy).unwrap();
+            assert_eq!(chain.mnemonic(), single.mnemonic());
+            assert_eq!(
+                chain.address_at(2, Network::Mainnet).unwrap(),
+                single.address_at(2, Network::Mainnet).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_addresses_match_address_at() {
+        let chains = ExternalChain::from_entropy_lanes(&[[9u8; 32], [10u8; 32]]).unwrap();
+        let batch = batch_addresses(&chains, 3, Network::Mainnet);
+        assert_eq!(batch.len(), 6);
+        for (c, chain) in chains.iter().enumerate() {
+            for index in 0..3 {
+                assert_eq!(
+                    batch[c * 3 + index as usize].as_deref(),
+                    Some(chain.address_at(index, Network::Mainnet).unwrap().as_str())
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_generate_address_from_known_entropy() {
         // All zeros → "abandon" mnemonic
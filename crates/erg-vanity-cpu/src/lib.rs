@@ -2,13 +2,17 @@
 
 #![forbid(unsafe_code)]
 
+pub mod bench;
 pub mod generator;
 pub mod matcher;
 pub mod parallel;
 
 pub use generator::{
-    generate_address, generate_address_from_entropy, generate_address_from_entropy_at,
-    GeneratedAddress,
+    batch_addresses, generate_address, generate_address_from_entropy,
+    generate_address_from_entropy_at, ExternalChain, GeneratedAddress,
+};
+pub use matcher::{first_match, MatchType, Pattern, NEVER_MATCH_PREFIX};
+pub use parallel::{
+    chunk_seeds, search, search_counter_range, search_many, CpuHit, SearchResult, CHUNK_SEEDS,
+    SEED_LANES,
 };
-pub use matcher::{first_match, MatchType, Pattern};
-pub use parallel::{search, search_counter_range, search_many, CpuHit, SearchResult};
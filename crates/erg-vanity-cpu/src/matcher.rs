@@ -2,6 +2,11 @@
 
 #![forbid(unsafe_code)]
 
+/// Prefix that practically never matches (about one address in 58^18), for
+/// benches, tuning and calibration runs that must not stop or slow down on a
+/// hit. The matcher still scans the whole pattern for every address.
+pub const NEVER_MATCH_PREFIX: &str = "9eeeeeeeeeeeeeeeeeee";
+
 /// Match type for vanity patterns.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MatchType {
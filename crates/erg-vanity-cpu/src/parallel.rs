@@ -2,9 +2,10 @@
 
 #![forbid(unsafe_code)]
 
-use crate::generator::{generate_address_from_entropy_at, GeneratedAddress};
+use crate::generator::{batch_addresses, ExternalChain, GeneratedAddress};
 use crate::matcher::{first_match, Pattern};
 use erg_vanity_address::Network;
+use erg_vanity_core::Secret;
 use erg_vanity_crypto::entropy::from_salt_counter;
 use rand::rngs::OsRng;
 use rand::RngCore;
@@ -22,11 +23,11 @@ pub struct SearchResult {
     pub attempts: u64,
 }
 
-/// A single verified CPU hit.
+/// A single verified CPU hit. Secrets are wiped on drop.
 #[derive(Clone)]
 pub struct CpuHit {
     pub generated: GeneratedAddress,
-    pub entropy: [u8; 32],
+    pub entropy: Secret<[u8; 32]>,
     pub pattern_index: u32,
 }
 
@@ -112,7 +113,28 @@ fn search_with_salt(
     }
 }
 
+/// Seeds per PBKDF2 batch on CPU. Four u64 lanes fill an AVX2 register.
+pub const SEED_LANES: usize = 4;
+
+/// Upper bound on seeds per rayon task in `search_counter_range`: a multiple of
+/// `SEED_LANES`, and the most chains whose pubkeys share one field inversion.
+pub const CHUNK_SEEDS: usize = 16 * SEED_LANES;
+
+/// Seeds per rayon task for a batch of `batch_size` seeds: enough tasks for
+/// every thread in the current pool, rounded down to whole PBKDF2 lane groups
+/// and capped at `CHUNK_SEEDS`.
+pub fn chunk_seeds(batch_size: u64) -> usize {
+    let per_thread = batch_size / rayon::current_num_threads().max(1) as u64;
+    let lanes = per_thread.min(CHUNK_SEEDS as u64) as usize / SEED_LANES;
+    lanes.max(1) * SEED_LANES
+}
+
 /// Scan a reserved counter range. Used by the engine for batched CPU search.
+///
+/// Each rayon task owns [`chunk_seeds`] seeds: PBKDF2 runs `SEED_LANES` at a
+/// time, then every address in the chunk goes to affine with a single
+/// inversion. A seed whose derivation fails is skipped; the rest of its chunk
+/// is still searched.
 pub fn search_counter_range(
     patterns: &[Pattern],
     network: Network,
@@ -122,25 +144,71 @@ pub fn search_counter_range(
     batch_size: u64,
     stop: &AtomicBool,
 ) -> Option<CpuHit> {
-    (start..start.saturating_add(batch_size))
-        .into_par_iter()
-        .find_map_any(|attempt_id| {
-            if stop.load(Ordering::Relaxed) {
-                return None;
+    let end = start.saturating_add(batch_size);
+    let chunk_len = chunk_seeds(end - start);
+    let chunks = (end - start).div_ceil(chunk_len as u64);
+    (0..chunks).into_par_iter().find_map_any(|chunk| {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let chunk_start = start + chunk * chunk_len as u64;
+        let seeds = (end - chunk_start).min(chunk_len as u64) as usize;
+
+        let mut chains = Vec::with_capacity(seeds);
+        let mut entropies = Vec::with_capacity(seeds);
+        let mut push = |entropy: [u8; 32], chain: Result<ExternalChain, &str>| {
+            if let Ok(chain) = chain {
+                chains.push(chain);
+                entropies.push(entropy);
+            }
+        };
+        let full = seeds - seeds % SEED_LANES;
+        // Mnemonic, PBKDF2 and the hardened path once per seed, like the GPU kernel.
+        for first in (0..full).step_by(SEED_LANES) {
+            let first = chunk_start + first as u64;
+            let group: [[u8; 32]; SEED_LANES] =
+                std::array::from_fn(|l| from_salt_counter(salt, first + l as u64));
+            match ExternalChain::from_entropy_lanes(&group) {
+                Ok(lanes) => {
+                    for (entropy, chain) in group.into_iter().zip(lanes) {
+                        push(entropy, Ok(chain));
+                    }
+                }
+                // One bad lane fails the group; redo it per seed to find which.
+                Err(_) => {
+                    for entropy in group {
+                        push(entropy, ExternalChain::from_entropy(&entropy));
+                    }
+                }
             }
-            let entropy = from_salt_counter(salt, attempt_id);
+        }
+        // Remainder seeds go one lane at a time so nothing past `end` is derived.
+        for s in full..seeds {
+            let entropy = from_salt_counter(salt, chunk_start + s as u64);
+            push(entropy, ExternalChain::from_entropy(&entropy));
+        }
+
+        let addresses = batch_addresses(&chains, num_indices, network);
+        let per_seed = num_indices as usize;
+        for (s, (chain, entropy)) in chains.iter().zip(entropies).enumerate() {
             for addr_idx in 0..num_indices {
-                let result = generate_address_from_entropy_at(&entropy, network, addr_idx).ok()?;
-                if let Some(pattern_index) = first_match(patterns, &result.address) {
+                let Some(address) = &addresses[s * per_seed + addr_idx as usize] else {
+                    continue;
+                };
+                if let Some(pattern_index) = first_match(patterns, address) {
+                    let Ok(generated) = chain.generate_at(addr_idx, network) else {
+                        continue;
+                    };
                     return Some(CpuHit {
-                        generated: result,
-                        entropy,
+                        generated,
+                        entropy: Secret::locked(entropy),
                         pattern_index: pattern_index as u32,
                     });
                 }
             }
-            None
-        })
+        }
+        None
+    })
 }
 
 #[cfg(test)]
@@ -187,6 +255,44 @@ mod tests {
         assert!(result.attempts > 0);
     }
 
+    #[test]
+    fn test_counter_range_hit_matches_single_index_path() {
+        let salt = [3u8; 32];
+        let stop = AtomicBool::new(false);
+        let hit = search_counter_range(
+            &[Pattern::prefix("9")],
+            Network::Mainnet,
+            4,
+            &salt,
+            0,
+            1,
+            &stop,
+        )
+        .unwrap();
+        let expected = crate::generator::generate_address_from_entropy_at(
+            &hit.entropy[..],
+            Network::Mainnet,
+            hit.generated.address_index,
+        )
+        .unwrap();
+        assert_eq!(hit.generated.address, expected.address);
+        assert_eq!(hit.generated.private_key, expected.private_key);
+    }
+
+    #[test]
+    fn test_chunk_seeds_spreads_batch_over_threads() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(16)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            assert_eq!(chunk_seeds(256), 16);
+            assert_eq!(chunk_seeds(1), SEED_LANES);
+            assert_eq!(chunk_seeds(70), SEED_LANES);
+            assert_eq!(chunk_seeds(1 << 20), CHUNK_SEEDS);
+        });
+    }
+
     #[test]
     fn test_search_invalid_pattern_returns_immediately() {
         let pattern = Pattern::prefix("0invalid");
@@ -95,11 +95,11 @@ fn test_mnemonic_matches_bip39_crate() {
             .to_string();
 
         assert_eq!(
-            ours.mnemonic,
+            *ours.mnemonic,
             reference,
             "Mnemonic mismatch for entropy {:02x?}!\n  Ours:      {}\n  Reference: {}",
             &entropy[..4],
-            ours.mnemonic,
+            ours.mnemonic.as_str(),
             reference
         );
     }
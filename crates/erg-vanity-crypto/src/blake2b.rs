@@ -5,12 +5,14 @@
 #![forbid(unsafe_code)]
 
 /// Blake2b block size in bytes.
+#[cfg(any(not(feature = "audited"), test))]
 const BLOCK_SIZE: usize = 128;
 
 /// Blake2b-256 output size.
 const OUT_SIZE: usize = 32;
 
 /// Blake2b initialization vector.
+#[cfg(any(not(feature = "audited"), test))]
 const IV: [u64; 8] = [
     0x6a09e667f3bcc908,
     0xbb67ae8584caa73b,
@@ -23,6 +25,7 @@ const IV: [u64; 8] = [
 ];
 
 /// Sigma permutation table for message schedule.
+#[cfg(any(not(feature = "audited"), test))]
 const SIGMA: [[usize; 16]; 12] = [
     [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
     [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
@@ -39,6 +42,7 @@ const SIGMA: [[usize; 16]; 12] = [
 ];
 
 /// G mixing function.
+#[cfg(any(not(feature = "audited"), test))]
 #[inline]
 fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
     v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
@@ -52,6 +56,7 @@ fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64)
 }
 
 /// Compress a single block.
+#[cfg(any(not(feature = "audited"), test))]
 fn compress(h: &mut [u64; 8], block: &[u8; BLOCK_SIZE], t: u128, last: bool) {
     // Parse message block into 16 words
     let mut m = [0u64; 16];
@@ -92,7 +97,22 @@ fn compress(h: &mut [u64; 8], block: &[u8; BLOCK_SIZE], t: u128, last: bool) {
 }
 
 /// Compute Blake2b-256 digest of input data.
+#[cfg(not(feature = "audited"))]
 pub fn digest(data: &[u8]) -> [u8; OUT_SIZE] {
+    native_digest(data)
+}
+
+/// Audited build: RustCrypto `blake2`.
+#[cfg(feature = "audited")]
+pub fn digest(data: &[u8]) -> [u8; OUT_SIZE] {
+    use blake2::digest::consts::U32;
+    use blake2::Digest;
+    blake2::Blake2b::<U32>::digest(data).into()
+}
+
+/// From-scratch Blake2b-256. The audited build keeps it for the tests against `blake2`.
+#[cfg(any(not(feature = "audited"), test))]
+fn native_digest(data: &[u8]) -> [u8; OUT_SIZE] {
     // Initialize state with parameter block
     // h[0] ^= 0x01010000 ^ (keylen << 8) ^ outlen
     // For Blake2b-256 with no key: h[0] ^= 0x01010000 ^ 32 = 0x01010020
@@ -182,7 +202,7 @@ mod tests {
         ];
 
         for data in test_cases {
-            let our_result = digest(data);
+            let our_result = native_digest(data);
             let ref_result: [u8; 32] = Blake2b256::digest(data).into();
             assert_eq!(
                 our_result,
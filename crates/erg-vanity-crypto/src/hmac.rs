@@ -2,9 +2,13 @@
 
 #![forbid(unsafe_code)]
 
+#[cfg(any(not(feature = "audited"), test))]
 use crate::sha512;
+#[cfg(any(not(feature = "audited"), test))]
+use zeroize::Zeroize;
 
 /// SHA-512 block size in bytes.
+#[cfg(any(not(feature = "audited"), test))]
 const BLOCK_SIZE: usize = 128;
 
 /// Compute HMAC-SHA512.
@@ -12,36 +16,115 @@ const BLOCK_SIZE: usize = 128;
 /// HMAC(K, m) = H((K' XOR opad) || H((K' XOR ipad) || m))
 /// where K' is the key padded/hashed to block size.
 pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
-    // If key is longer than block size, hash it first
-    let key_block: [u8; BLOCK_SIZE] = if key.len() > BLOCK_SIZE {
-        let mut block = [0u8; BLOCK_SIZE];
-        block[..64].copy_from_slice(&sha512::digest(key));
-        block
-    } else {
-        let mut block = [0u8; BLOCK_SIZE];
-        block[..key.len()].copy_from_slice(key);
-        block
-    };
-
-    // Compute inner and outer padded keys
-    let mut i_key_pad = [0x36u8; BLOCK_SIZE];
-    let mut o_key_pad = [0x5cu8; BLOCK_SIZE];
-    for i in 0..BLOCK_SIZE {
-        i_key_pad[i] ^= key_block[i];
-        o_key_pad[i] ^= key_block[i];
+    HmacSha512::new(key).mac(data)
+}
+
+/// HMAC-SHA512 key prepared once for many messages (PBKDF2).
+#[derive(Clone)]
+pub struct HmacSha512 {
+    #[cfg(not(feature = "audited"))]
+    midstates: Midstates,
+    /// Audited build: the keyed RustCrypto MAC that `mac` clones.
+    #[cfg(feature = "audited")]
+    keyed: ::hmac::Hmac<sha2::Sha512>,
+}
+
+impl HmacSha512 {
+    #[cfg(not(feature = "audited"))]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            midstates: Midstates::new(key),
+        }
+    }
+
+    /// Audited build: RustCrypto `hmac`.
+    #[cfg(feature = "audited")]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            keyed: ::hmac::Mac::new_from_slice(key).expect("HMAC takes keys of any length"),
+        }
+    }
+
+    /// MAC one message.
+    #[cfg(not(feature = "audited"))]
+    pub fn mac(&self, data: &[u8]) -> [u8; 64] {
+        self.midstates.mac(data)
+    }
+
+    /// Audited build: RustCrypto `hmac`.
+    #[cfg(feature = "audited")]
+    pub fn mac(&self, data: &[u8]) -> [u8; 64] {
+        use ::hmac::Mac;
+        let mut mac = self.keyed.clone();
+        mac.update(data);
+        mac.finalize().into_bytes().into()
+    }
+}
+
+/// From-scratch HMAC-SHA512 key with the ipad and opad blocks already compressed.
+///
+/// Reusing one key for many messages (PBKDF2) skips two of the four
+/// compressions per MAC. The midstates are as good as the key, so they are
+/// wiped on drop.
+#[cfg(any(not(feature = "audited"), test))]
+#[derive(Clone)]
+pub(crate) struct Midstates {
+    /// SHA-512 state after `K' XOR ipad`.
+    pub(crate) inner: [u64; 8],
+    /// SHA-512 state after `K' XOR opad`.
+    pub(crate) outer: [u64; 8],
+}
+
+#[cfg(any(not(feature = "audited"), test))]
+impl Midstates {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        // If key is longer than block size, hash it first
+        let mut key_block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            key_block[..64].copy_from_slice(&sha512::native_digest(key));
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        // Compute inner and outer padded keys
+        let mut i_key_pad = [0x36u8; BLOCK_SIZE];
+        let mut o_key_pad = [0x5cu8; BLOCK_SIZE];
+        for i in 0..BLOCK_SIZE {
+            i_key_pad[i] ^= key_block[i];
+            o_key_pad[i] ^= key_block[i];
+        }
+
+        let mut inner = sha512::H;
+        let mut outer = sha512::H;
+        sha512::compress(&mut inner, &i_key_pad);
+        sha512::compress(&mut outer, &o_key_pad);
+        key_block.zeroize();
+        i_key_pad.zeroize();
+        o_key_pad.zeroize();
+        Self { inner, outer }
+    }
+
+    /// MAC one message.
+    pub(crate) fn mac(&self, data: &[u8]) -> [u8; 64] {
+        // Inner hash: H(i_key_pad || data)
+        let mut inner_hash = sha512::digest_from(self.inner, BLOCK_SIZE, data);
+        let mut inner_bytes = sha512::state_to_bytes(&inner_hash);
+        // Outer hash: H(o_key_pad || inner_hash)
+        let mut outer_hash = sha512::digest_from(self.outer, BLOCK_SIZE, &inner_bytes);
+        let out = sha512::state_to_bytes(&outer_hash);
+        inner_hash.zeroize();
+        inner_bytes.zeroize();
+        outer_hash.zeroize();
+        out
     }
+}
 
-    // Inner hash: H(i_key_pad || data)
-    let mut inner_input = Vec::with_capacity(BLOCK_SIZE + data.len());
-    inner_input.extend_from_slice(&i_key_pad);
-    inner_input.extend_from_slice(data);
-    let inner_hash = sha512::digest(&inner_input);
-
-    // Outer hash: H(o_key_pad || inner_hash)
-    let mut outer_input = [0u8; BLOCK_SIZE + 64];
-    outer_input[..BLOCK_SIZE].copy_from_slice(&o_key_pad);
-    outer_input[BLOCK_SIZE..].copy_from_slice(&inner_hash);
-    sha512::digest(&outer_input)
+#[cfg(any(not(feature = "audited"), test))]
+impl Drop for Midstates {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+        self.outer.zeroize();
+    }
 }
 
 #[cfg(test)]
@@ -120,10 +203,11 @@ mod tests {
         ];
 
         for (key, data) in test_cases {
-            let our_result = hmac_sha512(key, data);
+            let our_result = Midstates::new(key).mac(data);
             let mut mac = HmacSha512::new_from_slice(key).unwrap();
             mac.update(data);
             let ref_result: [u8; 64] = mac.finalize().into_bytes().into();
+            assert_eq!(hmac_sha512(key, data), ref_result);
             assert_eq!(
                 our_result,
                 ref_result,
@@ -1,7 +1,12 @@
 //! Cryptographic primitives for erg-vanity.
 //!
 //! All implementations are from scratch for GPU portability.
-//! Reference crates (sha2, hmac, etc.) are dev-dependencies only.
+//! Reference crates (sha2, hmac, etc.) are dev-dependencies only, unless the
+//! `audited` feature is on: then SHA-256/512, HMAC, PBKDF2, Blake2b, Base58 and
+//! public key derivation forward to those crates behind the same API. The
+//! from-scratch code those paths replace is gated with
+//! `#[cfg(any(not(feature = "audited"), test))]`, so an audited build still
+//! compiles it for the tests that check it against the reference crates.
 
 #![forbid(unsafe_code)]
 
@@ -13,3 +18,6 @@ pub mod pbkdf2;
 pub mod secp256k1;
 pub mod sha256;
 pub mod sha512;
+
+/// True when built with the `audited` feature.
+pub const AUDITED: bool = cfg!(feature = "audited");
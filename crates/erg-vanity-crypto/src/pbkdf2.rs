@@ -3,7 +3,12 @@
 
 #![forbid(unsafe_code)]
 
-use crate::hmac::hmac_sha512;
+#[cfg(any(not(feature = "audited"), test))]
+use crate::hmac::Midstates;
+#[cfg(any(not(feature = "audited"), test))]
+use crate::sha512::{bytes_to_state, compress_lanes, state_to_bytes};
+#[cfg(any(not(feature = "audited"), test))]
+use zeroize::Zeroize;
 
 /// HMAC-SHA512 output length.
 const HLEN: usize = 64;
@@ -13,9 +18,24 @@ const HLEN: usize = 64;
 /// DK = T1 || T2 || ... || Tn where Ti = F(Password, Salt, c, i)
 /// F(P, S, c, i) = U1 ^ U2 ^ ... ^ Uc
 /// U1 = HMAC(P, S || INT_32_BE(i)), Uj = HMAC(P, Uj-1)
+#[cfg(not(feature = "audited"))]
 pub fn derive(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    native_derive(password, salt, iterations, output)
+}
+
+/// Audited build: RustCrypto `pbkdf2`.
+#[cfg(feature = "audited")]
+pub fn derive(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    assert!(iterations >= 1, "PBKDF2 iterations must be >= 1");
+    pbkdf2::pbkdf2_hmac::<sha2::Sha512>(password, salt, iterations, output);
+}
+
+/// From-scratch `derive`. The audited build keeps it for the tests against `pbkdf2`.
+#[cfg(any(not(feature = "audited"), test))]
+fn native_derive(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
     assert!(iterations >= 1, "PBKDF2 iterations must be >= 1");
 
+    let key = [Midstates::new(password)];
     let num_blocks = output.len().div_ceil(HLEN);
 
     for block_idx in 0..num_blocks {
@@ -23,25 +43,117 @@ pub fn derive(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8])
         let block_start = block_idx * HLEN;
         let block_end = (block_start + HLEN).min(output.len());
 
-        // U1 = HMAC(password, salt || block_num)
-        let mut salt_block = Vec::with_capacity(salt.len() + 4);
-        salt_block.extend_from_slice(salt);
-        salt_block.extend_from_slice(&block_num.to_be_bytes());
+        let [mut result] = f_lanes(&key, salt, block_num, iterations);
 
-        let mut u = hmac_sha512(password, &salt_block);
-        let mut result = u;
+        // Copy to output (may be partial for last block)
+        output[block_start..block_end].copy_from_slice(&result[..block_end - block_start]);
+        result.zeroize();
+    }
+}
 
-        // U2 ... Uc, XORing into result
-        for _ in 1..iterations {
-            u = hmac_sha512(password, &u);
-            for (r, ui) in result.iter_mut().zip(u.iter()) {
-                *r ^= *ui;
-            }
+/// PBKDF2-HMAC-SHA512 for `L` passwords that share a salt, one 64-byte block each
+/// (the BIP39 seed size).
+///
+/// The lanes run in lockstep through the 2×`iterations` compressions, so the
+/// compiler can keep 2, 4 or 8 of them in vector registers.
+#[cfg(not(feature = "audited"))]
+pub fn derive_lanes<const L: usize>(
+    passwords: [&[u8]; L],
+    salt: &[u8],
+    iterations: u32,
+) -> [[u8; HLEN]; L] {
+    native_derive_lanes(passwords, salt, iterations)
+}
+
+/// Audited build: RustCrypto `pbkdf2`, one lane at a time.
+#[cfg(feature = "audited")]
+pub fn derive_lanes<const L: usize>(
+    passwords: [&[u8]; L],
+    salt: &[u8],
+    iterations: u32,
+) -> [[u8; HLEN]; L] {
+    passwords.map(|password| {
+        let mut out = [0u8; HLEN];
+        derive(password, salt, iterations, &mut out);
+        out
+    })
+}
+
+/// From-scratch `derive_lanes`, kept like `native_derive`.
+#[cfg(any(not(feature = "audited"), test))]
+fn native_derive_lanes<const L: usize>(
+    passwords: [&[u8]; L],
+    salt: &[u8],
+    iterations: u32,
+) -> [[u8; HLEN]; L] {
+    assert!(iterations >= 1, "PBKDF2 iterations must be >= 1");
+    let keys = passwords.map(Midstates::new);
+    f_lanes(&keys, salt, 1, iterations)
+}
+
+/// F(P, S, c, i) for each lane, with the ipad/opad midstates reused across iterations.
+///
+/// Every working buffer is derived from the passwords and is wiped before returning.
+#[cfg(any(not(feature = "audited"), test))]
+fn f_lanes<const L: usize>(
+    keys: &[Midstates; L],
+    salt: &[u8],
+    block_num: u32,
+    iterations: u32,
+) -> [[u8; HLEN]; L] {
+    let mut inner = [[0u64; L]; 8];
+    let mut outer = [[0u64; L]; 8];
+    let mut u = [[0u64; L]; 8];
+
+    // U1 = HMAC(password, salt || block_num); variable length, so one lane at a time
+    let mut salt_block = Vec::with_capacity(salt.len() + 4);
+    salt_block.extend_from_slice(salt);
+    salt_block.extend_from_slice(&block_num.to_be_bytes());
+    for (l, key) in keys.iter().enumerate() {
+        let mut mac = key.mac(&salt_block);
+        let mut u1 = bytes_to_state(&mac);
+        for j in 0..8 {
+            inner[j][l] = key.inner[j];
+            outer[j][l] = key.outer[j];
+            u[j][l] = u1[j];
         }
+        mac.zeroize();
+        u1.zeroize();
+    }
+    let mut result = u;
 
-        // Copy to output (may be partial for last block)
-        output[block_start..block_end].copy_from_slice(&result[..block_end - block_start]);
+    // Uj is 64 bytes after a 128-byte pad block: one padded block, fixed length word.
+    let mut block = [[0u64; L]; 16];
+    block[8] = [1u64 << 63; L];
+    block[15] = [((128 + HLEN) * 8) as u64; L];
+
+    // U2 ... Uc, XORing into result
+    let mut state = inner;
+    for _ in 1..iterations {
+        block[..8].copy_from_slice(&u);
+        state = inner;
+        compress_lanes(&mut state, &block);
+
+        block[..8].copy_from_slice(&state);
+        u = outer;
+        compress_lanes(&mut u, &block);
+
+        for (r, ui) in result.iter_mut().zip(u.iter()) {
+            for l in 0..L {
+                r[l] ^= ui[l];
+            }
+        }
     }
+
+    let out = std::array::from_fn(|l| state_to_bytes(&std::array::from_fn(|j| result[j][l])));
+    inner.zeroize();
+    outer.zeroize();
+    u.zeroize();
+    result.zeroize();
+    state.zeroize();
+    block.zeroize();
+    salt_block.zeroize();
+    out
 }
 
 #[cfg(test)]
@@ -110,6 +222,24 @@ mod tests {
         assert_eq!(&output[..64], &block1[..]);
     }
 
+    #[test]
+    fn test_lanes_match_single() {
+        let long = [b'x'; 200];
+        let passwords: [&[u8]; 4] = [b"password", b"", &long, b"abandon about"];
+        let lanes = derive_lanes(passwords, b"mnemonic", 2048);
+        for (password, lane) in passwords.iter().zip(lanes) {
+            let mut single = [0u8; 64];
+            derive(password, b"mnemonic", 2048, &mut single);
+            assert_eq!(lane, single);
+        }
+
+        let [a, b] = derive_lanes([b"password".as_slice(), b"pass"], b"salt", 1);
+        let mut expected = [0u8; 64];
+        derive(b"pass", b"salt", 1, &mut expected);
+        assert_eq!(b, expected);
+        assert_ne!(a, b);
+    }
+
     #[test]
     fn test_against_pbkdf2_crate() {
         use pbkdf2::pbkdf2_hmac;
@@ -130,7 +260,7 @@ mod tests {
 
         for (password, salt, iterations) in test_cases {
             let mut our_result = [0u8; 64];
-            derive(password, salt, iterations, &mut our_result);
+            native_derive(password, salt, iterations, &mut our_result);
 
             let mut ref_result = [0u8; 64];
             pbkdf2_hmac::<Sha512>(password, salt, iterations, &mut ref_result);
@@ -143,6 +273,15 @@ mod tests {
                 String::from_utf8_lossy(salt),
                 iterations
             );
+
+            let mut public = [0u8; 64];
+            derive(password, salt, iterations, &mut public);
+            assert_eq!(public, ref_result);
+
+            let [a, b, c, d, e, f, g, h] = native_derive_lanes([password; 8], salt, iterations);
+            for lane in [a, b, c, d, e, f, g, h] {
+                assert_eq!(lane, ref_result);
+            }
         }
     }
 }
@@ -0,0 +1,99 @@
+//! 8-bit fixed-base comb table for k·G, shared by the CPU and the GPU upload.
+//!
+//! Layout: 32 windows × 256 entries × 16 little-endian u32 limbs (affine X||Y).
+//! Entry `[w][b]` is `b · 256^(31−w) · G`, so window 0 is the MSB of
+//! `Scalar::to_bytes`. Entry 0 is unused (infinity).
+//!
+//! Regenerate with: cargo run -p erg-vanity-gpu --bin gen_g_table
+
+#![forbid(unsafe_code)]
+
+use super::field::FieldElement;
+use super::point::Point;
+use super::scalar::Scalar;
+use std::sync::OnceLock;
+
+pub const COMB_WINDOWS: usize = 32;
+pub const COMB_ENTRIES: usize = 256;
+pub const COMB_XY_LIMBS: usize = 16;
+pub const COMB_TABLE_U32S: usize = COMB_WINDOWS * COMB_ENTRIES * COMB_XY_LIMBS;
+pub const COMB_TABLE_BYTES: &[u8] = include_bytes!("comb_table.bin");
+
+/// Raw table as u32 limbs, the GPU buffer layout.
+pub fn comb_table_words() -> Vec<u32> {
+    assert_eq!(
+        COMB_TABLE_BYTES.len(),
+        COMB_TABLE_U32S * 4,
+        "comb_table.bin size"
+    );
+    COMB_TABLE_BYTES
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// Affine table entries decoded once into field elements (~1 MiB).
+fn table() -> &'static [(FieldElement, FieldElement)] {
+    static TABLE: OnceLock<Vec<(FieldElement, FieldElement)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        comb_table_words()
+            .chunks_exact(COMB_XY_LIMBS)
+            .map(|e| (field_from_u32s(&e[..8]), field_from_u32s(&e[8..])))
+            .collect()
+    })
+}
+
+fn field_from_u32s(limbs: &[u32]) -> FieldElement {
+    let mut out = [0u64; 4];
+    for (i, limb) in out.iter_mut().enumerate() {
+        *limb = limbs[2 * i] as u64 | (limbs[2 * i + 1] as u64) << 32;
+    }
+    FieldElement::from_limbs(out)
+}
+
+/// k·G as one mixed addition per non-zero scalar byte, no doublings.
+pub fn mul_generator(k: &Scalar) -> Point {
+    let table = table();
+    let mut acc = Point::INFINITY;
+    for (window, &b) in k.to_bytes().iter().enumerate() {
+        if b != 0 {
+            let (x, y) = &table[window * COMB_ENTRIES + b as usize];
+            acc = acc.add_affine(x, y);
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTED_GX: [u32; 8] = [
+        0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC,
+        0x79BE667E,
+    ];
+    const EXPECTED_GY: [u32; 8] = [
+        0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC, 0x26A3C465,
+        0x483ADA77,
+    ];
+
+    #[test]
+    fn comb_table_lsb_one_is_g() {
+        let words = comb_table_words();
+        let off = (31 * COMB_ENTRIES + 1) * COMB_XY_LIMBS;
+        assert_eq!(&words[off..off + 8], &EXPECTED_GX);
+        assert_eq!(&words[off + 8..off + 16], &EXPECTED_GY);
+    }
+
+    #[test]
+    fn comb_matches_double_and_add() {
+        let mut k = [0u8; 32];
+        for (i, b) in k.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(37) ^ 0x5a;
+        }
+        k[0] = 0x7f;
+        k[7] = 0;
+        let k = Scalar::from_bytes(&k).unwrap();
+        assert_eq!(mul_generator(&k), Point::generator().mul_double_and_add(&k));
+    }
+}
@@ -0,0 +1,192 @@
+//! Constant-time k·P for keys that are kept and shown to the user.
+//!
+//! The search paths (`Point::mul`, the comb in `mul_generator`) branch on key
+//! bits and index tables by them; that is fine for throwaway candidates. Here
+//! every step is fixed: 4-bit windows over all 64 nibbles, table entries picked
+//! by scanning the whole table under a mask, and the complete projective
+//! formulas of Renes–Costello–Batina (2016, algorithms 7 and 9), which need no
+//! special cases for the identity or for doubling.
+
+#![forbid(unsafe_code)]
+
+use super::field::{mask, FieldElement};
+use super::point::Point;
+use super::scalar::Scalar;
+
+/// 3·b for y² = x³ + 7.
+const B3: FieldElement = FieldElement::from_limbs([21, 0, 0, 0]);
+
+const WINDOW: usize = 4;
+const TABLE_LEN: usize = 1 << WINDOW;
+const WINDOWS: usize = 256 / WINDOW;
+
+/// Homogeneous projective point (X : Y : Z), x = X/Z, y = Y/Z.
+/// The identity is (0 : 1 : 0).
+#[derive(Clone, Copy)]
+struct Projective {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+}
+
+impl Projective {
+    const IDENTITY: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ZERO,
+    };
+
+    /// From Jacobian (X, Y, Z), where x = X/Z², y = Y/Z³: (X·Z : Y : Z³).
+    fn from_point(p: &Point) -> Self {
+        let (x, y, z) = p.coords();
+        let projective = Self {
+            x: x.mul(&z),
+            y,
+            z: z.square().mul(&z),
+        };
+        let infinity = z.zero_mask();
+        Self::select(infinity, &Self::IDENTITY, &projective)
+    }
+
+    /// To Jacobian: (X·Z, Y·Z², Z). The identity maps to a Z = 0 point.
+    fn to_point(self) -> Point {
+        let z2 = self.z.square();
+        Point::from_jacobian(self.x.mul(&self.z), self.y.mul(&z2), self.z)
+    }
+
+    fn select(mask: u64, a: &Self, b: &Self) -> Self {
+        Self {
+            x: FieldElement::select(mask, &a.x, &b.x),
+            y: FieldElement::select(mask, &a.y, &b.y),
+            z: FieldElement::select(mask, &a.z, &b.z),
+        }
+    }
+
+    /// Complete addition (RCB algorithm 7, a = 0).
+    fn add(&self, other: &Self) -> Self {
+        let (x1, y1, z1) = (&self.x, &self.y, &self.z);
+        let (x2, y2, z2) = (&other.x, &other.y, &other.z);
+
+        let mut t0 = x1.mul(x2);
+        let mut t1 = y1.mul(y2);
+        let mut t2 = z1.mul(z2);
+        let mut t3 = x1.add(y1).mul(&x2.add(y2));
+        let mut t4 = t0.add(&t1);
+        t3 = t3.sub(&t4);
+        t4 = y1.add(z1).mul(&y2.add(z2));
+        t4 = t4.sub(&t1.add(&t2));
+        let mut x3 = x1.add(z1).mul(&x2.add(z2));
+        let mut y3 = x3.sub(&t0.add(&t2));
+        x3 = t0.add(&t0);
+        t0 = x3.add(&t0);
+        t2 = B3.mul(&t2);
+        let mut z3 = t1.add(&t2);
+        t1 = t1.sub(&t2);
+        y3 = B3.mul(&y3);
+        x3 = t3.mul(&t1).sub(&t4.mul(&y3));
+        y3 = t1.mul(&z3).add(&y3.mul(&t0));
+        z3 = z3.mul(&t4).add(&t0.mul(&t3));
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// Complete doubling (RCB algorithm 9, a = 0).
+    fn double(&self) -> Self {
+        let (x, y, z) = (&self.x, &self.y, &self.z);
+
+        let t0 = y.square();
+        let mut z3 = t0.add(&t0);
+        z3 = z3.add(&z3);
+        z3 = z3.add(&z3);
+        let t1 = y.mul(z);
+        let t2 = B3.mul(&z.square());
+        let mut x3 = t2.mul(&z3);
+        let mut y3 = t0.add(&t2);
+        z3 = t1.mul(&z3);
+        let t0 = t0.sub(&t2.add(&t2).add(&t2));
+        y3 = x3.add(&t0.mul(&y3));
+        x3 = t0.mul(&x.mul(y));
+        x3 = x3.add(&x3);
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+/// k·P in constant time with respect to `k` (and to P's coordinates).
+///
+/// Returns a Z = 0 point for k = 0 or P at infinity, like `Point::mul`.
+pub fn mul(p: &Point, k: &Scalar) -> Point {
+    let base = Projective::from_point(p);
+
+    // table[i] = i·P
+    let mut table = [Projective::IDENTITY; TABLE_LEN];
+    for i in 1..TABLE_LEN {
+        table[i] = table[i - 1].add(&base);
+    }
+
+    let mut acc = Projective::IDENTITY;
+    for w in (0..WINDOWS).rev() {
+        for _ in 0..WINDOW {
+            acc = acc.double();
+        }
+        let nibble = k.nibble(w);
+        let mut entry = Projective::IDENTITY;
+        for (i, candidate) in table.iter().enumerate() {
+            entry = Projective::select(mask(i as u64 == nibble), candidate, &entry);
+        }
+        acc = acc.add(&entry);
+    }
+    acc.to_point()
+}
+
+/// k·G in constant time with respect to `k`.
+pub fn mul_generator(k: &Scalar) -> Point {
+    mul(&Point::generator(), k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256;
+
+    fn scalar(label: &str) -> Scalar {
+        Scalar::from_bytes(&sha256::digest(label.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn matches_variable_time_mul() {
+        let g = Point::generator();
+        let p = Point::mul_generator(&scalar("base"));
+        let n_minus_1 = Scalar::ONE.neg();
+        let mut ks = vec![Scalar::ONE, Scalar::from_limbs([16, 0, 0, 0]), n_minus_1];
+        ks.extend((0..8).map(|i| scalar(&format!("k{i}"))));
+        for k in ks {
+            assert_eq!(mul_generator(&k), g.mul_double_and_add(&k));
+            assert_eq!(mul(&p, &k), p.mul_double_and_add(&k));
+        }
+    }
+
+    #[test]
+    fn zero_and_infinity() {
+        assert!(mul_generator(&Scalar::ZERO).is_infinity());
+        assert!(mul(&Point::INFINITY, &scalar("k")).is_infinity());
+    }
+
+    #[test]
+    fn complete_formulas_handle_doubling_and_identity() {
+        let g = Projective::from_point(&Point::generator());
+        let doubled = Point::generator().double();
+        assert_eq!(g.add(&g).to_point(), doubled);
+        assert_eq!(g.double().to_point(), doubled);
+        assert_eq!(g.add(&Projective::IDENTITY).to_point(), Point::generator());
+        assert!(Projective::IDENTITY.double().to_point().is_infinity());
+    }
+}
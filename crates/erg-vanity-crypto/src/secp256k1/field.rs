@@ -1,8 +1,14 @@
 //! Prime field arithmetic for secp256k1.
 //! p = 2^256 - 2^32 - 977 = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F
+//!
+//! add, sub, neg, mul and square run in constant time: reductions select their
+//! result by mask instead of branching on the value. `pow` branches on the
+//! exponent only, so `inv` (fixed exponent p − 2) is constant time for non-zero input.
 
 #![forbid(unsafe_code)]
 
+use std::hint::black_box;
+
 /// The secp256k1 field prime p = 2^256 - 2^32 - 977.
 /// Represented as 4 x 64-bit limbs in little-endian order.
 const P: [u64; 4] = [
@@ -20,6 +26,11 @@ const P_MINUS_2: [u64; 4] = [
     0xFFFFFFFFFFFFFFFF,
 ];
 
+/// All-ones for true, 0 for false.
+pub(crate) fn mask(bit: bool) -> u64 {
+    (bit as u64).wrapping_neg()
+}
+
 /// Field element in secp256k1's prime field GF(p).
 /// Stored as 4 x 64-bit limbs in little-endian order.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -97,7 +108,21 @@ impl FieldElement {
 
     /// Check if zero.
     pub fn is_zero(&self) -> bool {
-        self.limbs[0] == 0 && self.limbs[1] == 0 && self.limbs[2] == 0 && self.limbs[3] == 0
+        (self.limbs[0] | self.limbs[1] | self.limbs[2] | self.limbs[3]) == 0
+    }
+
+    /// All-ones if zero, else 0, without branching.
+    pub(crate) fn zero_mask(&self) -> u64 {
+        let any = self.limbs[0] | self.limbs[1] | self.limbs[2] | self.limbs[3];
+        ((any | any.wrapping_neg()) >> 63).wrapping_sub(1)
+    }
+
+    /// `a` where `mask` is all-ones, `b` where it is 0.
+    pub(crate) fn select(mask: u64, a: &Self, b: &Self) -> Self {
+        let mask = black_box(mask);
+        Self {
+            limbs: std::array::from_fn(|i| (a.limbs[i] & mask) | (b.limbs[i] & !mask)),
+        }
     }
 
     /// Addition: self + other (mod p).
@@ -107,26 +132,26 @@ impl FieldElement {
         let (r2, c2) = self.limbs[2].carrying_add(other.limbs[2], c1);
         let (r3, c3) = self.limbs[3].carrying_add(other.limbs[3], c2);
 
-        let mut result = Self {
+        let sum = Self {
             limbs: [r0, r1, r2, r3],
         };
-
-        if c3 || result.gte_p() {
-            result = result.sub_p();
-        }
-
-        result
+        // Keep sum − p if the sum carried out or did not borrow against p.
+        let (reduced, borrow) = sum.sub_p();
+        Self::select(mask(c3 | !borrow), &reduced, &sum)
     }
 
-    /// Subtract p from self.
-    fn sub_p(&self) -> Self {
+    /// Subtract p from self; also returns the borrow (self < p).
+    fn sub_p(&self) -> (Self, bool) {
         let (r0, b0) = self.limbs[0].overflowing_sub(P[0]);
         let (r1, b1) = self.limbs[1].borrowing_sub(P[1], b0);
         let (r2, b2) = self.limbs[2].borrowing_sub(P[2], b1);
-        let (r3, _) = self.limbs[3].borrowing_sub(P[3], b2);
-        Self {
-            limbs: [r0, r1, r2, r3],
-        }
+        let (r3, b3) = self.limbs[3].borrowing_sub(P[3], b2);
+        (
+            Self {
+                limbs: [r0, r1, r2, r3],
+            },
+            b3,
+        )
     }
 
     /// Subtraction: self - other (mod p).
@@ -136,23 +161,19 @@ impl FieldElement {
         let (r2, b2) = self.limbs[2].borrowing_sub(other.limbs[2], b1);
         let (r3, b3) = self.limbs[3].borrowing_sub(other.limbs[3], b2);
 
-        let mut result = Self {
+        Self {
             limbs: [r0, r1, r2, r3],
-        };
-
-        if b3 {
-            result = result.add_p();
         }
-
-        result
+        .add_p_masked(mask(b3))
     }
 
-    /// Add p to self.
-    fn add_p(&self) -> Self {
-        let (r0, c0) = self.limbs[0].overflowing_add(P[0]);
-        let (r1, c1) = self.limbs[1].carrying_add(P[1], c0);
-        let (r2, c2) = self.limbs[2].carrying_add(P[2], c1);
-        let (r3, _) = self.limbs[3].carrying_add(P[3], c2);
+    /// Add `p & mask` to self.
+    fn add_p_masked(&self, mask: u64) -> Self {
+        let mask = black_box(mask);
+        let (r0, c0) = self.limbs[0].overflowing_add(P[0] & mask);
+        let (r1, c1) = self.limbs[1].carrying_add(P[1] & mask, c0);
+        let (r2, c2) = self.limbs[2].carrying_add(P[2] & mask, c1);
+        let (r3, _) = self.limbs[3].carrying_add(P[3] & mask, c2);
         Self {
             limbs: [r0, r1, r2, r3],
         }
@@ -160,17 +181,15 @@ impl FieldElement {
 
     /// Negation: -self (mod p).
     pub fn neg(&self) -> Self {
-        if self.is_zero() {
-            *self
-        } else {
-            let (r0, b0) = P[0].overflowing_sub(self.limbs[0]);
-            let (r1, b1) = P[1].borrowing_sub(self.limbs[1], b0);
-            let (r2, b2) = P[2].borrowing_sub(self.limbs[2], b1);
-            let (r3, _) = P[3].borrowing_sub(self.limbs[3], b2);
-            Self {
-                limbs: [r0, r1, r2, r3],
-            }
-        }
+        let (r0, b0) = P[0].overflowing_sub(self.limbs[0]);
+        let (r1, b1) = P[1].borrowing_sub(self.limbs[1], b0);
+        let (r2, b2) = P[2].borrowing_sub(self.limbs[2], b1);
+        let (r3, _) = P[3].borrowing_sub(self.limbs[3], b2);
+        let negated = Self {
+            limbs: [r0, r1, r2, r3],
+        };
+        // p − 0 = p, which must come back as 0.
+        Self::select(self.zero_mask(), &Self::ZERO, &negated)
     }
 
     /// Multiplication: self * other (mod p).
@@ -198,15 +217,8 @@ impl FieldElement {
                 carry = (acc >> 64) + (hi as u128);
             }
 
-            // Spill carry into following limbs
-            let mut k = i + 4;
-            while carry != 0 {
-                debug_assert!(k < 8, "512-bit multiply overflow");
-                let acc = (wide[k] as u128) + carry;
-                wide[k] = acc as u64;
-                carry = acc >> 64;
-                k += 1;
-            }
+            // Row i has only touched limbs i..i+3, and the final carry fits in 64 bits.
+            wide[i + 4] = carry as u64;
         }
 
         Self::reduce(&wide)
@@ -251,8 +263,9 @@ impl FieldElement {
             carry >>= 64;
         }
 
-        // If result[4] != 0, reduce again
-        while result[4] != 0 {
+        // Fold result[4] back in twice: the first leaves at most 1 there with a
+        // small low part, the second then cannot carry.
+        for _ in 0..2 {
             let overflow = result[4] as u128;
             result[4] = 0;
 
@@ -276,16 +289,14 @@ impl FieldElement {
             result[4] = carry as u64;
         }
 
-        let mut fe = Self {
+        debug_assert_eq!(result[4], 0);
+
+        // Now below 2^256 < 2p: at most one subtraction.
+        let fe = Self {
             limbs: [result[0], result[1], result[2], result[3]],
         };
-
-        // Final reduction if >= p (may need multiple subtractions)
-        while fe.gte_p() {
-            fe = fe.sub_p();
-        }
-
-        fe
+        let (reduced, borrow) = fe.sub_p();
+        Self::select(mask(!borrow), &reduced, &fe)
     }
 
     /// Exponentiation: self^exp (mod p).
@@ -315,6 +326,34 @@ impl FieldElement {
         Some(self.pow(&P_MINUS_2))
     }
 
+    /// Invert every element with one `inv` (Montgomery's trick).
+    /// Zero elements map to None and do not poison the rest.
+    pub fn batch_inv(elems: &[Self]) -> Vec<Option<Self>> {
+        // prefix[i] = product of the non-zero elements in elems[..i]
+        let mut prefix = Vec::with_capacity(elems.len());
+        let mut acc = Self::ONE;
+        for e in elems {
+            prefix.push(acc);
+            if !e.is_zero() {
+                acc = acc.mul(e);
+            }
+        }
+
+        let mut inv = match acc.inv() {
+            Some(inv) => inv,
+            None => return vec![None; elems.len()],
+        };
+        let mut out = vec![None; elems.len()];
+        for (i, e) in elems.iter().enumerate().rev() {
+            if e.is_zero() {
+                continue;
+            }
+            out[i] = Some(inv.mul(&prefix[i]));
+            inv = inv.mul(e);
+        }
+        out
+    }
+
     /// Check if self is odd (least significant bit is 1).
     pub fn is_odd(&self) -> bool {
         self.limbs[0] & 1 == 1
@@ -400,6 +439,20 @@ mod tests {
         assert_eq!(a.mul(&a.inv().unwrap()), FieldElement::ONE);
     }
 
+    #[test]
+    fn test_batch_inv_matches_inv() {
+        let mut elems: Vec<FieldElement> = (0u64..9)
+            .map(|i| FieldElement::from_limbs([i * 0x9E37_79B9, i, u64::MAX - i, i << 40]))
+            .collect();
+        elems[0] = FieldElement::ZERO;
+        let batch = FieldElement::batch_inv(&elems);
+        assert_eq!(batch[0], None);
+        for (e, inv) in elems.iter().zip(&batch).skip(1) {
+            assert_eq!(*inv, e.inv());
+        }
+        assert!(FieldElement::batch_inv(&[]).is_empty());
+    }
+
     #[test]
     fn test_neg() {
         let a = fe_from_hex("0000000000000000000000000000000000000000000000000000000000000001");
@@ -0,0 +1,291 @@
+//! Variable-base k·P with the GLV endomorphism and width-5 wNAF.
+//!
+//! secp256k1 has φ(x, y) = (β·x, y) = λ·P. Splitting k = k1 + k2·λ (mod n) with
+//! |k1|, |k2| ≈ 2^128 halves the doublings, and wNAF recoding leaves about one
+//! addition per 6 bits of each half.
+//!
+//! Constants and the split follow libsecp256k1 (`secp256k1_scalar_split_lambda`).
+//!
+//! Only variable-base `Point::mul` uses this. Every multiply on the derivation
+//! path (BIP32 keys, search candidates, hit checks) is k·G and goes through
+//! the comb table, which needs no doublings and stays faster than GLV for a
+//! fixed base. `--devices cpu --bench` prints all three side by side.
+
+#![forbid(unsafe_code)]
+
+use super::field::FieldElement;
+use super::point::Point;
+use super::scalar::Scalar;
+
+/// λ: cube root of unity mod n, with λ·P = φ(P).
+pub(crate) const LAMBDA: Scalar = Scalar::from_limbs([
+    0xDF02967C1B23BD72,
+    0x122E22EA20816678,
+    0xA5261C028812645A,
+    0x5363AD4CC05C30E0,
+]);
+
+/// β: cube root of unity mod p, the x multiplier of φ.
+pub(crate) const BETA: FieldElement = FieldElement::from_limbs([
+    0xC1396C28719501EE,
+    0x9CF0497512F58995,
+    0x6E64479EAC3434E9,
+    0x7AE96A2B657C0710,
+]);
+
+/// −b1 and −b2 of the reduced lattice basis.
+const MINUS_B1: Scalar = Scalar::from_limbs([0x6F547FA90ABFE4C3, 0xE4437ED6010E8828, 0, 0]);
+const MINUS_B2: Scalar = Scalar::from_limbs([
+    0xD765CDA83DB1562C,
+    0x8A280AC50774346D,
+    0xFFFFFFFFFFFFFFFE,
+    0xFFFFFFFFFFFFFFFF,
+]);
+
+/// round(2^384 · b2 / n) and round(2^384 · −b1 / n).
+const G1: [u64; 4] = [
+    0xE893209A45DBB031,
+    0x3DAA8A1471E8CA7F,
+    0xE86C90E49284EB15,
+    0x3086D221A7D46BCD,
+];
+const G2: [u64; 4] = [
+    0x1571B4AE8AC47F71,
+    0x221208AC9DF506C6,
+    0x6F547FA90ABFE4C4,
+    0xE4437ED6010E8828,
+];
+
+const WINDOW: u32 = 5;
+/// Odd multiples P, 3P, …, 15P.
+const TABLE_LEN: usize = 1 << (WINDOW - 2);
+/// wNAF of a value below 2^256 has at most 257 digits.
+const MAX_DIGITS: usize = 257;
+
+/// k·P.
+pub fn mul(p: &Point, k: &Scalar) -> Point {
+    if k.is_zero() || p.is_infinity() {
+        return Point::INFINITY;
+    }
+
+    let (k1, k2) = split_lambda(k);
+    let (k1, neg1) = shortest(k1);
+    let (k2, neg2) = shortest(k2);
+    let (d1, len1) = wnaf(k1.limbs());
+    let (d2, len2) = wnaf(k2.limbs());
+
+    let mut t1 = [*p; TABLE_LEN];
+    let twice = p.double();
+    for i in 1..TABLE_LEN {
+        t1[i] = t1[i - 1].add(&twice);
+    }
+    let mut t2 = t1.map(|q| q.endomorphism());
+    if neg1 {
+        t1 = t1.map(|q| q.neg());
+    }
+    if neg2 {
+        t2 = t2.map(|q| q.neg());
+    }
+
+    let mut acc = Point::INFINITY;
+    for i in (0..len1.max(len2)).rev() {
+        acc = acc.double();
+        acc = add_digit(acc, &t1, d1[i]);
+        acc = add_digit(acc, &t2, d2[i]);
+    }
+    acc
+}
+
+fn add_digit(acc: Point, table: &[Point; TABLE_LEN], digit: i8) -> Point {
+    match digit {
+        0 => acc,
+        d if d > 0 => acc.add(&table[(d / 2) as usize]),
+        d => acc.add(&table[(-d / 2) as usize].neg()),
+    }
+}
+
+/// k1, k2 with k1 + k2·λ ≡ k (mod n). Each is within ~2^128 of 0 or of n.
+pub(crate) fn split_lambda(k: &Scalar) -> (Scalar, Scalar) {
+    let c1 = mul_shift_384(k, &G1).mul(&MINUS_B1);
+    let c2 = mul_shift_384(k, &G2).mul(&MINUS_B2);
+    let k2 = c1.add(&c2);
+    let k1 = k.sub(&k2.mul(&LAMBDA));
+    (k1, k2)
+}
+
+/// round(k · g / 2^384).
+fn mul_shift_384(k: &Scalar, g: &[u64; 4]) -> Scalar {
+    let wide = k.mul_wide(&Scalar::from_limbs(*g));
+    let round = wide[5] >> 63;
+    let (lo, carry) = wide[6].overflowing_add(round);
+    let hi = wide[7] + carry as u64;
+    Scalar::from_limbs([lo, hi, 0, 0])
+}
+
+/// `s` or `n − s`, whichever is smaller, and whether it was negated.
+fn shortest(s: Scalar) -> (Scalar, bool) {
+    let neg = s.neg();
+    if limbs_lt(&neg.limbs(), &s.limbs()) {
+        (neg, true)
+    } else {
+        (s, false)
+    }
+}
+
+fn limbs_lt(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// Width-5 NAF digits, least significant first: each non-zero digit is odd,
+/// in −15..=15, and followed by at least four zeros.
+fn wnaf(mut k: [u64; 4]) -> ([i8; MAX_DIGITS], usize) {
+    let mut digits = [0i8; MAX_DIGITS];
+    let mut len = 0;
+    while k != [0; 4] {
+        if k[0] & 1 == 1 {
+            let mut d = (k[0] & ((1 << WINDOW) - 1)) as i64;
+            if d >= 1 << (WINDOW - 1) {
+                d -= 1 << WINDOW;
+            }
+            digits[len] = d as i8;
+            // k − d; k stays below n + 16 < 2^256, so the carry never leaves limb 3.
+            k = if d > 0 {
+                sub_small(k, d as u64)
+            } else {
+                add_small(k, (-d) as u64)
+            };
+        }
+        k = [
+            k[0] >> 1 | k[1] << 63,
+            k[1] >> 1 | k[2] << 63,
+            k[2] >> 1 | k[3] << 63,
+            k[3] >> 1,
+        ];
+        len += 1;
+    }
+    (digits, len)
+}
+
+fn add_small(mut k: [u64; 4], v: u64) -> [u64; 4] {
+    let mut carry = v;
+    for limb in k.iter_mut() {
+        let (r, c) = limb.overflowing_add(carry);
+        *limb = r;
+        carry = c as u64;
+    }
+    k
+}
+
+fn sub_small(mut k: [u64; 4], v: u64) -> [u64; 4] {
+    let mut borrow = v;
+    for limb in k.iter_mut() {
+        let (r, b) = limb.overflowing_sub(borrow);
+        *limb = r;
+        borrow = b as u64;
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256;
+
+    /// Deterministic pseudo-random scalars: SHA-256 of a label and counter.
+    fn scalars(label: &str, count: u32) -> Vec<Scalar> {
+        (0..count)
+            .filter_map(|i| Scalar::from_bytes(&sha256::digest(format!("{label}{i}").as_bytes())))
+            .collect()
+    }
+
+    #[test]
+    fn lambda_and_beta_are_cube_roots() {
+        assert_eq!(LAMBDA.mul(&LAMBDA).mul(&LAMBDA), Scalar::ONE);
+        assert_eq!(BETA.mul(&BETA).mul(&BETA), FieldElement::ONE);
+        let g = Point::generator();
+        assert_eq!(g.endomorphism(), g.mul_double_and_add(&LAMBDA));
+    }
+
+    #[test]
+    fn split_recombines_with_short_halves() {
+        let n_minus_1 = Scalar::ONE.neg();
+        let mut all = vec![Scalar::ONE, n_minus_1, LAMBDA];
+        all.extend(scalars("split", 64));
+        for k in all {
+            let (k1, k2) = split_lambda(&k);
+            assert_eq!(k1.add(&k2.mul(&LAMBDA)), k);
+            for half in [k1, k2] {
+                let (short, _) = shortest(half);
+                assert_eq!(short.limbs()[2..], [0, 0], "half exceeds 128 bits");
+            }
+        }
+    }
+
+    #[test]
+    fn wnaf_digits_rebuild_value() {
+        let k = [0x1234_5678_9ABC_DEF1, u64::MAX, 0x8000_0000_0000_0001, 7];
+        let (digits, len) = wnaf(k);
+        // k < n, so rebuilding it mod n is exact.
+        let two = Scalar::from_limbs([2, 0, 0, 0]);
+        let mut value = Scalar::ZERO;
+        for &d in digits[..len].iter().rev() {
+            value = value.mul(&two);
+            let mag = Scalar::from_limbs([d.unsigned_abs() as u64, 0, 0, 0]);
+            value = if d < 0 {
+                value.sub(&mag)
+            } else {
+                value.add(&mag)
+            };
+        }
+        assert_eq!(value, Scalar::from_limbs(k));
+        for (i, &d) in digits[..len].iter().enumerate() {
+            if d != 0 {
+                assert!(d % 2 != 0 && (-15..=15).contains(&d));
+                assert!(digits[i + 1..len.min(i + 5)].iter().all(|&z| z == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn glv_matches_k256() {
+        use k256::elliptic_curve::ff::PrimeField;
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+        use k256::{FieldBytes, ProjectivePoint, Scalar as K256Scalar};
+
+        fn k256_scalar(s: &Scalar) -> K256Scalar {
+            Option::<K256Scalar>::from(K256Scalar::from_repr(FieldBytes::from(s.to_bytes())))
+                .unwrap()
+        }
+
+        for (base, k) in scalars("base", 32).into_iter().zip(scalars("k", 32)) {
+            let p = Point::mul_generator(&base);
+            let (x, y) = mul(&p, &k).to_affine().unwrap();
+            let mut ours = [0u8; 33];
+            ours[0] = if y.is_odd() { 0x03 } else { 0x02 };
+            ours[1..].copy_from_slice(&x.to_bytes());
+
+            let theirs = (ProjectivePoint::GENERATOR * k256_scalar(&base) * k256_scalar(&k))
+                .to_affine()
+                .to_encoded_point(true);
+            assert_eq!(&ours[..], theirs.as_bytes());
+        }
+    }
+
+    #[test]
+    fn glv_edge_scalars() {
+        let g = Point::generator();
+        let n_minus_1 = Scalar::ONE.neg();
+        assert!(mul(&g, &Scalar::ZERO).is_infinity());
+        assert!(mul(&Point::INFINITY, &Scalar::ONE).is_infinity());
+        assert_eq!(mul(&g, &Scalar::ONE), g);
+        assert_eq!(mul(&g, &n_minus_1), g.neg());
+        assert_eq!(mul(&g, &LAMBDA), g.endomorphism());
+        let two = Scalar::from_limbs([2, 0, 0, 0]);
+        assert_eq!(mul(&g, &two), g.double());
+    }
+}
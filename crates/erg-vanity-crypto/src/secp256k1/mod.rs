@@ -2,7 +2,10 @@
 
 #![forbid(unsafe_code)]
 
+pub mod comb;
+pub mod ct;
 pub mod field;
+pub mod glv;
 pub mod point;
 pub mod pubkey;
 pub mod scalar;
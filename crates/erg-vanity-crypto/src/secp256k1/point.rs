@@ -62,6 +62,11 @@ impl Point {
         Self::from_affine(FieldElement::from_limbs(GX), FieldElement::from_limbs(GY))
     }
 
+    /// Jacobian (X, Y, Z).
+    pub(crate) fn coords(&self) -> (FieldElement, FieldElement, FieldElement) {
+        (self.x, self.y, self.z)
+    }
+
     /// Check if this is the point at infinity.
     pub fn is_infinity(&self) -> bool {
         self.z.is_zero()
@@ -84,6 +89,22 @@ impl Point {
         Some((x, y))
     }
 
+    /// Convert many points to affine with a single field inversion.
+    /// Same results as calling `to_affine` on each point.
+    pub fn batch_to_affine(points: &[Self]) -> Vec<Option<(FieldElement, FieldElement)>> {
+        let zs: Vec<FieldElement> = points.iter().map(|p| p.z).collect();
+        points
+            .iter()
+            .zip(FieldElement::batch_inv(&zs))
+            .map(|(p, z_inv)| {
+                let z_inv = z_inv?;
+                let z_inv2 = z_inv.square();
+                let z_inv3 = z_inv2.mul(&z_inv);
+                Some((p.x.mul(&z_inv2), p.y.mul(&z_inv3)))
+            })
+            .collect()
+    }
+
     /// Point doubling: 2P.
     /// Uses standard Jacobian doubling formulas for a=0 curves.
     pub fn double(&self) -> Self {
@@ -181,9 +202,60 @@ impl Point {
         }
     }
 
+    /// Mixed addition P + (x, y) with an affine second operand (Z2 = 1).
+    ///
+    /// Saves the Z2 multiplications of `add`; used for table lookups.
+    pub fn add_affine(&self, x: &FieldElement, y: &FieldElement) -> Self {
+        if self.is_infinity() {
+            return Self::from_affine(*x, *y);
+        }
+
+        let z1_2 = self.z.square();
+        let z1_3 = z1_2.mul(&self.z);
+
+        // U1 = X1, U2 = x*Z1²; S1 = Y1, S2 = y*Z1³
+        let u2 = x.mul(&z1_2);
+        let s2 = y.mul(&z1_3);
+
+        // H = U2 - U1, R = S2 - S1
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y);
+
+        if h.is_zero() {
+            if r.is_zero() {
+                return self.double();
+            }
+            return Self::INFINITY;
+        }
+
+        let h2 = h.square();
+        let h3 = h2.mul(&h);
+
+        // X3 = R² - H³ - 2*U1*H²
+        let u1_h2 = self.x.mul(&h2);
+        let x3 = r.square().sub(&h3).sub(&u1_h2).sub(&u1_h2);
+
+        // Y3 = R*(U1*H² - X3) - S1*H³
+        let y3 = r.mul(&u1_h2.sub(&x3)).sub(&self.y.mul(&h3));
+
+        // Z3 = H*Z1
+        let z3 = h.mul(&self.z);
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
     /// Scalar multiplication: k * P.
-    /// Uses double-and-add algorithm.
+    /// Uses the GLV endomorphism with width-5 wNAF (see `glv`).
     pub fn mul(&self, k: &Scalar) -> Self {
+        super::glv::mul(self, k)
+    }
+
+    /// Scalar multiplication by plain double-and-add. Reference for tests.
+    pub fn mul_double_and_add(&self, k: &Scalar) -> Self {
         if k.is_zero() || self.is_infinity() {
             return Self::INFINITY;
         }
@@ -206,9 +278,29 @@ impl Point {
         result
     }
 
+    /// Point negation: -P = (X, -Y, Z).
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x,
+            y: self.y.neg(),
+            z: self.z,
+        }
+    }
+
+    /// φ(P) = (β·x, y) = λ·P.
+    pub(crate) fn endomorphism(&self) -> Self {
+        Self {
+            x: self.x.mul(&super::glv::BETA),
+            y: self.y,
+            z: self.z,
+        }
+    }
+
     /// Multiply generator G by scalar k: k * G.
+    ///
+    /// Uses the 8-bit comb table (32 mixed additions, no doublings).
     pub fn mul_generator(k: &Scalar) -> Self {
-        Self::generator().mul(k)
+        super::comb::mul_generator(k)
     }
 }
 
@@ -316,6 +408,34 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_add_affine_matches_add() {
+        let g = Point::generator();
+        let (gx, gy) = g.to_affine().unwrap();
+        let p = g.double().double().add(&g);
+        assert_eq!(p.add_affine(&gx, &gy), p.add(&g));
+        // Same point: falls through to doubling
+        assert_eq!(g.add_affine(&gx, &gy), g.double());
+        // Inverse: infinity
+        assert!(g.add_affine(&gx, &gy.neg()).is_infinity());
+        assert_eq!(Point::INFINITY.add_affine(&gx, &gy), g);
+    }
+
+    #[test]
+    fn test_batch_to_affine_matches_to_affine() {
+        let g = Point::generator();
+        let mut points = vec![g, g.double(), Point::INFINITY];
+        for i in 1u64..6 {
+            points.push(g.mul(&Scalar::from_limbs([i * 1_000_003, i, 0, 0])));
+        }
+        let batch = Point::batch_to_affine(&points);
+        assert_eq!(batch.len(), points.len());
+        assert_eq!(batch[2], None);
+        for (p, affine) in points.iter().zip(&batch) {
+            assert_eq!(*affine, p.to_affine());
+        }
+    }
+
     #[test]
     fn test_against_k256() {
         use k256::elliptic_curve::ff::PrimeField;
@@ -332,6 +452,11 @@ mod tests {
             "0000000000000000000000000000000000000000000000000000000000000003",
             "deadbeefcafebabedeadbeefcafebabedeadbeefcafebabedeadbeefcafebabe",
             "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            // n - 1: every window non-zero, result is -G
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+            // Zero bytes skip their windows
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff",
         ];
 
         for hex_k in &test_scalars {
@@ -6,6 +6,9 @@
 
 #![forbid(unsafe_code)]
 
+#[cfg(any(not(feature = "audited"), test))]
+use super::field::FieldElement;
+#[cfg(any(not(feature = "audited"), test))]
 use super::point::Point;
 use super::scalar::Scalar;
 
@@ -18,14 +21,77 @@ pub struct PublicKey {
 impl PublicKey {
     /// Generate public key from private key (scalar).
     /// Returns None if private key is zero.
+    #[cfg(not(feature = "audited"))]
     pub fn from_private_key(private_key: &Scalar) -> Option<Self> {
+        Self::native_from_private_key(private_key)
+    }
+
+    /// Audited build: RustCrypto `k256`.
+    #[cfg(feature = "audited")]
+    pub fn from_private_key(private_key: &Scalar) -> Option<Self> {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+        let secret = k256::SecretKey::from_bytes(&private_key.to_bytes().into()).ok()?;
+        let encoded = secret.public_key().to_encoded_point(true);
+        Some(Self {
+            bytes: encoded.as_bytes().try_into().ok()?,
+        })
+    }
+
+    /// Like `from_private_key`, but constant time in the key (`ct::mul_generator`).
+    /// Use for keys that are kept; the search uses the faster variable-time path.
+    #[cfg(not(feature = "audited"))]
+    pub fn from_private_key_ct(private_key: &Scalar) -> Option<Self> {
+        Self::native_from_private_key_ct(private_key)
+    }
+
+    /// Audited build: `k256` is constant time already.
+    #[cfg(feature = "audited")]
+    pub fn from_private_key_ct(private_key: &Scalar) -> Option<Self> {
+        Self::from_private_key(private_key)
+    }
+
+    /// Public keys for many private keys, sharing one field inversion.
+    /// Entries are None where `from_private_key` would return None.
+    #[cfg(not(feature = "audited"))]
+    pub fn batch_from_private_keys(private_keys: &[Scalar]) -> Vec<Option<Self>> {
+        Self::native_batch_from_private_keys(private_keys)
+    }
+
+    /// Audited build: one `k256` multiplication per key.
+    #[cfg(feature = "audited")]
+    pub fn batch_from_private_keys(private_keys: &[Scalar]) -> Vec<Option<Self>> {
+        private_keys.iter().map(Self::from_private_key).collect()
+    }
+
+    /// From-scratch `from_private_key` (comb k·G). The audited build keeps it
+    /// and the two below for the tests against `k256`.
+    #[cfg(any(not(feature = "audited"), test))]
+    fn native_from_private_key(private_key: &Scalar) -> Option<Self> {
         if private_key.is_zero() {
             return None;
         }
 
-        let point = Point::mul_generator(private_key);
-        let (x, y) = point.to_affine()?;
+        let (x, y) = Point::mul_generator(private_key).to_affine()?;
+        Some(Self::from_affine(&x, &y))
+    }
+
+    #[cfg(any(not(feature = "audited"), test))]
+    fn native_from_private_key_ct(private_key: &Scalar) -> Option<Self> {
+        let (x, y) = super::ct::mul_generator(private_key).to_affine()?;
+        Some(Self::from_affine(&x, &y))
+    }
+
+    #[cfg(any(not(feature = "audited"), test))]
+    fn native_batch_from_private_keys(private_keys: &[Scalar]) -> Vec<Option<Self>> {
+        let points: Vec<Point> = private_keys.iter().map(Point::mul_generator).collect();
+        Point::batch_to_affine(&points)
+            .into_iter()
+            .map(|xy| xy.map(|(x, y)| Self::from_affine(&x, &y)))
+            .collect()
+    }
 
+    #[cfg(any(not(feature = "audited"), test))]
+    fn from_affine(x: &FieldElement, y: &FieldElement) -> Self {
         let mut bytes = [0u8; 33];
 
         // Prefix: 0x02 for even y, 0x03 for odd y
@@ -34,7 +100,7 @@ impl PublicKey {
         // X-coordinate (32 bytes big-endian)
         bytes[1..33].copy_from_slice(&x.to_bytes());
 
-        Some(Self { bytes })
+        Self { bytes }
     }
 
     /// Get the raw 33-byte compressed public key.
@@ -81,6 +147,23 @@ mod tests {
         assert!(PublicKey::from_private_key(&zero).is_none());
     }
 
+    #[test]
+    fn test_batch_matches_single() {
+        let keys = [
+            Scalar::ONE,
+            Scalar::ZERO,
+            scalar_from_hex("deadbeefcafebabedeadbeefcafebabedeadbeefcafebabedeadbeefcafebabe"),
+            scalar_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364140"),
+        ];
+        let batch = PublicKey::batch_from_private_keys(&keys);
+        let native = PublicKey::native_batch_from_private_keys(&keys);
+        for ((k, pk), native) in keys.iter().zip(&batch).zip(&native) {
+            assert_eq!(*pk, PublicKey::from_private_key(k));
+            assert_eq!(*native, PublicKey::native_from_private_key(k));
+        }
+        assert!(batch[1].is_none() && native[1].is_none());
+    }
+
     #[test]
     fn test_against_k256() {
         use k256::elliptic_curve::ff::PrimeField;
@@ -102,7 +185,7 @@ mod tests {
 
         for hex_k in &test_scalars {
             let k = scalar_from_hex(hex_k);
-            let our_pubkey = PublicKey::from_private_key(&k).unwrap();
+            let our_pubkey = PublicKey::native_from_private_key(&k).unwrap();
 
             let k256_k = k256_scalar_from_bytes(k.to_bytes());
             let k256_point = ProjectivePoint::GENERATOR * k256_k;
@@ -114,6 +197,8 @@ mod tests {
                 k256_compressed.as_bytes(),
                 "pubkey mismatch for k = {hex_k}"
             );
+            assert_eq!(PublicKey::native_from_private_key_ct(&k), Some(our_pubkey));
+            assert_eq!(PublicKey::from_private_key(&k), Some(our_pubkey));
         }
     }
 }
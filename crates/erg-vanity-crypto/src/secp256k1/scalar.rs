@@ -2,9 +2,14 @@
 //!
 //! n = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
 //! Used for private key operations. BIP32 derivation needs add mod n.
+//!
+//! Arithmetic is constant time (masked reductions, no branches on values).
 
 #![forbid(unsafe_code)]
 
+use super::field::mask;
+use std::hint::black_box;
+
 /// The secp256k1 curve order n.
 /// Represented as 4 x 64-bit limbs in little-endian order.
 const N: [u64; 4] = [
@@ -91,7 +96,20 @@ impl Scalar {
 
     /// Check if zero.
     pub fn is_zero(&self) -> bool {
-        self.limbs[0] == 0 && self.limbs[1] == 0 && self.limbs[2] == 0 && self.limbs[3] == 0
+        (self.limbs[0] | self.limbs[1] | self.limbs[2] | self.limbs[3]) == 0
+    }
+
+    /// `a` where `mask` is all-ones, `b` where it is 0.
+    fn select(mask: u64, a: &Self, b: &Self) -> Self {
+        let mask = black_box(mask);
+        Self {
+            limbs: std::array::from_fn(|i| (a.limbs[i] & mask) | (b.limbs[i] & !mask)),
+        }
+    }
+
+    /// Bits `[4 * i, 4 * i + 4)`, for fixed-window multiplication.
+    pub(crate) fn nibble(&self, i: usize) -> u64 {
+        (self.limbs[i / 16] >> ((i % 16) * 4)) & 0xF
     }
 
     /// Addition: self + other (mod n).
@@ -101,26 +119,26 @@ impl Scalar {
         let (r2, c2) = self.limbs[2].carrying_add(other.limbs[2], c1);
         let (r3, c3) = self.limbs[3].carrying_add(other.limbs[3], c2);
 
-        let mut result = Self {
+        let sum = Self {
             limbs: [r0, r1, r2, r3],
         };
-
-        if c3 || result.gte_n() {
-            result = result.sub_n();
-        }
-
-        result
+        // Keep sum − n if the sum carried out or did not borrow against n.
+        let (reduced, borrow) = sum.sub_n();
+        Self::select(mask(c3 | !borrow), &reduced, &sum)
     }
 
-    /// Subtract n from self.
-    fn sub_n(&self) -> Self {
+    /// Subtract n from self; also returns the borrow (self < n).
+    fn sub_n(&self) -> (Self, bool) {
         let (r0, b0) = self.limbs[0].overflowing_sub(N[0]);
         let (r1, b1) = self.limbs[1].borrowing_sub(N[1], b0);
         let (r2, b2) = self.limbs[2].borrowing_sub(N[2], b1);
-        let (r3, _) = self.limbs[3].borrowing_sub(N[3], b2);
-        Self {
-            limbs: [r0, r1, r2, r3],
-        }
+        let (r3, b3) = self.limbs[3].borrowing_sub(N[3], b2);
+        (
+            Self {
+                limbs: [r0, r1, r2, r3],
+            },
+            b3,
+        )
     }
 
     /// Subtraction: self - other (mod n).
@@ -130,23 +148,19 @@ impl Scalar {
         let (r2, b2) = self.limbs[2].borrowing_sub(other.limbs[2], b1);
         let (r3, b3) = self.limbs[3].borrowing_sub(other.limbs[3], b2);
 
-        let mut result = Self {
+        Self {
             limbs: [r0, r1, r2, r3],
-        };
-
-        if b3 {
-            result = result.add_n();
         }
-
-        result
+        .add_n_masked(mask(b3))
     }
 
-    /// Add n to self.
-    fn add_n(&self) -> Self {
-        let (r0, c0) = self.limbs[0].overflowing_add(N[0]);
-        let (r1, c1) = self.limbs[1].carrying_add(N[1], c0);
-        let (r2, c2) = self.limbs[2].carrying_add(N[2], c1);
-        let (r3, _) = self.limbs[3].carrying_add(N[3], c2);
+    /// Add `n & mask` to self.
+    fn add_n_masked(&self, mask: u64) -> Self {
+        let mask = black_box(mask);
+        let (r0, c0) = self.limbs[0].overflowing_add(N[0] & mask);
+        let (r1, c1) = self.limbs[1].carrying_add(N[1] & mask, c0);
+        let (r2, c2) = self.limbs[2].carrying_add(N[2] & mask, c1);
+        let (r3, _) = self.limbs[3].carrying_add(N[3] & mask, c2);
         Self {
             limbs: [r0, r1, r2, r3],
         }
@@ -154,22 +168,24 @@ impl Scalar {
 
     /// Negation: -self (mod n).
     pub fn neg(&self) -> Self {
-        if self.is_zero() {
-            *self
-        } else {
-            let (r0, b0) = N[0].overflowing_sub(self.limbs[0]);
-            let (r1, b1) = N[1].borrowing_sub(self.limbs[1], b0);
-            let (r2, b2) = N[2].borrowing_sub(self.limbs[2], b1);
-            let (r3, _) = N[3].borrowing_sub(self.limbs[3], b2);
-            Self {
-                limbs: [r0, r1, r2, r3],
-            }
-        }
+        let (r0, b0) = N[0].overflowing_sub(self.limbs[0]);
+        let (r1, b1) = N[1].borrowing_sub(self.limbs[1], b0);
+        let (r2, b2) = N[2].borrowing_sub(self.limbs[2], b1);
+        let (r3, _) = N[3].borrowing_sub(self.limbs[3], b2);
+        let negated = Self {
+            limbs: [r0, r1, r2, r3],
+        };
+        // n − 0 = n, which must come back as 0.
+        Self::select(mask(self.is_zero()), &Self::ZERO, &negated)
     }
 
     /// Multiplication: self * other (mod n).
     pub fn mul(&self, other: &Self) -> Self {
-        // 512-bit product as 8x64 limbs (little endian)
+        Self::reduce_wide(&self.mul_wide(other))
+    }
+
+    /// Full 512-bit product as 8x64 limbs (little endian), not reduced.
+    pub(crate) fn mul_wide(&self, other: &Self) -> [u64; 8] {
         let mut wide = [0u64; 8];
 
         for i in 0..4 {
@@ -186,17 +202,16 @@ impl Scalar {
                 carry = (acc >> 64) + (hi as u128);
             }
 
-            let mut k = i + 4;
-            while carry != 0 {
-                debug_assert!(k < 8, "512-bit multiply overflow");
-                let acc = (wide[k] as u128) + carry;
-                wide[k] = acc as u64;
-                carry = acc >> 64;
-                k += 1;
-            }
+            // Row i has only touched limbs i..i+3, and the final carry fits in 64 bits.
+            wide[i + 4] = carry as u64;
         }
 
-        Self::reduce_wide(&wide)
+        wide
+    }
+
+    /// Little-endian limbs.
+    pub(crate) fn limbs(&self) -> [u64; 4] {
+        self.limbs
     }
 
     /// Reduce 512-bit number mod n using bit-by-bit reduction.
@@ -212,9 +227,7 @@ impl Scalar {
                 rem = rem.add(&rem);
 
                 // rem = (rem + bit) mod n
-                if ((limb >> bit_idx) & 1) == 1 {
-                    rem = rem.add(&Self::ONE);
-                }
+                rem = rem.add(&Self::from_limbs([(limb >> bit_idx) & 1, 0, 0, 0]));
             }
         }
 
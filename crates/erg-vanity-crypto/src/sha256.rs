@@ -4,11 +4,13 @@
 #![forbid(unsafe_code)]
 
 /// Initial hash values (first 32 bits of fractional parts of square roots of first 8 primes).
+#[cfg(any(not(feature = "audited"), test))]
 const H: [u32; 8] = [
     0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
 /// Round constants (first 32 bits of fractional parts of cube roots of first 64 primes).
+#[cfg(any(not(feature = "audited"), test))]
 const K: [u32; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
@@ -21,7 +23,21 @@ const K: [u32; 64] = [
 ];
 
 /// Compute SHA-256 digest of input data.
+#[cfg(not(feature = "audited"))]
 pub fn digest(data: &[u8]) -> [u8; 32] {
+    native_digest(data)
+}
+
+/// Audited build: RustCrypto `sha2`.
+#[cfg(feature = "audited")]
+pub fn digest(data: &[u8]) -> [u8; 32] {
+    use sha2::Digest;
+    sha2::Sha256::digest(data).into()
+}
+
+/// From-scratch SHA-256. The audited build keeps it for the tests against `sha2`.
+#[cfg(any(not(feature = "audited"), test))]
+fn native_digest(data: &[u8]) -> [u8; 32] {
     let mut state = H;
     let padded = pad(data);
 
@@ -37,6 +53,7 @@ pub fn digest(data: &[u8]) -> [u8; 32] {
 }
 
 /// Pad message according to FIPS 180-4 Section 5.1.1.
+#[cfg(any(not(feature = "audited"), test))]
 fn pad(data: &[u8]) -> Vec<u8> {
     let bit_len = (data.len() as u64) * 8;
     let mut padded = data.to_vec();
@@ -56,6 +73,7 @@ fn pad(data: &[u8]) -> Vec<u8> {
 }
 
 /// Compress one 512-bit block into the state.
+#[cfg(any(not(feature = "audited"), test))]
 fn compress(state: &mut [u32; 8], block: &[u8]) {
     // Prepare message schedule W[0..63]
     let mut w = [0u32; 64];
@@ -165,7 +183,7 @@ mod tests {
         ];
 
         for data in test_cases {
-            let our_result = digest(data);
+            let our_result = native_digest(data);
             let ref_result: [u8; 32] = Sha256::digest(data).into();
             assert_eq!(
                 our_result,
@@ -4,7 +4,8 @@
 #![forbid(unsafe_code)]
 
 /// Initial hash values (first 64 bits of fractional parts of square roots of first 8 primes).
-const H: [u64; 8] = [
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) const H: [u64; 8] = [
     0x6a09e667f3bcc908,
     0xbb67ae8584caa73b,
     0x3c6ef372fe94f82b,
@@ -16,6 +17,7 @@ const H: [u64; 8] = [
 ];
 
 /// Round constants (first 64 bits of fractional parts of cube roots of first 80 primes).
+#[cfg(any(not(feature = "audited"), test))]
 const K: [u64; 80] = [
     0x428a2f98d728ae22,
     0x7137449123ef65cd,
@@ -100,14 +102,41 @@ const K: [u64; 80] = [
 ];
 
 /// Compute SHA-512 digest of input data.
+#[cfg(not(feature = "audited"))]
 pub fn digest(data: &[u8]) -> [u8; 64] {
-    let mut state = H;
-    let padded = pad(data);
+    native_digest(data)
+}
+
+/// Audited build: RustCrypto `sha2`.
+#[cfg(feature = "audited")]
+pub fn digest(data: &[u8]) -> [u8; 64] {
+    use sha2::Digest;
+    sha2::Sha512::digest(data).into()
+}
+
+/// From-scratch SHA-512. The audited build keeps it (and the midstate helpers
+/// below) for the from-scratch HMAC and PBKDF2 tests.
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn native_digest(data: &[u8]) -> [u8; 64] {
+    state_to_bytes(&digest_from(H, 0, data))
+}
+
+/// Finish a hash whose first `prefix_len` bytes (a multiple of 128) are already
+/// compressed into `state`.
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn digest_from(mut state: [u64; 8], prefix_len: usize, data: &[u8]) -> [u64; 8] {
+    debug_assert_eq!(prefix_len % 128, 0);
+    let padded = pad(data, prefix_len + data.len());
 
     for chunk in padded.chunks_exact(128) {
         compress(&mut state, chunk);
     }
+    state
+}
 
+/// Big-endian serialization of a state.
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn state_to_bytes(state: &[u64; 8]) -> [u8; 64] {
     let mut result = [0u8; 64];
     for (i, word) in state.iter().enumerate() {
         result[i * 8..(i + 1) * 8].copy_from_slice(&word.to_be_bytes());
@@ -115,9 +144,21 @@ pub fn digest(data: &[u8]) -> [u8; 64] {
     result
 }
 
-/// Pad message according to FIPS 180-4 Section 5.1.2.
-fn pad(data: &[u8]) -> Vec<u8> {
-    let bit_len = (data.len() as u128) * 8;
+/// Big-endian parse of a 64-byte digest.
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn bytes_to_state(bytes: &[u8; 64]) -> [u64; 8] {
+    let mut state = [0u64; 8];
+    for (word, chunk) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Pad message according to FIPS 180-4 Section 5.1.2. `total_len` counts any
+/// prefix already compressed.
+#[cfg(any(not(feature = "audited"), test))]
+fn pad(data: &[u8], total_len: usize) -> Vec<u8> {
+    let bit_len = (total_len as u128) * 8;
     let mut padded = data.to_vec();
 
     // Append bit '1' (0x80 byte)
@@ -135,25 +176,35 @@ fn pad(data: &[u8]) -> Vec<u8> {
 }
 
 /// Compress one 1024-bit block into the state.
-fn compress(state: &mut [u64; 8], block: &[u8]) {
-    // Prepare message schedule W[0..79]
-    let mut w = [0u64; 80];
-
-    // First 16 words from block
-    for (i, chunk) in block.chunks_exact(8).enumerate() {
-        w[i] = u64::from_be_bytes([
-            chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
-        ]);
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn compress(state: &mut [u64; 8], block: &[u8]) {
+    let mut words = [[0u64; 1]; 16];
+    for (w, chunk) in words.iter_mut().zip(block.chunks_exact(8)) {
+        w[0] = u64::from_be_bytes(chunk.try_into().unwrap());
     }
+    let mut lanes = state.map(|v| [v]);
+    compress_lanes(&mut lanes, &words);
+    *state = lanes.map(|[v]| v);
+}
+
+/// Compress one block for each of `L` independent hashes.
+///
+/// Lane-major layout: `state[j][l]` is word `j` of lane `l`. Every step is the same
+/// operation across lanes, so the inner loops vectorize without intrinsics.
+#[cfg(any(not(feature = "audited"), test))]
+pub(crate) fn compress_lanes<const L: usize>(state: &mut [[u64; L]; 8], block: &[[u64; L]; 16]) {
+    // Prepare message schedule W[0..79]
+    let mut w = [[0u64; L]; 80];
+    w[..16].copy_from_slice(block);
 
     // Extend to 80 words
     for i in 16..80 {
-        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
-        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
-        w[i] = w[i - 16]
-            .wrapping_add(s0)
-            .wrapping_add(w[i - 7])
-            .wrapping_add(s1);
+        let (w16, w15, w7, w2) = (w[i - 16], w[i - 15], w[i - 7], w[i - 2]);
+        for (l, out) in w[i].iter_mut().enumerate() {
+            let s0 = w15[l].rotate_right(1) ^ w15[l].rotate_right(8) ^ (w15[l] >> 7);
+            let s1 = w2[l].rotate_right(19) ^ w2[l].rotate_right(61) ^ (w2[l] >> 6);
+            *out = w16[l].wrapping_add(s0).wrapping_add(w7[l]).wrapping_add(s1);
+        }
     }
 
     // Initialize working variables
@@ -161,36 +212,41 @@ fn compress(state: &mut [u64; 8], block: &[u8]) {
 
     // 80 rounds
     for i in 0..80 {
-        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
-        let ch = (e & f) ^ (!e & g);
-        let temp1 = h
-            .wrapping_add(s1)
-            .wrapping_add(ch)
-            .wrapping_add(K[i])
-            .wrapping_add(w[i]);
-        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
-        let maj = (a & b) ^ (a & c) ^ (b & c);
-        let temp2 = s0.wrapping_add(maj);
+        let mut temp1 = [0u64; L];
+        let mut temp2 = [0u64; L];
+        for l in 0..L {
+            let s1 = e[l].rotate_right(14) ^ e[l].rotate_right(18) ^ e[l].rotate_right(41);
+            let ch = (e[l] & f[l]) ^ (!e[l] & g[l]);
+            temp1[l] = h[l]
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i][l]);
+            let s0 = a[l].rotate_right(28) ^ a[l].rotate_right(34) ^ a[l].rotate_right(39);
+            let maj = (a[l] & b[l]) ^ (a[l] & c[l]) ^ (b[l] & c[l]);
+            temp2[l] = s0.wrapping_add(maj);
+        }
 
         h = g;
         g = f;
         f = e;
-        e = d.wrapping_add(temp1);
+        for l in 0..L {
+            e[l] = d[l].wrapping_add(temp1[l]);
+        }
         d = c;
         c = b;
         b = a;
-        a = temp1.wrapping_add(temp2);
+        for l in 0..L {
+            a[l] = temp1[l].wrapping_add(temp2[l]);
+        }
     }
 
     // Add compressed chunk to current state
-    state[0] = state[0].wrapping_add(a);
-    state[1] = state[1].wrapping_add(b);
-    state[2] = state[2].wrapping_add(c);
-    state[3] = state[3].wrapping_add(d);
-    state[4] = state[4].wrapping_add(e);
-    state[5] = state[5].wrapping_add(f);
-    state[6] = state[6].wrapping_add(g);
-    state[7] = state[7].wrapping_add(h);
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        for l in 0..L {
+            s[l] = s[l].wrapping_add(v[l]);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -250,7 +306,7 @@ mod tests {
         ];
 
         for data in test_cases {
-            let our_result = digest(data);
+            let our_result = native_digest(data);
             let ref_result: [u8; 64] = Sha512::digest(data).into();
             assert_eq!(
                 our_result,
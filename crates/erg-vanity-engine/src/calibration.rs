@@ -0,0 +1,419 @@
+//! Measured seeds/s per device, kept across runs.
+//!
+//! One record per (device, driver, `num_indices`). Real searches and `--bench`
+//! overwrite the matching record; `guess_rate_for` prefers these over the name table.
+//! CPU records are keyed by [`cpu_key`]: the CPU model and thread count.
+//!
+//! File format is one tab-separated record per line:
+//! `device  driver  num_indices  seeds_per_sec  source  unix_time`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Overrides the calibration file location.
+pub const CALIBRATION_ENV: &str = "ERG_VANITY_CALIBRATION";
+
+/// Device name prefix for CPU records, followed by the CPU model.
+pub const CPU_DEVICE: &str = "cpu";
+
+/// Runs shorter than this (after the first batch) are not recorded.
+pub const MIN_CALIBRATION_SECS: f64 = 3.0;
+
+/// Where a measurement came from. Live searches win over isolated kernel benches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationSource {
+    Search,
+    Bench,
+}
+
+impl CalibrationSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            CalibrationSource::Search => "search",
+            CalibrationSource::Bench => "bench",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "search" => Some(CalibrationSource::Search),
+            "bench" => Some(CalibrationSource::Bench),
+            _ => None,
+        }
+    }
+}
+
+/// One measured rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    pub device: String,
+    pub driver: String,
+    pub num_indices: u32,
+    pub seeds_per_sec: f64,
+    pub source: CalibrationSource,
+    /// Unix seconds.
+    pub recorded_at: u64,
+}
+
+impl Calibration {
+    pub fn new(
+        device: &str,
+        driver: &str,
+        num_indices: u32,
+        seeds_per_sec: f64,
+        source: CalibrationSource,
+    ) -> Self {
+        Self {
+            device: clean_field(device),
+            driver: clean_field(driver),
+            num_indices: num_indices.max(1),
+            seeds_per_sec,
+            source,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    fn same_key(&self, other: &Calibration) -> bool {
+        self.device == other.device
+            && self.driver == other.driver
+            && self.num_indices == other.num_indices
+            && self.source == other.source
+    }
+}
+
+/// All stored measurements.
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationDb {
+    entries: Vec<Calibration>,
+}
+
+impl CalibrationDb {
+    /// `$ERG_VANITY_CALIBRATION`, else `calibration.tsv` in the per-user config dir.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(p) = std::env::var(CALIBRATION_ENV) {
+            if !p.is_empty() {
+                return Some(PathBuf::from(p));
+            }
+        }
+        let dir = if let Ok(appdata) = std::env::var("APPDATA") {
+            PathBuf::from(appdata)
+        } else if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+            PathBuf::from(xdg)
+        } else {
+            PathBuf::from(std::env::var("HOME").ok()?).join(".config")
+        };
+        Some(dir.join("erg-vanity").join("calibration.tsv"))
+    }
+
+    /// Load the default file. Missing or unreadable files give an empty database.
+    pub fn load_default() -> Self {
+        Self::default_path()
+            .and_then(|p| Self::load(&p).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load from `path`. A missing file is an empty database.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("cannot read {}: {e}", path.display())),
+        }
+    }
+
+    /// Parse file contents. Malformed lines are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .filter_map(parse_line)
+            .collect();
+        Self { entries }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out =
+            String::from("# device\tdriver\tnum_indices\tseeds_per_sec\tsource\tunix_time\n");
+        for e in &self.entries {
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{:.0}\t{}\t{}\n",
+                e.device,
+                e.driver,
+                e.num_indices,
+                e.seeds_per_sec,
+                e.source.as_str(),
+                e.recorded_at
+            ));
+        }
+        out
+    }
+
+    /// Writes to a temporary file and renames it, so a crash or a concurrent
+    /// run leaves either the old file or the complete new one.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+            }
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        let tmp = PathBuf::from(tmp);
+        let write = || -> std::io::Result<()> {
+            let mut f = std::fs::File::create(&tmp)?;
+            f.write_all(self.to_text().as_bytes())?;
+            f.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            format!("cannot write {}: {e}", path.display())
+        })
+    }
+
+    pub fn entries(&self) -> &[Calibration] {
+        &self.entries
+    }
+
+    /// Insert or replace the record with the same key and source.
+    pub fn record(&mut self, entry: Calibration) {
+        if !entry.seeds_per_sec.is_finite() || entry.seeds_per_sec <= 0.0 {
+            return;
+        }
+        match self.entries.iter_mut().find(|e| e.same_key(&entry)) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Measured seeds/s for this device, driver and index count. A search record
+    /// beats a bench record for the same key.
+    pub fn lookup(&self, device: &str, driver: &str, num_indices: u32) -> Option<&Calibration> {
+        let device = clean_field(device);
+        let driver = clean_field(driver);
+        let num_indices = num_indices.max(1);
+        let mut matches = self
+            .entries
+            .iter()
+            .filter(|e| e.device == device && e.driver == driver && e.num_indices == num_indices);
+        let first = matches.next()?;
+        if first.source == CalibrationSource::Search {
+            return Some(first);
+        }
+        Some(
+            matches
+                .find(|e| e.source == CalibrationSource::Search)
+                .unwrap_or(first),
+        )
+    }
+}
+
+/// Device and driver fields for this machine's CPU with the global rayon pool:
+/// `cpu <model>` and the worker thread count. A rate saved on other hardware,
+/// e.g. through a shared home directory, then no longer matches.
+pub fn cpu_key() -> (String, String) {
+    static BRAND: OnceLock<String> = OnceLock::new();
+    let brand = BRAND.get_or_init(|| {
+        let brand = cpu_brand().map(|b| b.split_whitespace().collect::<Vec<_>>().join(" "));
+        brand
+            .filter(|b| !b.is_empty())
+            .unwrap_or_else(|| std::env::consts::ARCH.to_string())
+    });
+    (
+        format!("{CPU_DEVICE} {brand}"),
+        format!("{} threads", rayon::current_num_threads()),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_brand() -> Option<String> {
+    let info = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    // x86 has "model name"; many ARM kernels only have "Hardware" or "Model".
+    ["model name", "Hardware", "Model"].iter().find_map(|key| {
+        info.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            (k.trim() == *key).then(|| v.trim().to_string())
+        })
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_brand() -> Option<String> {
+    let out = std::process::Command::new("sysctl")
+        .args(["-n", "machdep.cpu.brand_string"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(windows)]
+fn cpu_brand() -> Option<String> {
+    std::env::var("PROCESSOR_IDENTIFIER").ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn cpu_brand() -> Option<String> {
+    None
+}
+
+/// Merge `entries` into the default calibration file.
+pub fn record_measurements(entries: Vec<Calibration>) -> Result<PathBuf, String> {
+    let path = CalibrationDb::default_path()
+        .ok_or_else(|| "no home directory for the calibration file".to_string())?;
+    let mut db = CalibrationDb::load(&path)?;
+    for e in entries {
+        db.record(e);
+    }
+    db.save(&path)?;
+    Ok(path)
+}
+
+fn parse_line(line: &str) -> Option<Calibration> {
+    let mut f = line.split('\t');
+    let device = f.next()?.to_string();
+    let driver = f.next()?.to_string();
+    let num_indices = f.next()?.parse().ok()?;
+    let seeds_per_sec: f64 = f.next()?.parse().ok()?;
+    let source = CalibrationSource::parse(f.next()?)?;
+    let recorded_at = f.next()?.parse().ok()?;
+    if device.is_empty() || !seeds_per_sec.is_finite() || seeds_per_sec <= 0.0 {
+        return None;
+    }
+    Some(Calibration {
+        device,
+        driver,
+        num_indices,
+        seeds_per_sec,
+        source,
+        recorded_at,
+    })
+}
+
+fn clean_field(s: &str) -> String {
+    s.trim().replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_replaces_same_key_and_round_trips() {
+        let mut db = CalibrationDb::default();
+        db.record(Calibration::new(
+            "RTX 3080 Ti",
+            "550.54",
+            1,
+            500_000.0,
+            CalibrationSource::Search,
+        ));
+        db.record(Calibration::new(
+            " RTX 3080 Ti ",
+            "550.54",
+            1,
+            610_000.0,
+            CalibrationSource::Search,
+        ));
+        db.record(Calibration::new(
+            "RTX 3080 Ti",
+            "550.54",
+            4,
+            180_000.0,
+            CalibrationSource::Search,
+        ));
+        assert_eq!(db.entries().len(), 2);
+
+        let back = CalibrationDb::parse(&db.to_text());
+        assert_eq!(back.entries().len(), 2);
+        let hit = back.lookup("RTX 3080 Ti", "550.54", 1).unwrap();
+        assert_eq!(hit.seeds_per_sec, 610_000.0);
+        assert!(back.lookup("RTX 3080 Ti", "535.00", 1).is_none());
+        assert!(back.lookup("RTX 3080 Ti", "550.54", 2).is_none());
+    }
+
+    #[test]
+    fn search_record_beats_bench() {
+        let mut db = CalibrationDb::default();
+        db.record(Calibration::new(
+            "gpu",
+            "1.0",
+            1,
+            900.0,
+            CalibrationSource::Bench,
+        ));
+        assert_eq!(db.lookup("gpu", "1.0", 1).unwrap().seeds_per_sec, 900.0);
+        db.record(Calibration::new(
+            "gpu",
+            "1.0",
+            1,
+            700.0,
+            CalibrationSource::Search,
+        ));
+        assert_eq!(db.lookup("gpu", "1.0", 1).unwrap().seeds_per_sec, 700.0);
+    }
+
+    #[test]
+    fn cpu_key_names_model_and_threads() {
+        let (device, driver) = cpu_key();
+        assert!(device.len() > CPU_DEVICE.len() + 1);
+        assert!(device.starts_with("cpu "));
+        assert_eq!(driver, format!("{} threads", rayon::current_num_threads()));
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        assert_eq!(pool.install(cpu_key).1, "3 threads");
+    }
+
+    #[test]
+    fn save_replaces_file_and_leaves_no_temp() {
+        let dir =
+            std::env::temp_dir().join(format!("erg-vanity-calibration-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("calibration.tsv");
+        let mut db = CalibrationDb::default();
+        db.record(Calibration::new(
+            "RTX 3080 Ti",
+            "550.54",
+            1,
+            500_000.0,
+            CalibrationSource::Search,
+        ));
+        db.save(&path).unwrap();
+        db.record(Calibration::new(
+            "RTX 3080 Ti",
+            "550.54",
+            1,
+            610_000.0,
+            CalibrationSource::Search,
+        ));
+        db.save(&path).unwrap();
+
+        let loaded = CalibrationDb::load(&path).unwrap();
+        assert_eq!(
+            loaded
+                .lookup("RTX 3080 Ti", "550.54", 1)
+                .unwrap()
+                .seeds_per_sec,
+            610_000.0
+        );
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_skips_bad_lines() {
+        let db = CalibrationDb::parse(
+            "# header\ngpu\t1.0\t1\t1000\tsearch\t0\nbroken line\ngpu\t1.0\tx\t1\tsearch\t0\n",
+        );
+        assert_eq!(db.entries().len(), 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! How much of the machine a CPU search may take: worker threads, OS priority
+//! and duty cycle. All unset means every core at normal priority, non-stop.
+
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Highest `nice` value accepted (lowest priority), as on Unix.
+pub const MAX_NICE: i32 = 19;
+
+/// Accepted duty cycles in percent, for the CPU and GPU alike.
+pub const DUTY_PERCENT_RANGE: RangeInclusive<u8> = 1..=100;
+
+/// Check a `--cpu-duty` / `--gpu-duty` value against `DUTY_PERCENT_RANGE`.
+pub(crate) fn validate_duty(flag: &str, percent: u8) -> Result<u8, String> {
+    if DUTY_PERCENT_RANGE.contains(&percent) {
+        Ok(percent)
+    } else {
+        Err(format!(
+            "{flag} {percent} must be {} to {}",
+            DUTY_PERCENT_RANGE.start(),
+            DUTY_PERCENT_RANGE.end()
+        ))
+    }
+}
+
+/// `percent` clamped into `DUTY_PERCENT_RANGE`.
+pub(crate) fn clamp_duty(percent: u8) -> u8 {
+    percent.clamp(*DUTY_PERCENT_RANGE.start(), *DUTY_PERCENT_RANGE.end())
+}
+
+/// Longest single sleep while throttled, so a stop request is seen promptly.
+const PAUSE_SLICE: Duration = Duration::from_millis(50);
+
+/// Resource limits for the CPU backend. GPU searches ignore them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuLimits {
+    /// Worker threads in a dedicated pool. `None` uses one per logical core.
+    pub threads: Option<usize>,
+    /// Scheduling niceness for the worker threads, 0 (normal) to 19 (lowest).
+    pub nice: Option<i32>,
+    /// Percent of wall time spent searching, 1–100. The rest is slept between batches.
+    pub duty_percent: Option<u8>,
+}
+
+impl CpuLimits {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(0) = self.threads {
+            return Err("--cpu-threads must be at least 1".into());
+        }
+        if let Some(nice) = self.nice {
+            if !(0..=MAX_NICE).contains(&nice) {
+                return Err(format!("--cpu-nice {nice} must be 0 to {MAX_NICE}"));
+            }
+            if !cfg!(target_os = "linux") {
+                return Err("--cpu-nice is only supported on Linux".into());
+            }
+        }
+        if let Some(duty) = self.duty_percent {
+            validate_duty("--cpu-duty", duty)?;
+        }
+        Ok(())
+    }
+
+    /// True if the search needs a dedicated rayon pool (thread count or
+    /// priority set); false if the global pool can be used as is.
+    pub fn needs_pool(&self) -> bool {
+        self.threads.is_some() || self.nice.is_some()
+    }
+
+    /// True if the measured rate reflects the whole, unthrottled CPU.
+    pub fn is_full_speed(&self) -> bool {
+        self.threads.is_none() && self.duty_fraction() >= 1.0
+    }
+
+    pub fn duty_fraction(&self) -> f64 {
+        self.duty_percent
+            .map_or(1.0, |d| clamp_duty(d) as f64 / 100.0)
+    }
+
+    /// Expected share of the full-speed rate: duty cycle × share of cores used.
+    pub fn rate_factor(&self) -> f64 {
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let threads = self.threads.unwrap_or(cores).min(cores);
+        self.duty_fraction() * threads as f64 / cores as f64
+    }
+
+    /// Short human summary, e.g. "4 threads, nice 10, 50% duty". Empty if unlimited.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(t) = self.threads {
+            parts.push(format!("{t} thread{}", if t == 1 { "" } else { "s" }));
+        }
+        if let Some(n) = self.nice {
+            parts.push(format!("nice {n}"));
+        }
+        if let Some(d) = self.duty_percent.filter(|&d| d < 100) {
+            parts.push(format!("{d}% duty"));
+        }
+        parts.join(", ")
+    }
+
+    /// Sleep needed after `busy` of work to hold the duty cycle.
+    pub fn pause_after(&self, busy: Duration) -> Duration {
+        duty_pause(self.duty_fraction(), busy)
+    }
+
+    /// Dedicated rayon pool with the thread count and priority applied.
+    pub(crate) fn build_pool(&self) -> Result<rayon::ThreadPool, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.threads.unwrap_or(0))
+            .thread_name(|i| format!("erg-vanity-cpu-{i}"))
+            .build()
+            .map_err(|e| format!("cannot start CPU thread pool: {e}"))?;
+        if let Some(nice) = self.nice {
+            pool.broadcast(|_| lower_priority(nice))
+                .into_iter()
+                .collect::<Result<Vec<()>, String>>()?;
+        }
+        Ok(pool)
+    }
+}
+
+/// Sleep that makes `busy` the `duty` fraction (0–1] of wall time.
+pub(crate) fn duty_pause(duty: f64, busy: Duration) -> Duration {
+    if duty >= 1.0 {
+        return Duration::ZERO;
+    }
+    busy.mul_f64((1.0 - duty) / duty)
+}
+
+/// Sleep for `pause` in short slices, returning early once `stop` is set.
+pub(crate) fn pause(pause: Duration, stop: &AtomicBool) {
+    let mut left = pause;
+    while !left.is_zero() && !stop.load(Ordering::Relaxed) {
+        let slice = left.min(PAUSE_SLICE);
+        std::thread::sleep(slice);
+        left -= slice;
+    }
+}
+
+/// Set the calling thread's niceness. On Linux, `setpriority(PRIO_PROCESS, tid)`
+/// applies to that thread only, so the UI and GPU threads keep their priority.
+#[cfg(target_os = "linux")]
+fn lower_priority(nice: i32) -> Result<(), String> {
+    let tid = rustix::thread::gettid();
+    rustix::process::setpriority_process(Some(tid), nice)
+        .map_err(|e| format!("cannot set CPU thread priority to nice {nice}: {e}"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn lower_priority(_nice: i32) -> Result<(), String> {
+    Err("--cpu-nice is only supported on Linux".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_ranges() {
+        assert!(CpuLimits::default().validate().is_ok());
+        let zero_threads = CpuLimits {
+            threads: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_threads.validate().is_err());
+        let bad_duty = CpuLimits {
+            duty_percent: Some(0),
+            ..Default::default()
+        };
+        assert!(bad_duty.validate().is_err());
+        let bad_nice = CpuLimits {
+            nice: Some(-5),
+            ..Default::default()
+        };
+        assert!(bad_nice.validate().is_err());
+    }
+
+    #[test]
+    fn duty_cycle_pause_and_summary() {
+        let half = CpuLimits {
+            threads: Some(2),
+            nice: None,
+            duty_percent: Some(50),
+        };
+        assert_eq!(
+            half.pause_after(Duration::from_millis(300)),
+            Duration::from_millis(300)
+        );
+        let quarter = CpuLimits {
+            duty_percent: Some(25),
+            ..Default::default()
+        };
+        assert_eq!(
+            quarter.pause_after(Duration::from_millis(100)),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            CpuLimits::default().pause_after(Duration::from_secs(1)),
+            Duration::ZERO
+        );
+        assert_eq!(half.describe(), "2 threads, 50% duty");
+        assert!(!half.is_full_speed());
+        assert!(CpuLimits::default().is_full_speed());
+        assert!(quarter.rate_factor() <= 0.25);
+    }
+}
@@ -1,8 +1,10 @@
 //! Difficulty estimates for vanity patterns.
 
 use erg_vanity_cpu::MatchType;
-use erg_vanity_gpu::context::GpuContext;
+use erg_vanity_gpu::context::{DeviceInfo, DeviceKind, GpuContext};
 
+use crate::calibration::{cpu_key, CalibrationDb};
+use crate::cpu_limits::CpuLimits;
 use crate::search::Backend;
 
 /// Conservative CPU addr/s. Not measured.
@@ -24,9 +26,11 @@ pub const GPU_ASSUMED_RATE: f64 = GPU_BASELINE_SEEDS_PER_SEC;
 #[derive(Debug, Clone)]
 pub struct GpuDeviceHint {
     pub index: usize,
+    pub kind: DeviceKind,
     pub name: String,
     pub vendor: String,
     pub platform: String,
+    pub driver: String,
     pub compute_units: u32,
     pub max_clock_mhz: Option<u32>,
 }
@@ -34,8 +38,9 @@ pub struct GpuDeviceHint {
 impl GpuDeviceHint {
     pub fn display_line(&self) -> String {
         format!(
-            "[{}] {} - {} (platform: {})",
+            "[{}] {} · {} - {} (platform: {})",
             self.index,
+            self.kind.label(),
             self.vendor.trim(),
             self.name.trim(),
             self.platform.trim()
@@ -55,46 +60,176 @@ pub struct RateGuess {
     pub label: String,
     pub is_gpu: bool,
     pub num_indices: u32,
+    /// Every selected device had a calibration record for this `num_indices`.
+    pub measured: bool,
 }
 
 impl RateGuess {
+    /// Waiting-time quantiles for the first hit on `est` at this rate.
+    pub fn percentile_times(&self, est: &PatternEstimate) -> EtaPercentiles {
+        EtaPercentiles::for_hits(est.attempts_needed, 1, self.addr_per_sec)
+    }
+
+    /// Scale a CPU guess down to what `limits` leaves. GPU guesses are unchanged.
+    pub fn throttled(mut self, limits: &CpuLimits) -> Self {
+        if !self.is_gpu {
+            let factor = limits.rate_factor();
+            self.addr_per_sec *= factor;
+            self.seeds_per_sec *= factor;
+        }
+        self
+    }
+
     pub fn note(&self) -> String {
+        let kind = if self.measured {
+            "measured on"
+        } else {
+            "guess from"
+        };
         if !self.is_gpu {
-            return "guess from CPU".into();
+            return format!("{kind} CPU");
         }
         if self.num_indices > 1 {
-            format!(
-                "guess from {} · {} BIP44 slots",
-                self.label, self.num_indices
-            )
+            format!("{kind} {} · {} BIP44 slots", self.label, self.num_indices)
         } else {
-            format!("guess from {}", self.label)
+            format!("{kind} {}", self.label)
         }
     }
 }
 
 /// Estimated effort for a pattern.
+///
+/// `attempts_needed` is the mean. Each address matches independently, so the
+/// wait is geometric: half of runs finish by `p50_attempts`, and 1 in 100 runs
+/// still has not hit at `p99_attempts` (~4.6× the mean).
 #[derive(Debug, Clone)]
 pub struct PatternEstimate {
     pub attempts_needed: f64,
+    pub p50_attempts: f64,
+    pub p90_attempts: f64,
+    pub p99_attempts: f64,
     pub has_invalid_chars: bool,
     pub invalid_chars: Vec<char>,
 }
 
+/// Waiting-time quantiles in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EtaPercentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl EtaPercentiles {
+    /// Time to `hits` more matches at `rate` addr/s when one address in
+    /// `attempts` matches on average.
+    pub fn for_hits(attempts: f64, hits: usize, rate: f64) -> Self {
+        let per_sec = rate.max(1.0);
+        let at = |q| attempts_for_hits(attempts, hits, q) / per_sec;
+        Self {
+            p50: at(0.5),
+            p90: at(0.9),
+            p99: at(0.99),
+        }
+    }
+}
+
+/// Live odds for a running search, from the measured rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiveOdds {
+    /// Chance that a search this long would already have all its hits.
+    pub probability: f64,
+    /// Time left for the hits still missing. The wait has no memory, so this does
+    /// not shrink just because the search has run for a while.
+    pub remaining: EtaPercentiles,
+}
+
+impl LiveOdds {
+    /// Odds for `target` hits with `found` so far after `checked` addresses.
+    pub fn new(attempts: f64, checked: u64, found: usize, target: usize, rate: f64) -> Self {
+        Self {
+            probability: probability_found(attempts, checked as f64, target),
+            remaining: EtaPercentiles::for_hits(attempts, target.saturating_sub(found), rate),
+        }
+    }
+}
+
+/// Probability of at least `hits` matches in `checked` addresses.
+pub fn probability_found(attempts: f64, checked: f64, hits: usize) -> f64 {
+    if hits == 0 {
+        return 1.0;
+    }
+    if !attempts.is_finite() || attempts <= 0.0 {
+        return 0.0;
+    }
+    poisson_at_least(checked.max(0.0) / attempts, hits)
+}
+
+/// Addresses needed for `hits` matches with probability `q`.
+pub fn attempts_for_hits(attempts: f64, hits: usize, q: f64) -> f64 {
+    if hits == 0 {
+        return 0.0;
+    }
+    if !attempts.is_finite() {
+        return f64::INFINITY;
+    }
+    if hits == 1 {
+        return -attempts * (1.0 - q).ln();
+    }
+    // P(Poisson(λ) >= hits) rises with λ; bisect for q.
+    let mut lo = 0.0;
+    let mut hi = hits as f64;
+    while poisson_at_least(hi, hits) < q {
+        lo = hi;
+        hi *= 2.0;
+    }
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.0;
+        if poisson_at_least(mid, hits) < q {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi * attempts
+}
+
+fn poisson_at_least(lambda: f64, k: usize) -> f64 {
+    let mut term = (-lambda).exp();
+    let mut below = 0.0;
+    for i in 0..k {
+        below += term;
+        term *= lambda / (i + 1) as f64;
+    }
+    (1.0 - below).clamp(0.0, 1.0)
+}
+
 /// List OpenCL GPUs once. Callers should cache this; do not bench on every keystroke.
 pub fn list_gpu_device_hints() -> Result<Vec<GpuDeviceHint>, String> {
     let devices = GpuContext::enumerate_devices().map_err(|e| e.to_string())?;
-    Ok(devices
+    Ok(device_hints(devices))
+}
+
+/// List every OpenCL device: GPUs, then CPU and accelerator devices (e.g. POCL).
+pub fn list_opencl_device_hints() -> Result<Vec<GpuDeviceHint>, String> {
+    let devices = GpuContext::enumerate_all_devices().map_err(|e| e.to_string())?;
+    Ok(device_hints(devices))
+}
+
+fn device_hints(devices: Vec<DeviceInfo>) -> Vec<GpuDeviceHint> {
+    devices
         .into_iter()
         .map(|d| GpuDeviceHint {
             index: d.global_idx,
+            kind: d.kind,
             name: d.device_name,
             vendor: d.vendor,
             platform: d.platform_name,
+            driver: d.driver_version,
             compute_units: d.compute_units,
             max_clock_mhz: d.max_clock_mhz,
         })
-        .collect())
+        .collect()
 }
 
 /// Guess `--index 1` seeds/s for one GPU from the name table or CU scaling.
@@ -117,39 +252,67 @@ pub fn guess_gpu_seeds_per_sec(device: &GpuDeviceHint) -> f64 {
 }
 
 /// Pick a pre-search addr/s guess. `addr/s = seeds/s × index`. Suffix/contains and
-/// `--devices cpu` stay on the conservative CPU rate.
+/// `--devices cpu` use the CPU rate. Calibration records for the same device, driver
+/// and `num_indices` replace the name-table guess and the CPU constant. `devices`
+/// is every OpenCL device, as from `list_opencl_device_hints`.
 pub fn guess_rate_for(
     devices: &[GpuDeviceHint],
     backend: &Backend,
     match_type: MatchType,
     num_indices: u32,
+    calibration: &CalibrationDb,
 ) -> RateGuess {
     let num_indices = num_indices.max(1);
     let use_gpu = match (backend, match_type) {
         (_, MatchType::Suffix | MatchType::Contains) => false,
         (Backend::Cpu, _) => false,
-        (Backend::Gpu { .. } | Backend::Auto, MatchType::Prefix) => true,
+        (Backend::Gpu { .. } | Backend::OpenCl | Backend::Auto, MatchType::Prefix) => true,
     };
     let selected = select_gpu_hints(devices, backend);
     if !use_gpu || selected.is_empty() {
-        return RateGuess {
-            addr_per_sec: CPU_ASSUMED_RATE,
-            seeds_per_sec: CPU_ASSUMED_RATE,
-            label: "CPU".into(),
-            is_gpu: false,
-            num_indices,
+        let (cpu, threads) = cpu_key();
+        return match calibration.lookup(&cpu, &threads, num_indices) {
+            Some(m) => RateGuess {
+                addr_per_sec: m.seeds_per_sec * num_indices as f64,
+                seeds_per_sec: m.seeds_per_sec,
+                label: "CPU".into(),
+                is_gpu: false,
+                num_indices,
+                measured: true,
+            },
+            None => RateGuess {
+                addr_per_sec: CPU_ASSUMED_RATE,
+                seeds_per_sec: CPU_ASSUMED_RATE,
+                label: "CPU".into(),
+                is_gpu: false,
+                num_indices,
+                measured: false,
+            },
         };
     }
-    let seeds_per_sec: f64 = selected.iter().map(guess_gpu_seeds_per_sec).sum();
+    let mut seeds_per_sec = 0.0;
+    let mut measured = true;
+    for d in &selected {
+        match calibration.lookup(&d.name, &d.driver, num_indices) {
+            Some(m) => seeds_per_sec += m.seeds_per_sec,
+            None => {
+                seeds_per_sec += guess_gpu_seeds_per_sec(d);
+                measured = false;
+            }
+        }
+    }
     RateGuess {
         addr_per_sec: seeds_per_sec * num_indices as f64,
         seeds_per_sec,
         label: gpu_guess_label(&selected),
         is_gpu: true,
         num_indices,
+        measured,
     }
 }
 
+/// The devices `backend` searches on, from every OpenCL device: explicit
+/// indices and `OpenCl` take any kind, `Auto` and "all GPUs" only GPUs.
 fn select_gpu_hints(devices: &[GpuDeviceHint], backend: &Backend) -> Vec<GpuDeviceHint> {
     match backend {
         Backend::Cpu => Vec::new(),
@@ -158,7 +321,12 @@ fn select_gpu_hints(devices: &[GpuDeviceHint], backend: &Backend) -> Vec<GpuDevi
             .filter(|d| list.contains(&d.index))
             .cloned()
             .collect(),
-        _ => devices.to_vec(),
+        Backend::OpenCl => devices.to_vec(),
+        _ => devices
+            .iter()
+            .filter(|d| d.kind == DeviceKind::Gpu)
+            .cloned()
+            .collect(),
     }
 }
 
@@ -226,6 +394,9 @@ pub fn estimate_pattern(
     if !invalid_chars.is_empty() {
         return PatternEstimate {
             attempts_needed: f64::INFINITY,
+            p50_attempts: f64::INFINITY,
+            p90_attempts: f64::INFINITY,
+            p99_attempts: f64::INFINITY,
             has_invalid_chars: true,
             invalid_chars,
         };
@@ -257,13 +428,157 @@ pub fn estimate_pattern(
         }
     }
 
+    let mean = attempts * 1.2;
     PatternEstimate {
-        attempts_needed: attempts * 1.2,
+        attempts_needed: mean,
+        p50_attempts: attempts_for_hits(mean, 1, 0.5),
+        p90_attempts: attempts_for_hits(mean, 1, 0.9),
+        p99_attempts: attempts_for_hits(mean, 1, 0.99),
         has_invalid_chars: false,
         invalid_chars: Vec::new(),
     }
 }
 
+/// Effort for a search over several patterns, where any match counts.
+#[derive(Debug, Clone)]
+pub struct CombinedEstimate {
+    /// Mean addresses per hit over the union of all patterns.
+    pub attempts_needed: f64,
+    /// Fraction of hits credited to each pattern (request order) after
+    /// longest-first resolution. Sums to 1 unless every pattern is impossible.
+    pub shares: Vec<f64>,
+    /// Mean addresses per hit for each pattern on its own (request order).
+    pub pattern_attempts: Vec<f64>,
+}
+
+impl CombinedEstimate {
+    /// Addresses needed for `hits` matches on any pattern with probability `q`.
+    pub fn attempts_for(&self, hits: usize, q: f64) -> f64 {
+        attempts_for_hits(self.attempts_needed, hits, q)
+    }
+
+    /// Addresses until every pattern has `quota` hits with probability `q`.
+    ///
+    /// Bounded by the slowest pattern; the others retire first and stop competing.
+    pub fn attempts_for_quota(&self, quota: usize, q: f64) -> f64 {
+        self.pattern_attempts
+            .iter()
+            .map(|&a| attempts_for_hits(a, quota, q))
+            .fold(0.0, f64::max)
+    }
+
+    /// Smallest set of patterns that supplies at least `fraction` of hits, largest
+    /// share first. The rest barely move the time to the first hit.
+    pub fn dominant(&self, fraction: f64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.shares.len()).collect();
+        order.sort_by(|&a, &b| self.shares[b].total_cmp(&self.shares[a]).then(a.cmp(&b)));
+        let mut out = Vec::new();
+        let mut covered = 0.0;
+        for i in order {
+            if covered >= fraction || self.shares[i] <= 0.0 {
+                break;
+            }
+            covered += self.shares[i];
+            out.push(i);
+        }
+        out
+    }
+}
+
+/// Estimate a multi-pattern search.
+///
+/// Prefix and suffix patterns either nest (`9er` covers every `9err` address) or
+/// are disjoint, so the union is exact up to the per-pattern estimates. Contains
+/// patterns that do not nest are treated as independent. Hits on nested patterns
+/// go to the longest one, as in `first_match`.
+pub fn estimate_patterns(
+    patterns: &[String],
+    match_type: MatchType,
+    ignore_case: bool,
+) -> CombinedEstimate {
+    let keys: Vec<String> = patterns
+        .iter()
+        .map(|p| {
+            if ignore_case {
+                p.to_ascii_lowercase()
+            } else {
+                p.clone()
+            }
+        })
+        .collect();
+    let pattern_attempts: Vec<f64> = patterns
+        .iter()
+        .map(|p| estimate_pattern(p, match_type, ignore_case).attempts_needed)
+        .collect();
+    let probs: Vec<f64> = pattern_attempts.iter().map(|a| 1.0 / a).collect();
+    // `covers(i, j)`: every address matching pattern j also matches pattern i.
+    let covers = |i: usize, j: usize| match match_type {
+        MatchType::Prefix => keys[j].starts_with(keys[i].as_str()),
+        MatchType::Suffix => keys[j].ends_with(keys[i].as_str()),
+        MatchType::Contains => keys[j].contains(keys[i].as_str()),
+    };
+    let union = |set: &[usize]| -> f64 {
+        let outer: Vec<usize> = set
+            .iter()
+            .copied()
+            .filter(|&j| {
+                !set.iter()
+                    .any(|&i| i != j && covers(i, j) && (keys[i] != keys[j] || i < j))
+            })
+            .collect();
+        match match_type {
+            MatchType::Prefix | MatchType::Suffix => outer.iter().map(|&i| probs[i]).sum(),
+            MatchType::Contains => 1.0 - outer.iter().map(|&i| 1.0 - probs[i]).product::<f64>(),
+        }
+    };
+
+    let all: Vec<usize> = (0..patterns.len()).collect();
+    let total = union(&all);
+    let shares = (0..patterns.len())
+        .map(|i| {
+            let wins_over = |j: usize| {
+                let (li, lj) = (patterns[i].len(), patterns[j].len());
+                j != i && (lj > li || (lj == li && j < i))
+            };
+            let inside: Vec<usize> = all
+                .iter()
+                .copied()
+                .filter(|&j| wins_over(j) && covers(i, j))
+                .collect();
+            let mut credited = (probs[i] - union(&inside)).max(0.0);
+            if match_type == MatchType::Contains {
+                for j in all
+                    .iter()
+                    .copied()
+                    .filter(|&j| wins_over(j) && !covers(i, j))
+                {
+                    credited *= 1.0 - probs[j];
+                }
+            }
+            if total > 0.0 {
+                credited / total
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    CombinedEstimate {
+        attempts_needed: if total > 0.0 {
+            1.0 / total
+        } else {
+            f64::INFINITY
+        },
+        shares,
+        pattern_attempts,
+    }
+}
+
+/// Mean addresses per hit when a match on any pattern counts.
+pub fn combined_attempts(patterns: &[String], match_type: MatchType, ignore_case: bool) -> f64 {
+    estimate_patterns(patterns, match_type, ignore_case).attempts_needed
+}
+
 /// Format seconds as a short human string.
 pub fn format_time(seconds: f64) -> String {
     if seconds.is_infinite() {
@@ -317,13 +632,16 @@ fn has_base58_case_pair(c: char) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::calibration::{Calibration, CalibrationSource};
 
     fn hint(name: &str, compute_units: u32, max_clock_mhz: Option<u32>) -> GpuDeviceHint {
         GpuDeviceHint {
             index: 0,
+            kind: DeviceKind::Gpu,
             name: name.into(),
             vendor: "NVIDIA".into(),
             platform: "NVIDIA CUDA".into(),
+            driver: "550.54".into(),
             compute_units,
             max_clock_mhz,
         }
@@ -357,6 +675,80 @@ mod tests {
         assert!(insensitive.attempts_needed < sensitive.attempts_needed);
     }
 
+    #[test]
+    fn percentiles_follow_geometric_wait() {
+        let e = estimate_pattern("9ergo", MatchType::Prefix, false);
+        let mean = e.attempts_needed;
+        assert!((e.p50_attempts / mean - std::f64::consts::LN_2).abs() < 1e-9);
+        assert!((e.p90_attempts / mean - 10f64.ln()).abs() < 1e-9);
+        assert!((e.p99_attempts / mean - 100f64.ln()).abs() < 1e-9);
+        assert!(e.p50_attempts < mean && mean < e.p90_attempts);
+    }
+
+    #[test]
+    fn multi_hit_quantiles_invert_probability() {
+        let a = 1000.0;
+        for hits in [1, 3, 10] {
+            for q in [0.5, 0.9, 0.99] {
+                let n = attempts_for_hits(a, hits, q);
+                assert!((probability_found(a, n, hits) - q).abs() < 1e-6);
+            }
+        }
+        assert!(attempts_for_hits(a, 3, 0.5) > attempts_for_hits(a, 1, 0.5));
+    }
+
+    #[test]
+    fn live_odds_rise_but_remaining_time_does_not_shrink() {
+        let early = LiveOdds::new(1000.0, 100, 0, 1, 100.0);
+        let late = LiveOdds::new(1000.0, 5000, 0, 1, 100.0);
+        assert!(late.probability > early.probability);
+        assert!((late.probability - (1.0 - (-5.0f64).exp())).abs() < 1e-9);
+        assert_eq!(early.remaining, late.remaining);
+        let done = LiveOdds::new(1000.0, 5000, 1, 1, 100.0);
+        assert_eq!(done.remaining.p99, 0.0);
+    }
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn disjoint_prefixes_add_up() {
+        let c = estimate_patterns(&strings(&["9err", "9ego"]), MatchType::Prefix, false);
+        let one = estimate_pattern("9err", MatchType::Prefix, false).attempts_needed;
+        assert!((c.attempts_needed - one / 2.0).abs() < 1e-6);
+        assert!((c.shares[0] - 0.5).abs() < 1e-9);
+        assert!((c.shares[1] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nested_prefix_counts_once_and_longest_wins() {
+        let c = estimate_patterns(&strings(&["9e", "9err"]), MatchType::Prefix, false);
+        let short = estimate_pattern("9e", MatchType::Prefix, false).attempts_needed;
+        let long = estimate_pattern("9err", MatchType::Prefix, false).attempts_needed;
+        assert!((c.attempts_needed - short).abs() < 1e-9);
+        assert!((c.shares[1] - short / long).abs() < 1e-9);
+        assert!((c.shares[0] + c.shares[1] - 1.0).abs() < 1e-9);
+        assert_eq!(c.dominant(0.9), vec![0]);
+    }
+
+    #[test]
+    fn duplicate_patterns_credit_the_first() {
+        let c = estimate_patterns(&strings(&["9err", "9ERR"]), MatchType::Prefix, true);
+        let one = estimate_pattern("9err", MatchType::Prefix, true).attempts_needed;
+        assert!((c.attempts_needed - one).abs() < 1e-6);
+        assert!((c.shares[0] - 1.0).abs() < 1e-9);
+        assert_eq!(c.shares[1], 0.0);
+    }
+
+    #[test]
+    fn quota_waits_on_the_slowest_pattern() {
+        let c = estimate_patterns(&strings(&["9err", "9ergo"]), MatchType::Prefix, false);
+        let slow = estimate_pattern("9ergo", MatchType::Prefix, false).attempts_needed;
+        assert!((c.attempts_for_quota(1, 0.5) - attempts_for_hits(slow, 1, 0.5)).abs() < 1e-6);
+        assert!(c.attempts_for(2, 0.5) > c.attempts_for(1, 0.5));
+    }
+
     #[test]
     fn name_table_3080_ti_is_baseline() {
         let d = hint("NVIDIA GeForce RTX 3080 Ti", 80, None);
@@ -378,9 +770,11 @@ mod tests {
     fn apple_is_conservative() {
         let d = GpuDeviceHint {
             index: 0,
+            kind: DeviceKind::Gpu,
             name: "Apple M1".into(),
             vendor: "Apple".into(),
             platform: "Apple".into(),
+            driver: "1.2".into(),
             compute_units: 8,
             max_clock_mhz: None,
         };
@@ -410,12 +804,14 @@ mod tests {
             &Backend::Auto,
             MatchType::Prefix,
             1,
+            &CalibrationDb::default(),
         );
         let hundred = guess_rate_for(
             std::slice::from_ref(&d),
             &Backend::Auto,
             MatchType::Prefix,
             100,
+            &CalibrationDb::default(),
         );
         assert!((one.addr_per_sec - GPU_BASELINE_SEEDS_PER_SEC).abs() < 1.0);
         assert!((hundred.addr_per_sec - GPU_BASELINE_SEEDS_PER_SEC * 100.0).abs() < 1.0);
@@ -432,12 +828,14 @@ mod tests {
             &Backend::Auto,
             MatchType::Suffix,
             1,
+            &CalibrationDb::default(),
         );
         let cpu = guess_rate_for(
             std::slice::from_ref(&d),
             &Backend::Cpu,
             MatchType::Prefix,
             1,
+            &CalibrationDb::default(),
         );
         assert_eq!(suffix.addr_per_sec, CPU_ASSUMED_RATE);
         assert_eq!(cpu.addr_per_sec, CPU_ASSUMED_RATE);
@@ -445,6 +843,87 @@ mod tests {
         assert_eq!(suffix.note(), "guess from CPU");
     }
 
+    #[test]
+    fn cpu_opencl_device_counts_only_when_picked() {
+        let gpu = hint("NVIDIA GeForce RTX 3080 Ti", 80, None);
+        let pocl = GpuDeviceHint {
+            index: 1,
+            kind: DeviceKind::Cpu,
+            name: "pthread-AMD Ryzen 9 5950X".into(),
+            vendor: "AuthenticAMD".into(),
+            platform: "Portable Computing Language".into(),
+            driver: "5.0".into(),
+            compute_units: 16,
+            max_clock_mhz: None,
+        };
+        let pocl_rate = GPU_BASELINE_SEEDS_PER_SEC * 16.0 / 80.0;
+        let devices = [gpu, pocl];
+        let guess = |backend: Backend| {
+            guess_rate_for(
+                &devices,
+                &backend,
+                MatchType::Prefix,
+                1,
+                &CalibrationDb::default(),
+            )
+        };
+
+        let auto = guess(Backend::Auto);
+        assert!((auto.seeds_per_sec - GPU_BASELINE_SEEDS_PER_SEC).abs() < 1.0);
+        let by_index = guess(Backend::Gpu { devices: vec![1] });
+        assert!(by_index.is_gpu);
+        assert!((by_index.seeds_per_sec - pocl_rate).abs() < 1.0);
+        assert!(by_index.note().contains("pthread"));
+        let all = guess(Backend::OpenCl);
+        assert!((all.seeds_per_sec - GPU_BASELINE_SEEDS_PER_SEC - pocl_rate).abs() < 1.0);
+    }
+
+    #[test]
+    fn calibration_replaces_guess_for_matching_index_count() {
+        let d = hint("NVIDIA GeForce RTX 3080 Ti", 80, None);
+        let mut db = CalibrationDb::default();
+        db.record(Calibration::new(
+            &d.name,
+            &d.driver,
+            4,
+            200_000.0,
+            CalibrationSource::Search,
+        ));
+        let (cpu, threads) = cpu_key();
+        db.record(Calibration::new(
+            &cpu,
+            &threads,
+            4,
+            3_000.0,
+            CalibrationSource::Search,
+        ));
+        let four = guess_rate_for(
+            std::slice::from_ref(&d),
+            &Backend::Auto,
+            MatchType::Prefix,
+            4,
+            &db,
+        );
+        assert!(four.measured);
+        assert_eq!(four.seeds_per_sec, 200_000.0);
+        assert_eq!(four.addr_per_sec, 800_000.0);
+        assert!(four.note().starts_with("measured on RTX 3080 Ti"));
+
+        let one = guess_rate_for(
+            std::slice::from_ref(&d),
+            &Backend::Auto,
+            MatchType::Prefix,
+            1,
+            &db,
+        );
+        assert!(!one.measured);
+        assert_eq!(one.seeds_per_sec, GPU_BASELINE_SEEDS_PER_SEC);
+
+        let cpu = guess_rate_for(&[], &Backend::Cpu, MatchType::Suffix, 4, &db);
+        assert_eq!(cpu.addr_per_sec, 12_000.0);
+        assert_eq!(cpu.note(), "measured on CPU");
+    }
+
     #[test]
     fn format_rate_tracks_assumed_constants() {
         assert_eq!(
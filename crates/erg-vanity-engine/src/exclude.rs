@@ -0,0 +1,177 @@
+//! Blocklist rules that discard otherwise valid hits.
+
+use std::path::Path;
+
+/// Characters `run:` rules treat as look-alikes by default (after
+/// lowercasing): `1`, `i`, `j` and `L`, which read as a thin vertical stroke.
+pub const CONFUSABLE_CHARS: &str = "1ijl";
+
+/// One blocklist rule. Matching is ASCII case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exclusion {
+    /// Reject exactly this address.
+    Literal(String),
+    /// Reject any address that contains this substring.
+    Contains(String),
+    /// Reject any address with `min_len` or more consecutive characters
+    /// drawn from `chars`, e.g. `1i1Lj1`.
+    Run { min_len: usize, chars: String },
+}
+
+impl Exclusion {
+    /// Rule rejecting runs of `min_len` or more `CONFUSABLE_CHARS`.
+    pub fn confusable_run(min_len: usize) -> Self {
+        Exclusion::Run {
+            min_len: min_len.max(1),
+            chars: CONFUSABLE_CHARS.into(),
+        }
+    }
+
+    /// Parse one rule. `literal:` selects a whole-address match; `contains:` or no
+    /// prefix selects a substring match. `run:<N>` rejects runs of `N` or more
+    /// `CONFUSABLE_CHARS`; `run:<N>:<chars>` uses `chars` instead.
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        if let Some(rest) = rule.strip_prefix("run:") {
+            return Self::parse_run(rule, rest);
+        }
+        let (value, literal) = if let Some(rest) = rule.strip_prefix("literal:") {
+            (rest.trim(), true)
+        } else if let Some(rest) = rule.strip_prefix("contains:") {
+            (rest.trim(), false)
+        } else {
+            (rule, false)
+        };
+        if value.is_empty() {
+            return Err(format!("empty exclusion rule '{rule}'"));
+        }
+        if !value.is_ascii() {
+            return Err(format!("exclusion '{value}' contains non-ASCII characters"));
+        }
+        let value = value.to_ascii_lowercase();
+        Ok(if literal {
+            Exclusion::Literal(value)
+        } else {
+            Exclusion::Contains(value)
+        })
+    }
+
+    fn parse_run(rule: &str, rest: &str) -> Result<Self, String> {
+        let (len, chars) = match rest.split_once(':') {
+            Some((len, chars)) => (len.trim(), chars.trim()),
+            None => (rest.trim(), CONFUSABLE_CHARS),
+        };
+        let min_len = len
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n >= 2)
+            .ok_or_else(|| format!("run length in '{rule}' must be a number of at least 2"))?;
+        if chars.is_empty() || !chars.is_ascii() {
+            return Err(format!(
+                "run characters in '{rule}' must be non-empty ASCII"
+            ));
+        }
+        Ok(Exclusion::Run {
+            min_len,
+            chars: chars.to_ascii_lowercase(),
+        })
+    }
+
+    /// Check one address against this rule.
+    pub fn matches(&self, address: &str) -> bool {
+        let addr = address.to_ascii_lowercase();
+        match self {
+            Exclusion::Literal(s) => addr == *s,
+            Exclusion::Contains(s) => addr.contains(s.as_str()),
+            Exclusion::Run { min_len, chars } => {
+                let mut run = 0;
+                addr.bytes().any(|b| {
+                    run = if chars.as_bytes().contains(&b) {
+                        run + 1
+                    } else {
+                        0
+                    };
+                    run >= *min_len
+                })
+            }
+        }
+    }
+}
+
+/// True if any rule rejects the address.
+pub fn is_excluded(rules: &[Exclusion], address: &str) -> bool {
+    rules.iter().any(|r| r.matches(address))
+}
+
+/// Parse a blocklist: one rule per line, blank lines and `#` comments skipped.
+pub fn parse_blocklist(text: &str) -> Result<Vec<Exclusion>, String> {
+    let mut rules = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        rules.push(Exclusion::parse(line).map_err(|e| format!("line {}: {e}", n + 1))?);
+    }
+    Ok(rules)
+}
+
+/// Read and parse a blocklist file.
+pub fn load_blocklist(path: &Path) -> Result<Vec<Exclusion>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read blocklist {}: {e}", path.display()))?;
+    parse_blocklist(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: &str = "9errK7Qa3oBVHbS4uGFPSe7ETvfHkZGcskV1gqGf6fqLUPAamo";
+
+    #[test]
+    fn contains_is_case_insensitive() {
+        let rule = Exclusion::parse("gfpse").unwrap();
+        assert_eq!(rule, Exclusion::Contains("gfpse".into()));
+        assert!(rule.matches(ADDR));
+        assert!(!Exclusion::parse("zzzz").unwrap().matches(ADDR));
+    }
+
+    #[test]
+    fn literal_needs_whole_address() {
+        let rule = Exclusion::parse(&format!("literal:{ADDR}")).unwrap();
+        assert!(rule.matches(ADDR));
+        assert!(!Exclusion::parse("literal:9err").unwrap().matches(ADDR));
+    }
+
+    #[test]
+    fn run_rejects_mixed_confusables() {
+        let rule = Exclusion::parse("run:4").unwrap();
+        assert_eq!(rule, Exclusion::confusable_run(4));
+        assert!(rule.matches("9err1iL1xyz"));
+        assert!(!rule.matches("9err1iLx1xyz"));
+        assert!(!rule.matches(ADDR));
+
+        let custom = Exclusion::parse("run:3:Zz").unwrap();
+        assert!(custom.matches("9errzZzq"));
+        assert!(Exclusion::parse("run:1").is_err());
+        assert!(Exclusion::parse("run:x").is_err());
+        assert!(Exclusion::parse("run:3:").is_err());
+    }
+
+    #[test]
+    fn blocklist_skips_comments_and_reports_lines() {
+        let rules =
+            parse_blocklist("# offensive\n\n111111\ncontains: zzz\nliteral:9abc\n").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                Exclusion::Contains("111111".into()),
+                Exclusion::Contains("zzz".into()),
+                Exclusion::Literal("9abc".into()),
+            ]
+        );
+        let err = parse_blocklist("ok\nliteral:\n").unwrap_err();
+        assert!(err.starts_with("line 2:"));
+    }
+}
@@ -0,0 +1,143 @@
+//! GPU duty cycle: the share of wall time each device spends running batches,
+//! for rigs that share a power budget. Adjustable while a search runs.
+
+use crate::cpu_limits::{clamp_duty, duty_pause, validate_duty, DUTY_PERCENT_RANGE};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Per-device GPU duty cycle in percent, within `DUTY_PERCENT_RANGE`. Unset
+/// devices run non-stop.
+///
+/// Clones share one table, so a caller that keeps a clone of the request's
+/// handle can change the duty cycle of a running search.
+#[derive(Debug, Clone, Default)]
+pub struct GpuDuty {
+    table: Arc<RwLock<DutyTable>>,
+}
+
+#[derive(Debug, Default)]
+struct DutyTable {
+    /// Duty of devices without their own entry. `None` is 100%.
+    all: Option<u8>,
+    devices: BTreeMap<usize, u8>,
+}
+
+impl GpuDuty {
+    /// Parse `60` (every device) or `0:60,1:80` (per device; others run at 100%).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let duty = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (device, percent) = match part.split_once(':') {
+                Some((d, p)) => {
+                    let d = d
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| format!("--gpu-duty: bad device index in '{part}'"))?;
+                    (Some(d), p.trim())
+                }
+                None => (None, part),
+            };
+            let percent = percent.parse::<u8>().map_err(|_| {
+                format!(
+                    "--gpu-duty: '{part}' must be a percent from {} to {}",
+                    DUTY_PERCENT_RANGE.start(),
+                    DUTY_PERCENT_RANGE.end()
+                )
+            })?;
+            let percent = validate_duty("--gpu-duty", percent)?;
+            match device {
+                Some(d) => duty.set_device(d, percent),
+                None => duty.set_all(percent),
+            }
+        }
+        Ok(duty)
+    }
+
+    /// Set every device to `percent` (clamped to `DUTY_PERCENT_RANGE`), replacing
+    /// per-device values.
+    pub fn set_all(&self, percent: u8) {
+        let mut table = self.table.write().unwrap_or_else(|e| e.into_inner());
+        table.all = Some(clamp_duty(percent));
+        table.devices.clear();
+    }
+
+    /// Set one device to `percent` (clamped to `DUTY_PERCENT_RANGE`).
+    pub fn set_device(&self, device: usize, percent: u8) {
+        let mut table = self.table.write().unwrap_or_else(|e| e.into_inner());
+        table.devices.insert(device, clamp_duty(percent));
+    }
+
+    /// Current duty of `device` in percent.
+    pub fn percent(&self, device: usize) -> u8 {
+        let table = self.table.read().unwrap_or_else(|e| e.into_inner());
+        table
+            .devices
+            .get(&device)
+            .copied()
+            .or(table.all)
+            .unwrap_or(100)
+    }
+
+    /// Short human summary, e.g. "60% duty" or "gpu:0 50%, gpu:1 80%". Empty if unthrottled.
+    pub fn describe(&self) -> String {
+        let table = self.table.read().unwrap_or_else(|e| e.into_inner());
+        let mut parts = Vec::new();
+        if let Some(all) = table.all.filter(|&p| p < 100) {
+            parts.push(format!("{all}% duty"));
+        }
+        for (device, percent) in &table.devices {
+            parts.push(format!("gpu:{device} {percent}%"));
+        }
+        parts.join(", ")
+    }
+
+    /// Idle gap after `busy` of batch work on `device` to hold its duty cycle.
+    pub(crate) fn pause_after(&self, device: usize, busy: Duration) -> Duration {
+        duty_pause(self.percent(device) as f64 / 100.0, busy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_global_and_per_device() {
+        let all = GpuDuty::parse("60").unwrap();
+        assert_eq!(all.percent(0), 60);
+        assert_eq!(all.percent(3), 60);
+        assert_eq!(all.describe(), "60% duty");
+
+        let per = GpuDuty::parse("0:50, 2:80").unwrap();
+        assert_eq!(per.percent(0), 50);
+        assert_eq!(per.percent(1), 100);
+        assert_eq!(per.percent(2), 80);
+        assert_eq!(per.describe(), "gpu:0 50%, gpu:2 80%");
+
+        assert!(GpuDuty::parse("0").is_err());
+        assert!(GpuDuty::parse("101").is_err());
+        assert!(GpuDuty::parse("x:50").is_err());
+        assert_eq!(GpuDuty::default().describe(), "");
+    }
+
+    #[test]
+    fn clones_share_runtime_changes() {
+        let duty = GpuDuty::default();
+        let worker = duty.clone();
+        assert_eq!(
+            worker.pause_after(0, Duration::from_millis(100)),
+            Duration::ZERO
+        );
+
+        duty.set_all(25);
+        assert_eq!(
+            worker.pause_after(0, Duration::from_millis(100)),
+            Duration::from_millis(300)
+        );
+        duty.set_device(1, 0);
+        assert_eq!(worker.percent(1), 1);
+        duty.set_all(100);
+        assert_eq!(worker.percent(1), 100);
+    }
+}
@@ -2,17 +2,29 @@
 
 #![forbid(unsafe_code)]
 
+pub mod calibration;
+pub mod cpu_limits;
 pub mod estimate;
+pub mod exclude;
+pub mod gpu_duty;
 pub mod search;
 pub mod verify;
 
+pub use calibration::{record_measurements, Calibration, CalibrationDb, CalibrationSource};
+pub use cpu_limits::{CpuLimits, DUTY_PERCENT_RANGE};
 pub use estimate::{
-    estimate_pattern, format_rate, format_time, guess_rate_for, list_gpu_device_hints,
-    GpuDeviceHint, PatternEstimate, RateGuess, CPU_ASSUMED_RATE, GPU_ASSUMED_RATE,
-    GPU_BASELINE_SEEDS_PER_SEC,
+    attempts_for_hits, combined_attempts, estimate_pattern, estimate_patterns, format_rate,
+    format_time, guess_rate_for, list_gpu_device_hints, list_opencl_device_hints,
+    probability_found, CombinedEstimate, EtaPercentiles, GpuDeviceHint, LiveOdds, PatternEstimate,
+    RateGuess, CPU_ASSUMED_RATE, GPU_ASSUMED_RATE, GPU_BASELINE_SEEDS_PER_SEC,
 };
+pub use exclude::{is_excluded, load_blocklist, parse_blocklist, Exclusion};
+pub use gpu_duty::GpuDuty;
 pub use search::{
     list_gpu_devices, run_search, validate_pattern, Backend, Hit, SearchEvent, SearchRequest,
     MAX_PATTERNS, MAX_PATTERN_DATA, MAX_PATTERN_LEN,
 };
 pub use verify::verify_hit_ergo_lib;
+
+/// Kind of OpenCL device in `GpuDeviceHint`.
+pub use erg_vanity_gpu::context::DeviceKind;
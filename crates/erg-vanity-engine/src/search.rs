@@ -1,11 +1,20 @@
 //! Search orchestration: pick GPU or CPU, stream events.
 
+use crate::calibration::{
+    cpu_key, record_measurements, Calibration, CalibrationSource, MIN_CALIBRATION_SECS,
+};
+use crate::cpu_limits::{pause, CpuLimits};
+use crate::estimate::{combined_attempts, LiveOdds};
+use crate::exclude::{is_excluded, Exclusion};
+use crate::gpu_duty::GpuDuty;
 use crate::verify::verify_hit_ergo_lib;
 use erg_vanity_address::Network;
+use erg_vanity_core::Secret;
 use erg_vanity_cpu::{search_counter_range, MatchType, Pattern};
-use erg_vanity_gpu::context::GpuContext;
-use erg_vanity_gpu::pipeline::{VanityConfig, VanityPipeline};
+use erg_vanity_gpu::context::{DeviceKind, GpuContext};
+use erg_vanity_gpu::pipeline::{VanityConfig, VanityPipeline, VanityResult};
 use rand::RngCore;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
@@ -25,16 +34,20 @@ pub enum Backend {
     Auto,
     /// Force CPU.
     Cpu,
-    /// Specific GPU device indices, or all GPUs if empty after resolve.
+    /// Specific OpenCL device indices as listed by `--list-devices` (any
+    /// kind), or all GPUs if empty after resolve.
     Gpu { devices: Vec<usize> },
+    /// Every OpenCL device: the GPUs plus CPU and accelerator devices such
+    /// as POCL, which are otherwise only used when picked by index.
+    OpenCl,
 }
 
-/// A verified vanity hit.
+/// A verified vanity hit. The mnemonic and entropy are wiped on drop.
 #[derive(Clone)]
 pub struct Hit {
     pub address: String,
-    pub mnemonic: String,
-    pub entropy: [u8; 32],
+    pub mnemonic: Secret<String>,
+    pub entropy: Secret<[u8; 32]>,
     pub address_index: u32,
     pub pattern_index: u32,
     pub device_label: String,
@@ -47,18 +60,33 @@ pub enum SearchEvent {
         checked: u64,
         rate: f64,
         found: usize,
+        rejected: u64,
+        /// Accepted hits per pattern, in request order.
+        per_pattern: Vec<usize>,
+        /// Odds of having all target hits by now, and time left, at `rate`.
+        odds: LiveOdds,
     },
     Hit(Hit),
+    /// A pattern met its `per_pattern` quota and is no longer searched.
+    Retired {
+        pattern_index: u32,
+    },
     Dropped {
         count: u64,
         reason: Option<String>,
     },
+    /// A device's measured rate was saved to the calibration file.
+    Calibrated {
+        device: String,
+        seeds_per_sec: f64,
+    },
     Error {
         message: String,
     },
     Done {
         checked: u64,
         found: usize,
+        rejected: u64,
         elapsed: Duration,
     },
 }
@@ -74,9 +102,32 @@ pub struct SearchRequest {
     pub duration: Option<Duration>,
     pub backend: Backend,
     pub batch_size: Option<usize>,
+    /// GPU interactive mode: resize batches to keep each kernel launch under
+    /// this time, with `batch_size` as the upper bound.
+    pub kernel_latency: Option<Duration>,
+    /// Hits allowed per pattern. A pattern that reaches it is retired, and the run
+    /// ends once every pattern is retired. Replaces `max_results` when set.
+    pub per_pattern: Option<usize>,
+    /// Hits whose address matches any rule are discarded and counted as rejected.
+    pub exclude: Vec<Exclusion>,
+    /// Thread count, priority and duty cycle for the CPU backend.
+    pub cpu_limits: CpuLimits,
+    /// Per-device GPU duty cycle. Keep a clone to change it while the search runs.
+    pub gpu_duty: GpuDuty,
+    /// Write measured device rates to the calibration file when the run is
+    /// long enough, unthrottled and not in interactive mode.
+    pub save_rates: bool,
 }
 
 impl SearchRequest {
+    /// Total hits that end the run.
+    pub fn target(&self) -> usize {
+        match self.per_pattern {
+            Some(quota) => quota.saturating_mul(self.patterns.len()),
+            None => self.max_results,
+        }
+    }
+
     /// Validate patterns and limits.
     pub fn validate(&self) -> Result<(), String> {
         if self.patterns.is_empty() {
@@ -96,6 +147,9 @@ impl SearchRequest {
         if self.max_results == 0 {
             return Err("--max-results must be at least 1".into());
         }
+        if let Some(0) = self.per_pattern {
+            return Err("--per-pattern must be at least 1".into());
+        }
         if self.num_indices == 0 {
             return Err("--index must be at least 1".into());
         }
@@ -108,6 +162,10 @@ impl SearchRequest {
         if let Some(0) = self.batch_size {
             return Err("--batch-size must be at least 1".into());
         }
+        if self.kernel_latency.is_some_and(|d| d.is_zero()) {
+            return Err("--kernel-latency-ms must be at least 1".into());
+        }
+        self.cpu_limits.validate()?;
         for p in &self.patterns {
             validate_pattern(p, self.match_type, self.ignore_case)?;
         }
@@ -171,6 +229,7 @@ pub fn run_search(req: SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicB
         let _ = tx.send(SearchEvent::Done {
             checked: 0,
             found: 0,
+            rejected: 0,
             elapsed: Duration::ZERO,
         });
         return;
@@ -179,7 +238,7 @@ pub fn run_search(req: SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicB
     let use_gpu = match (&req.backend, req.match_type) {
         (_, MatchType::Suffix | MatchType::Contains) => false,
         (Backend::Cpu, _) => false,
-        (Backend::Gpu { .. }, MatchType::Prefix) => true,
+        (Backend::Gpu { .. } | Backend::OpenCl, MatchType::Prefix) => true,
         (Backend::Auto, MatchType::Prefix) => gpu_available(),
     };
 
@@ -196,20 +255,118 @@ fn gpu_available() -> bool {
         .unwrap_or(false)
 }
 
-fn compiled_patterns(req: &SearchRequest) -> Vec<Pattern> {
+/// Compile the patterns not retired in `retired` (bit `i` is pattern `i`).
+///
+/// Returns the matchers and a map from matcher index back to request order.
+fn compiled_patterns(req: &SearchRequest, retired: u64) -> (Vec<Pattern>, Vec<u32>) {
     req.patterns
         .iter()
-        .map(|p| Pattern::new(p.clone(), req.match_type).ignore_case(req.ignore_case))
-        .collect()
+        .enumerate()
+        .filter(|(i, _)| retired & (1u64 << i) == 0)
+        .map(|(i, p)| {
+            (
+                Pattern::new(p.clone(), req.match_type).ignore_case(req.ignore_case),
+                i as u32,
+            )
+        })
+        .unzip()
+}
+
+/// Hit counters owned by the event loop.
+struct Tally {
+    found: usize,
+    rejected: u64,
+    per_pattern: Vec<usize>,
+    /// Bit `i` set once pattern `i` met its quota. Read by the search workers.
+    retired: Arc<AtomicU64>,
+    /// Mean addresses per hit over the patterns still searched.
+    attempts: f64,
+    target: usize,
+}
+
+impl Tally {
+    fn new(req: &SearchRequest) -> Self {
+        Self {
+            found: 0,
+            rejected: 0,
+            per_pattern: vec![0; req.patterns.len()],
+            retired: Arc::new(AtomicU64::new(0)),
+            attempts: combined_attempts(&req.patterns, req.match_type, req.ignore_case),
+            target: req.target(),
+        }
+    }
+
+    fn retire(&mut self, req: &SearchRequest, idx: usize) {
+        let mask = self.retired.fetch_or(1u64 << idx, Ordering::Relaxed) | (1u64 << idx);
+        let active: Vec<String> = req
+            .patterns
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1u64 << i) == 0)
+            .map(|(_, p)| p.clone())
+            .collect();
+        self.attempts = combined_attempts(&active, req.match_type, req.ignore_case);
+    }
+
+    fn progress(&self, checked: u64, rate: f64) -> SearchEvent {
+        SearchEvent::Progress {
+            checked,
+            rate,
+            found: self.found,
+            rejected: self.rejected,
+            per_pattern: self.per_pattern.clone(),
+            odds: LiveOdds::new(self.attempts, checked, self.found, self.target, rate),
+        }
+    }
+}
+
+/// Span of the GPU progress rate.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Checked-address rate over the last `RATE_WINDOW`, so a GPU duty-cycle change
+/// shows up in progress promptly instead of averaged into the whole run.
+#[derive(Default)]
+struct RecentRate {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RecentRate {
+    /// Record `checked` at `now`. `None` until the window spans a second.
+    fn update(&mut self, now: Instant, checked: u64) -> Option<f64> {
+        self.samples.push_back((now, checked));
+        while self.samples.len() > 2
+            && self
+                .samples
+                .get(1)
+                .is_some_and(|&(t, _)| now.duration_since(t) >= RATE_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+        let &(t0, c0) = self.samples.front()?;
+        let secs = now.duration_since(t0).as_secs_f64();
+        (secs >= 1.0).then(|| checked.saturating_sub(c0) as f64 / secs)
+    }
 }
 
 fn accept_hit(
     hit: Hit,
+    req: &SearchRequest,
     tx: &Sender<SearchEvent>,
-    found: &mut usize,
-    max: usize,
+    tally: &mut Tally,
     stop: &AtomicBool,
 ) {
+    if is_excluded(&req.exclude, &hit.address) {
+        tally.rejected += 1;
+        return;
+    }
+    let idx = hit.pattern_index as usize;
+    if idx >= tally.per_pattern.len() {
+        return;
+    }
+    // Batches already in flight can still hit a pattern that was just retired.
+    if req.per_pattern.is_some_and(|q| tally.per_pattern[idx] >= q) {
+        return;
+    }
     if !verify_hit_ergo_lib(
         &hit.entropy,
         hit.address_index,
@@ -225,32 +382,56 @@ fn accept_hit(
         });
         return;
     }
-    if *found >= max {
+    let target = tally.target;
+    if tally.found >= target {
         stop.store(true, Ordering::Relaxed);
         return;
     }
-    *found += 1;
+    tally.found += 1;
+    tally.per_pattern[idx] += 1;
     let _ = tx.send(SearchEvent::Hit(hit));
-    if *found >= max {
+    if req.per_pattern.is_some_and(|q| tally.per_pattern[idx] >= q) {
+        tally.retire(req, idx);
+        let _ = tx.send(SearchEvent::Retired {
+            pattern_index: idx as u32,
+        });
+    }
+    if tally.found >= target {
         stop.store(true, Ordering::Relaxed);
     }
 }
 
 fn run_cpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>) {
-    let patterns = compiled_patterns(req);
+    let limits = req.cpu_limits;
+    let pool = if limits.needs_pool() {
+        match limits.build_pool() {
+            Ok(pool) => Some(pool),
+            Err(message) => {
+                let _ = tx.send(SearchEvent::Error { message });
+                let _ = tx.send(SearchEvent::Done {
+                    checked: 0,
+                    found: 0,
+                    rejected: 0,
+                    elapsed: Duration::ZERO,
+                });
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut tally = Tally::new(req);
+    let mut retired_seen = 0u64;
+    let (mut patterns, mut pattern_map) = compiled_patterns(req, retired_seen);
     let mut salt = [0u8; 32];
     rand::thread_rng().fill_bytes(&mut salt);
     let counter = Arc::new(AtomicU64::new(0));
     let batch = req.batch_size.unwrap_or(256).max(1) as u64;
     let start = Instant::now();
-    let mut found = 0usize;
     let mut checked = 0u64;
     let mut last_report = Instant::now();
-    let _ = tx.send(SearchEvent::Progress {
-        checked: 0,
-        rate: 0.0,
-        found: 0,
-    });
+    let _ = tx.send(tally.progress(0, 0.0));
 
     if let Some(d) = req.duration {
         let stop = Arc::clone(&stop);
@@ -260,62 +441,117 @@ fn run_cpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
         });
     }
 
-    while !stop.load(Ordering::Relaxed) && found < req.max_results {
+    while !stop.load(Ordering::Relaxed) && tally.found < req.target() {
+        let retired = tally.retired.load(Ordering::Relaxed);
+        if retired != retired_seen {
+            (patterns, pattern_map) = compiled_patterns(req, retired);
+            retired_seen = retired;
+        }
         let start_id = counter.fetch_add(batch, Ordering::Relaxed);
-        if let Some(hit) = search_counter_range(
-            &patterns,
-            Network::Mainnet,
-            req.num_indices,
-            &salt,
-            start_id,
-            batch,
-            &stop,
-        ) {
+        let batch_start = Instant::now();
+        let search = || {
+            search_counter_range(
+                &patterns,
+                Network::Mainnet,
+                req.num_indices,
+                &salt,
+                start_id,
+                batch,
+                &stop,
+            )
+        };
+        let found = match &pool {
+            Some(pool) => pool.install(search),
+            None => search(),
+        };
+        let busy = batch_start.elapsed();
+        if let Some(hit) = found {
             accept_hit(
                 Hit {
                     address: hit.generated.address,
                     mnemonic: hit.generated.mnemonic,
                     entropy: hit.entropy,
                     address_index: hit.generated.address_index,
-                    pattern_index: hit.pattern_index,
+                    pattern_index: pattern_map[hit.pattern_index as usize],
                     device_label: "cpu".into(),
                 },
+                req,
                 &tx,
-                &mut found,
-                req.max_results,
+                &mut tally,
                 &stop,
             );
         }
         checked += batch * req.num_indices as u64;
+        // Rate includes the duty-cycle sleeps, so it is the effective rate.
+        pause(limits.pause_after(busy), &stop);
         if last_report.elapsed().as_secs_f64() >= 0.2 {
             let rate = checked as f64 / start.elapsed().as_secs_f64().max(0.001);
-            let _ = tx.send(SearchEvent::Progress {
-                checked,
-                rate,
-                found,
-            });
+            let _ = tx.send(tally.progress(checked, rate));
             last_report = Instant::now();
         }
     }
 
+    // A throttled or thread-limited run says nothing about the machine's full rate.
+    let secs = start.elapsed().as_secs_f64();
+    if req.save_rates && secs >= MIN_CALIBRATION_SECS && limits.is_full_speed() {
+        let seeds = checked as f64 / req.num_indices.max(1) as f64;
+        let (device, driver) = cpu_key();
+        save_calibration(
+            vec![Calibration::new(
+                &device,
+                &driver,
+                req.num_indices,
+                seeds / secs,
+                CalibrationSource::Search,
+            )],
+            &tx,
+        );
+    }
+
     let _ = tx.send(SearchEvent::Done {
         checked,
-        found,
+        found: tally.found,
+        rejected: tally.rejected,
         elapsed: start.elapsed(),
     });
 }
 
+/// Persist measured rates. A failed write must not fail the search, so errors are dropped.
+fn save_calibration(records: Vec<Calibration>, tx: &Sender<SearchEvent>) {
+    if records.is_empty() {
+        return;
+    }
+    let events: Vec<SearchEvent> = records
+        .iter()
+        .map(|r| SearchEvent::Calibrated {
+            device: r.device.clone(),
+            seeds_per_sec: r.seeds_per_sec,
+        })
+        .collect();
+    if record_measurements(records).is_ok() {
+        for e in events {
+            let _ = tx.send(e);
+        }
+    }
+}
+
 enum WorkerMsg {
     Ready,
     Hit(Hit),
-    Error { device: usize, message: String },
-    Stats { dropped: u64 },
+    Error {
+        device: usize,
+        message: String,
+    },
+    Stats {
+        dropped: u64,
+        measured: Option<Calibration>,
+    },
 }
 
 fn resolve_gpu_devices(backend: &Backend) -> Result<Vec<usize>, String> {
-    let devices = GpuContext::enumerate_devices().map_err(|e| e.to_string())?;
+    let devices = GpuContext::enumerate_all_devices().map_err(|e| e.to_string())?;
     if devices.is_empty() {
-        return Err("no OpenCL GPU devices found".into());
+        return Err("no OpenCL devices found".into());
     }
     let available: Vec<usize> = devices.iter().map(|d| d.global_idx).collect();
     match backend {
@@ -329,7 +565,22 @@ fn resolve_gpu_devices(backend: &Backend) -> Result<Vec<usize>, String> {
             }
             Ok(list.clone())
         }
-        _ => Ok(available),
+        Backend::OpenCl => Ok(available),
+        _ => {
+            let gpus: Vec<usize> = devices
+                .iter()
+                .filter(|d| d.kind == DeviceKind::Gpu)
+                .map(|d| d.global_idx)
+                .collect();
+            if gpus.is_empty() {
+                return Err(
+                    "no OpenCL GPU devices found (--devices opencl also uses CPU and \
+                     accelerator devices)"
+                        .into(),
+                );
+            }
+            Ok(gpus)
+        }
     }
 }
 
@@ -341,6 +592,7 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
             let _ = tx.send(SearchEvent::Done {
                 checked: 0,
                 found: 0,
+                rejected: 0,
                 elapsed: Duration::ZERO,
             });
             return;
@@ -350,18 +602,18 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
     let mut salt = [0u8; 32];
     rand::thread_rng().fill_bytes(&mut salt);
 
-    let default_batch = GpuContext::with_device(devices[0])
-        .ok()
-        .map(|ctx| ctx.recommended_batch_size())
-        .unwrap_or(1 << 18);
-    let batch_size = req.batch_size.unwrap_or(default_batch).max(1);
+    // Without --batch-size each device uses its autotuned or heuristic batch.
     let cfg = VanityConfig {
-        batch_size,
+        batch_size: req.batch_size,
         ignore_case: req.ignore_case,
         num_indices: req.num_indices,
         match_type: req.match_type,
+        kernel_latency: req.kernel_latency,
+        hit_capacity: None,
+        specialize: true,
     };
 
+    let mut tally = Tally::new(req);
     let counter = Arc::new(AtomicU64::new(0));
     let total_checked = Arc::new(AtomicU64::new(0));
     let (wtx, wrx) = std::sync::mpsc::channel::<WorkerMsg>();
@@ -374,6 +626,8 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
         let counter = Arc::clone(&counter);
         let stop = Arc::clone(&stop);
         let total_checked = Arc::clone(&total_checked);
+        let retired = Arc::clone(&tally.retired);
+        let gpu_duty = req.gpu_duty.clone();
         let handle = thread::spawn(move || {
             let mut pipeline = match VanityPipeline::new_with_device_and_salt(
                 &patterns,
@@ -391,41 +645,120 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
                 }
             };
             let _ = wtx.send(WorkerMsg::Ready);
+            let send_hits = |batch: Vec<VanityResult>| {
+                batch.into_iter().all(|result| {
+                    wtx.send(WorkerMsg::Hit(Hit {
+                        address: result.address,
+                        mnemonic: result.mnemonic,
+                        entropy: result.entropy,
+                        address_index: result.address_index,
+                        pattern_index: result.pattern_index,
+                        device_label: format!("gpu:{device_index}"),
+                    }))
+                    .is_ok()
+                })
+            };
+            let mut retired_seen = 0u64;
+            // Batches are pipelined: each call returns once the previous batch
+            // has finished. Rate is timed from the end of the first batch so
+            // kernel warm-up is excluded.
+            let mut batches = 0u64;
+            let mut first_batch_done: Option<Instant> = None;
+            let mut seeds_after_first = 0u64;
+            let mut throttled = false;
+            let mut failure: Option<String> = None;
             while !stop.load(Ordering::Relaxed) {
-                let counter_start = counter.fetch_add(cfg.batch_size as u64, Ordering::Relaxed);
+                let mask = retired.load(Ordering::Relaxed);
+                if mask != retired_seen {
+                    if let Err(e) = pipeline.set_retired_patterns(mask) {
+                        failure = Some(e.to_string());
+                        break;
+                    }
+                    retired_seen = mask;
+                }
+                // Interactive mode resizes batches, so reserve each one's range.
+                let batch_size = pipeline.batch_size() as u64;
+                let counter_start = counter.fetch_add(batch_size, Ordering::Relaxed);
+                let duty = gpu_duty.percent(device_index);
+                let batch_start = Instant::now();
+                // The pipeline searches the whole range, in smaller batches
+                // if the device runs out of resources.
+                let checked_before = pipeline.addresses_checked();
                 let batch = match pipeline.run_batch_with_counter(counter_start) {
                     Ok(r) => r,
                     Err(e) => {
-                        let _ = wtx.send(WorkerMsg::Error {
-                            device: device_index,
-                            message: e.to_string(),
-                        });
+                        failure = Some(e.to_string());
                         break;
                     }
                 };
-                total_checked.fetch_add(
-                    (cfg.batch_size as u64) * (cfg.num_indices as u64),
-                    Ordering::Relaxed,
-                );
-                for result in batch {
-                    if wtx
-                        .send(WorkerMsg::Hit(Hit {
-                            address: result.address,
-                            mnemonic: result.mnemonic,
-                            entropy: result.entropy,
-                            address_index: result.address_index,
-                            pattern_index: result.pattern_index,
-                            device_label: format!("gpu:{device_index}"),
-                        }))
-                        .is_err()
-                    {
-                        stop.store(true, Ordering::Relaxed);
-                        return;
+                let checked = pipeline.addresses_checked() - checked_before;
+                total_checked.fetch_add(checked, Ordering::Relaxed);
+                batches += 1;
+                match batches {
+                    1 => {}
+                    2 => first_batch_done = Some(Instant::now()),
+                    _ => seeds_after_first += checked / cfg.num_indices.max(1) as u64,
+                }
+                if !send_hits(batch) {
+                    stop.store(true, Ordering::Relaxed);
+                    return;
+                }
+                if duty < 100 {
+                    // Drain the in-flight batch so the device is really idle
+                    // during the gap, then sleep to hold the duty cycle.
+                    throttled = true;
+                    match pipeline.finish() {
+                        Ok(batch) => {
+                            if !send_hits(batch) {
+                                stop.store(true, Ordering::Relaxed);
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            failure = Some(e.to_string());
+                            break;
+                        }
+                    }
+                    pause(
+                        gpu_duty.pause_after(device_index, batch_start.elapsed()),
+                        &stop,
+                    );
+                }
+            }
+            // Collect the batch still in flight so its hits are not lost.
+            if failure.is_none() {
+                match pipeline.finish() {
+                    Ok(batch) => {
+                        if !send_hits(batch) {
+                            return;
+                        }
                     }
+                    Err(e) => failure = Some(e.to_string()),
                 }
             }
+            if let Some(message) = failure {
+                let _ = wtx.send(WorkerMsg::Error {
+                    device: device_index,
+                    message,
+                });
+            }
+            // A throttled run says nothing about the device's full rate.
+            let measured = first_batch_done.filter(|_| !throttled).and_then(|t| {
+                let secs = t.elapsed().as_secs_f64();
+                (secs >= MIN_CALIBRATION_SECS).then(|| {
+                    let info = pipeline.device_info();
+                    Calibration::new(
+                        &info.device_name,
+                        &info.driver_version,
+                        cfg.num_indices,
+                        seeds_after_first as f64 / secs,
+                        CalibrationSource::Search,
+                    )
+                })
+            });
             let _ = wtx.send(WorkerMsg::Stats {
                 dropped: pipeline.hits_dropped_total(),
+                measured,
             });
         });
         handles.push(handle);
@@ -434,16 +767,13 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
 
     let mut start: Option<Instant> = None;
     let mut last_report = Instant::now();
-    let mut found = 0usize;
     let mut dropped_total = 0u64;
+    let mut measured = Vec::new();
     let mut first_error: Option<String> = None;
     let mut workers_left = handles.len();
     let mut duration_armed = false;
-    let _ = tx.send(SearchEvent::Progress {
-        checked: 0,
-        rate: 0.0,
-        found: 0,
-    });
+    let mut recent = RecentRate::default();
+    let _ = tx.send(tally.progress(0, 0.0));
 
     loop {
         match wrx.recv_timeout(Duration::from_millis(200)) {
@@ -465,7 +795,7 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
                 }
             }
             Ok(WorkerMsg::Hit(hit)) => {
-                accept_hit(hit, &tx, &mut found, req.max_results, &stop);
+                accept_hit(hit, req, &tx, &mut tally, &stop);
             }
             Ok(WorkerMsg::Error { device, message }) => {
                 if first_error.is_none() {
@@ -476,22 +806,25 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
                     duration_armed = true;
                 }
             }
-            Ok(WorkerMsg::Stats { dropped }) => {
+            Ok(WorkerMsg::Stats {
+                dropped,
+                measured: m,
+            }) => {
                 dropped_total = dropped_total.saturating_add(dropped);
+                measured.extend(m);
             }
             Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
             Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
         }
         if last_report.elapsed().as_secs_f64() >= 0.2 {
             let checked = total_checked.load(Ordering::Relaxed);
+            // Includes duty-cycle gaps, so it is the effective throttled rate.
             let rate = start.map_or(0.0, |t| {
-                checked as f64 / t.elapsed().as_secs_f64().max(0.001)
-            });
-            let _ = tx.send(SearchEvent::Progress {
-                checked,
-                rate,
-                found,
+                recent
+                    .update(Instant::now(), checked)
+                    .unwrap_or_else(|| checked as f64 / t.elapsed().as_secs_f64().max(0.001))
             });
+            let _ = tx.send(tally.progress(checked, rate));
             last_report = Instant::now();
         }
     }
@@ -500,6 +833,10 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
         let _ = h.join();
     }
 
+    // Interactive mode keeps batches small on purpose, so its rate understates the device.
+    if req.save_rates && req.kernel_latency.is_none() {
+        save_calibration(measured, &tx);
+    }
     if dropped_total > 0 {
         let _ = tx.send(SearchEvent::Dropped {
             count: dropped_total,
@@ -511,7 +848,8 @@ fn run_gpu(req: &SearchRequest, tx: Sender<SearchEvent>, stop: Arc<AtomicBool>)
     }
     let _ = tx.send(SearchEvent::Done {
         checked: total_checked.load(Ordering::Relaxed),
-        found,
+        found: tally.found,
+        rejected: tally.rejected,
         elapsed: start.map_or(Duration::ZERO, |t| t.elapsed()),
     });
 }
@@ -539,6 +877,23 @@ mod tests {
         assert!(validate_pattern("cafe", MatchType::Suffix, false).is_ok());
     }
 
+    #[test]
+    fn recent_rate_follows_the_last_window() {
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        let mut recent = RecentRate::default();
+        assert_eq!(recent.update(at(0), 0), None);
+        // Full speed: 1000/s
+        for s in 1..=10 {
+            assert_eq!(recent.update(at(s), s * 1000), Some(1000.0));
+        }
+        // Throttled to 400/s: the window forgets the full-speed part
+        for s in 11..=30 {
+            recent.update(at(s), 10_000 + (s - 10) * 400);
+        }
+        assert_eq!(recent.update(at(31), 18_400), Some(400.0));
+    }
+
     #[test]
     fn rejects_zero_batch_size() {
         let req = SearchRequest {
@@ -550,6 +905,12 @@ mod tests {
             duration: None,
             backend: Backend::Cpu,
             batch_size: Some(0),
+            kernel_latency: None,
+            per_pattern: None,
+            exclude: Vec::new(),
+            cpu_limits: CpuLimits::default(),
+            gpu_duty: GpuDuty::default(),
+            save_rates: false,
         };
         assert!(req.validate().is_err());
         let ok = SearchRequest {
@@ -558,4 +919,118 @@ mod tests {
         };
         assert!(ok.validate().is_ok());
     }
+
+    #[test]
+    fn excluded_hit_is_counted_not_sent() {
+        let req = SearchRequest {
+            patterns: vec!["9err".into()],
+            match_type: MatchType::Prefix,
+            ignore_case: false,
+            max_results: 1,
+            num_indices: 1,
+            duration: None,
+            backend: Backend::Cpu,
+            batch_size: None,
+            kernel_latency: None,
+            per_pattern: None,
+            exclude: vec![Exclusion::parse("1111").unwrap()],
+            cpu_limits: CpuLimits::default(),
+            gpu_duty: GpuDuty::default(),
+            save_rates: false,
+        };
+        let hit = Hit {
+            address: "9err1111xyz".into(),
+            mnemonic: Secret::new(String::new()),
+            entropy: Secret::new([0u8; 32]),
+            address_index: 0,
+            pattern_index: 0,
+            device_label: "cpu".into(),
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let stop = AtomicBool::new(false);
+        let mut tally = Tally::new(&req);
+        accept_hit(hit, &req, &tx, &mut tally, &stop);
+        assert_eq!((tally.found, tally.rejected), (0, 1));
+        assert!(rx.try_recv().is_err());
+        assert!(!stop.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn per_pattern_quota_sets_target_and_retires() {
+        let req = SearchRequest {
+            patterns: vec!["9err".into(), "9ego".into(), "9fun".into()],
+            match_type: MatchType::Prefix,
+            ignore_case: false,
+            max_results: 1,
+            num_indices: 1,
+            duration: None,
+            backend: Backend::Cpu,
+            batch_size: None,
+            kernel_latency: None,
+            per_pattern: Some(2),
+            exclude: Vec::new(),
+            cpu_limits: CpuLimits::default(),
+            gpu_duty: GpuDuty::default(),
+            save_rates: false,
+        };
+        assert_eq!(req.target(), 6);
+        assert!(SearchRequest {
+            per_pattern: Some(0),
+            ..req.clone()
+        }
+        .validate()
+        .is_err());
+
+        let (patterns, map) = compiled_patterns(&req, 0b101);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].pattern(), "9ego");
+        assert_eq!(map, vec![1]);
+    }
+
+    #[test]
+    fn quota_hit_retires_pattern() {
+        let entropy = [0u8; 32];
+        let ours =
+            erg_vanity_cpu::generate_address_from_entropy(&entropy, Network::Mainnet).unwrap();
+        let req = SearchRequest {
+            patterns: vec!["9zzz".into(), "9".into()],
+            match_type: MatchType::Prefix,
+            ignore_case: false,
+            max_results: 1,
+            num_indices: 1,
+            duration: None,
+            backend: Backend::Cpu,
+            batch_size: None,
+            kernel_latency: None,
+            per_pattern: Some(1),
+            exclude: Vec::new(),
+            cpu_limits: CpuLimits::default(),
+            gpu_duty: GpuDuty::default(),
+            save_rates: false,
+        };
+        let hit = Hit {
+            address: ours.address,
+            mnemonic: ours.mnemonic,
+            entropy: Secret::new(entropy),
+            address_index: 0,
+            pattern_index: 1,
+            device_label: "cpu".into(),
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let stop = AtomicBool::new(false);
+        let mut tally = Tally::new(&req);
+        accept_hit(hit.clone(), &req, &tx, &mut tally, &stop);
+        assert!(matches!(rx.try_recv(), Ok(SearchEvent::Hit(_))));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SearchEvent::Retired { pattern_index: 1 })
+        ));
+        assert_eq!(tally.retired.load(Ordering::Relaxed), 0b10);
+        assert!(!stop.load(Ordering::Relaxed));
+
+        // A late hit for the retired pattern is ignored.
+        accept_hit(hit, &req, &tx, &mut tally, &stop);
+        assert_eq!(tally.per_pattern, vec![0, 1]);
+        assert!(rx.try_recv().is_err());
+    }
 }
@@ -213,6 +213,71 @@ inline int base58_check_prefix_global_icase(
     return 1;
 }
 
+// Base58 digits of a 38-byte address after its leading zero bytes, least
+// significant first (same layout as the prefix checks above).
+// Returns the digit count, or -1 if it would not fit in 53 digits.
+inline int base58_address_digits(
+    __private const uchar* addr_bytes,
+    int leading_zeros,
+    __private uchar* buf                // 53 digits out
+) {
+    int buf_len = 0;
+    for (int i = leading_zeros; i < 38; i++) {
+        int carry = (int)addr_bytes[i];
+        for (int j = 0; j < buf_len; j++) {
+            carry += (int)buf[j] * 256;
+            buf[j] = (uchar)(carry % 58);
+            carry /= 58;
+        }
+        while (carry > 0) {
+            if (buf_len >= 53) return -1;
+            buf[buf_len++] = (uchar)(carry % 58);
+            carry /= 58;
+        }
+    }
+    return buf_len;
+}
+
+// Prefix check against digits from base58_address_digits, so several
+// patterns can share one conversion. Same result as base58_check_prefix
+// (or its icase variant when icase is set; prefix must then be lowercase).
+// Meant for compile-time constant lengths: the loops unroll and stop at
+// the first wrong digit.
+inline int base58_digits_match_prefix(
+    __private const uchar* buf,
+    int buf_len,
+    int leading_zeros,
+    __global const char* prefix,
+    int prefix_len,
+    int icase
+) {
+    int prefix_ones = 0;
+    while (prefix_ones < prefix_len && prefix[prefix_ones] == '1')
+        prefix_ones++;
+
+    if (prefix_ones == prefix_len)
+        return (leading_zeros >= prefix_ones) ? 1 : 0;
+
+    if (leading_zeros != prefix_ones || buf_len < 0)
+        return 0;
+
+    for (int i = prefix_ones; i < prefix_len; i++) {
+        char expected = prefix[i];
+        int digit_idx = buf_len - 1 - (i - prefix_ones);
+        if (digit_idx < 0)
+            return 0;
+        if (icase) {
+            if (to_lower(BASE58_ALPHABET[buf[digit_idx]]) != expected)
+                return 0;
+        } else {
+            uchar decoded = BASE58_DECODE[(int)expected];
+            if (decoded == 0xFFu || buf[digit_idx] != decoded)
+                return 0;
+        }
+    }
+    return 1;
+}
+
 // Full Base58 encode for 38-byte address
 // Returns encoded length (typically 51 chars for Ergo mainnet P2PK)
 inline int base58_encode_address(
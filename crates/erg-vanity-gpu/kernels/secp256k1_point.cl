@@ -407,6 +407,93 @@ inline int pt_to_compressed_pubkey(__private uchar* pubkey, __private const uint
     return 0;
 }
 
+// Compressed pubkey from a Jacobian point and a precomputed 1/Z.
+inline void pt_to_compressed_pubkey_zinv(
+    __private uchar* pubkey,
+    __private const uint* p,
+    __private const uint* z_inv
+) {
+    uint z_inv2[8], z_inv3[8], x[8], y[8];
+    fe_sqr(z_inv2, z_inv);
+    fe_mul(z_inv3, z_inv2, z_inv);
+    fe_mul(x, p, z_inv2);
+    fe_mul(y, p + 8, z_inv3);
+
+    pubkey[0] = (y[0] & 1u) ? (uchar)0x03 : (uchar)0x02;
+    fe_to_bytes(pubkey + 1, x);
+}
+
+inline void fe_load_local(__private uint* r, __local const uint* a) {
+    for (int i = 0; i < 8; i++) r[i] = a[i];
+}
+
+inline void fe_store_local(__local uint* r, __private const uint* a) {
+    for (int i = 0; i < 8; i++) r[i] = a[i];
+}
+
+// Montgomery batch inversion across the work-group: r = 1/z for every work item,
+// with one fe_inv per group instead of one per item.
+//
+// Prefix and suffix products are built with a Hillis-Steele scan (log2(n) fe_mul
+// per item), then 1/z_i = P_(i-1) * S_(i+1) / P_n.
+//
+// Contains barriers: every work item in the group must call it, in the same order.
+// Items with nothing to invert pass z = 1 (z must be non-zero).
+// pre/suf: __local uint[get_local_size(0) * 8]; total: __local uint[8].
+inline void fe_inv_batch_wg(
+    __private uint* r,
+    __private const uint* z,
+    __local uint* pre,
+    __local uint* suf,
+    __local uint* total
+) {
+    uint lid = (uint)get_local_id(0);
+    uint n = (uint)get_local_size(0);
+    uint a[8], b[8], own[8];
+
+    fe_store_local(pre + lid * 8u, z);
+    fe_store_local(suf + lid * 8u, z);
+
+    for (uint off = 1u; off < n; off <<= 1) {
+        barrier(CLK_LOCAL_MEM_FENCE);
+        int has_pre = lid >= off;
+        int has_suf = lid + off < n;
+        if (has_pre) fe_load_local(a, pre + (lid - off) * 8u);
+        if (has_suf) fe_load_local(b, suf + (lid + off) * 8u);
+        barrier(CLK_LOCAL_MEM_FENCE);
+        if (has_pre) {
+            fe_load_local(own, pre + lid * 8u);
+            fe_mul(own, own, a);
+            fe_store_local(pre + lid * 8u, own);
+        }
+        if (has_suf) {
+            fe_load_local(own, suf + lid * 8u);
+            fe_mul(own, own, b);
+            fe_store_local(suf + lid * 8u, own);
+        }
+    }
+    barrier(CLK_LOCAL_MEM_FENCE);
+
+    if (lid == 0u) {
+        fe_load_local(a, pre + (n - 1u) * 8u);
+        fe_inv(b, a);
+        fe_store_local(total, b);
+    }
+    barrier(CLK_LOCAL_MEM_FENCE);
+
+    fe_load_local(r, total);
+    if (lid > 0u) {
+        fe_load_local(a, pre + (lid - 1u) * 8u);
+        fe_mul(r, r, a);
+    }
+    if (lid + 1u < n) {
+        fe_load_local(b, suf + (lid + 1u) * 8u);
+        fe_mul(r, r, b);
+    }
+    // Scratch is reused by the next call.
+    barrier(CLK_LOCAL_MEM_FENCE);
+}
+
 // Private key (32 bytes) → compressed pubkey (33 bytes).
 inline int priv_to_compressed_pubkey(
     __private const uchar* privkey,
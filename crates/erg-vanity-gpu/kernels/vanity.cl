@@ -83,6 +83,27 @@ __kernel void vanity_seed(
 
 // BIP32 + k·G + address match. Seeds come from vanity_seed.
 // First match wins by (address_index ascending, pattern list order).
+//
+// Affine conversion shares one field inversion per work-group per address index
+// (fe_inv_batch_wg). That has barriers, so every work item runs all num_indices
+// iterations; finished or invalid items pass z = 1 and skip the rest.
+// Host caps the local size at VANITY_MAX_WG.
+//
+// With VANITY_SPECIALIZED defined, the host has prepended the shape of the
+// pattern set as constants (VANITY_NUM_PATTERNS, VANITY_IGNORE_CASE,
+// VANITY_NUM_INDICES, VANITY_PATTERN_OFFSETS/LENS); only the pattern bytes
+// come from the arguments. Each address is converted to Base58 once and
+// checked against every pattern with fully unrolled compares.
+#define VANITY_MAX_WG 256
+
+#ifdef VANITY_SPECIALIZED
+#define SEARCH_NUM_PATTERNS VANITY_NUM_PATTERNS
+#define SEARCH_NUM_INDICES VANITY_NUM_INDICES
+#else
+#define SEARCH_NUM_PATTERNS num_patterns
+#define SEARCH_NUM_INDICES num_indices
+#endif
+
 __kernel void vanity_search(
     __global const uchar* salt,
     ulong counter_start,
@@ -98,6 +119,10 @@ __kernel void vanity_search(
     uint max_hits,
     __global const uint* comb
 ) {
+    __local uint z_pre[VANITY_MAX_WG * 8];
+    __local uint z_suf[VANITY_MAX_WG * 8];
+    __local uint z_total[8];
+
     uint gid = get_global_id(0);
 
     uchar seed[64];
@@ -105,47 +130,73 @@ __kernel void vanity_search(
     for (int i = 0; i < 64; i++) seed[i] = in[i];
 
     // Step 3: Derive to external chain m/44'/429'/0'/0 (done ONCE, amortizes cost)
+    // Invalid keys (astronomically rare) skip the work but still join the barriers.
+    int done = 0;
     uchar external_key[32], external_chain_code[32];
     if (bip32_derive_ergo_external_chain_comb(seed, external_key, external_chain_code, comb) != 0) {
-        // Invalid key (astronomically rare), skip this work item
-        return;
+        done = 1;
     }
 
     // Parent pubkey is identical for every address index under this seed.
     uchar external_pub[33];
-    if (priv_to_compressed_pubkey_comb(external_key, external_pub, comb) != 0) {
-        return;
+    if (!done && priv_to_compressed_pubkey_comb(external_key, external_pub, comb) != 0) {
+        done = 1;
     }
 
     // Step 4-6: Loop over address indices (outer) and patterns (inner)
     // First match wins by (address_index ascending, pattern list order)
-    for (uint addr_idx = 0; addr_idx < num_indices; addr_idx++) {
+    for (uint addr_idx = 0; addr_idx < SEARCH_NUM_INDICES; addr_idx++) {
         // Derive key for this address index: m/44'/429'/0'/0/<addr_idx>
-        uchar private_key[32];
-        if (bip32_derive_address_index_from_pub(
-                external_key, external_chain_code, external_pub, addr_idx, private_key
-            ) != 0) {
-            continue;  // Skip invalid (astronomically rare)
+        int live = !done;
+        uint point[24];
+        if (live) {
+            uchar private_key[32];
+            if (bip32_derive_address_index_from_pub(
+                    external_key, external_chain_code, external_pub, addr_idx, private_key
+                ) != 0) {
+                live = 0;  // Skip invalid (astronomically rare)
+            } else {
+                // Private key → public key (Jacobian)
+                uint key_limbs[8];
+                sc_from_bytes(key_limbs, private_key);
+                pt_mul_generator_comb(point, key_limbs, comb);
+                if (pt_is_infinity(point)) live = 0;
+            }
         }
 
-        // Private key → public key
-        uint key_limbs[8];
-        sc_from_bytes(key_limbs, private_key);
-
-        uint point[24];
-        pt_mul_generator_comb(point, key_limbs, comb);
+        uint z[8], z_inv[8];
+        if (live) {
+            fe_copy(z, point + 16);
+        } else {
+            fe_one(z);
+        }
+        fe_inv_batch_wg(z_inv, z, z_pre, z_suf, z_total);
+        if (!live) continue;
 
         uchar pubkey[33];
-        if (pt_to_compressed_pubkey(pubkey, point) != 0) {
-            continue;  // Point at infinity (shouldn't happen)
-        }
+        pt_to_compressed_pubkey_zinv(pubkey, point, z_inv);
 
         // Build Ergo address
         uchar addr_bytes[38];
         build_ergo_address(pubkey, addr_bytes);
 
         // Check each pattern (inner loop)
-        for (uint p = 0; p < num_patterns; p++) {
+#ifdef VANITY_SPECIALIZED
+        int leading_zeros = 0;
+        while (leading_zeros < 38 && addr_bytes[leading_zeros] == 0u)
+            leading_zeros++;
+        uchar digits[53];
+        int num_digits = base58_address_digits(addr_bytes, leading_zeros, digits);
+        #pragma unroll
+#endif
+        for (uint p = 0; p < SEARCH_NUM_PATTERNS; p++) {
+#ifdef VANITY_SPECIALIZED
+            int match = base58_digits_match_prefix(
+                digits, num_digits, leading_zeros,
+                &patterns[VANITY_PATTERN_OFFSETS[p]],
+                (int)VANITY_PATTERN_LENS[p], VANITY_IGNORE_CASE
+            );
+#else
             uint offset = pattern_offsets[p];
             int len = (int)pattern_lens[p];
 
@@ -155,6 +206,7 @@ __kernel void vanity_search(
             } else {
                 match = base58_check_prefix_global(addr_bytes, &patterns[offset], len);
             }
+#endif
 
             if (match) {
                 // Match found! Recompute entropy (cheap vs PBKDF2) for CPU verify.
@@ -175,13 +227,17 @@ __kernel void vanity_search(
                     hits[hit_idx].address_index = addr_idx;
                     hits[hit_idx].pattern_index = p;
                 }
-                // First match wins - exit both loops
-                return;
+                // First match wins: no more patterns or indices for this seed.
+                done = 1;
+                break;
             }
         }
     }
 }
 
+#undef SEARCH_NUM_PATTERNS
+#undef SEARCH_NUM_INDICES
+
 // Simplified kernel for testing: derives address from given entropy
 // (No pattern matching, outputs intermediate values for verification)
 __kernel void vanity_derive_address(
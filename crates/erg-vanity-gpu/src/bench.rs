@@ -66,6 +66,12 @@ impl DeviceBenchStats {
     pub fn total_ns(&self) -> u64 {
         self.pbkdf2.total_ns + self.bip32.total_ns + self.secp256k1.total_ns + self.base58.total_ns
     }
+
+    /// Seeds/s with the four stages run back to back. Ignores launch overlap and hit readback.
+    pub fn seeds_per_sec(&self, cfg: &BenchConfig) -> f64 {
+        let seeds = (cfg.iters as u64) * (cfg.batch_size as u64);
+        seeds as f64 / (self.total_ns().max(1) as f64 / 1e9)
+    }
 }
 
 /// Run benchmark on a specific device.
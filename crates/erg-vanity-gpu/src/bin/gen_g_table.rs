@@ -1,6 +1,9 @@
 //! Generate G_TABLE (8-bit windowed k·G) and the 8-bit comb table.
 //!
 //! cargo run -p erg-vanity-gpu --bin gen_g_table
+//!
+//! Builds from the generic double-and-add, not `Point::mul_generator`, which
+//! reads the comb table this writes.
 
 use erg_vanity_crypto::secp256k1::field::FieldElement;
 use erg_vanity_crypto::secp256k1::point::Point;
@@ -51,7 +54,7 @@ fn point_limbs(i: u16) -> ([u32; 8], [u32; 8], [u32; 8]) {
     bytes[30] = (i >> 8) as u8;
     bytes[31] = i as u8;
     let scalar = Scalar::from_bytes(&bytes).expect("window scalar in range");
-    let point = Point::mul_generator(&scalar);
+    let point = Point::generator().mul_double_and_add(&scalar);
     let (x, y) = point.to_affine().expect("i*G is not infinity");
     let z_one = [1u32, 0, 0, 0, 0, 0, 0, 0];
     (
@@ -187,7 +190,7 @@ fn build_comb_table() -> Vec<u8> {
         },
     ] {
         let scalar = Scalar::from_bytes(&k_bytes).expect("test scalar in range");
-        let expected = Point::mul_generator(&scalar);
+        let expected = Point::generator().mul_double_and_add(&scalar);
         let mut acc = Point::INFINITY;
         for (window, &b) in k_bytes.iter().enumerate() {
             acc = acc.add(&load_affine(&buf, window, b as usize));
@@ -208,7 +211,9 @@ fn main() {
     fs::write(&g_dest, g_buf).unwrap();
     eprintln!("wrote {}", g_dest.display());
 
-    let comb_dest = dir.join("comb_table.bin");
+    let comb_dest = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../erg-vanity-crypto/src/secp256k1")
+        .join("comb_table.bin");
     let comb = build_comb_table();
     fs::write(&comb_dest, &comb).unwrap();
     eprintln!("wrote {} ({} bytes)", comb_dest.display(), comb.len());
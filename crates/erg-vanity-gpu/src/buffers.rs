@@ -3,10 +3,13 @@
 //! Pre-allocated buffers for vanity address generation pipeline.
 
 use crate::context::{GpuContext, GpuError};
-use ocl::{Buffer, MemFlags};
+use ocl::{Buffer, Event, MemFlags, Queue};
 
-/// Maximum number of hits that can be stored per batch.
-pub const MAX_HITS: usize = 1024;
+/// Hits a batch can store before the pipeline grows its buffers.
+pub const DEFAULT_HIT_CAPACITY: usize = 1024;
+
+/// Upper bound on automatic hit buffer growth (64 MiB of `GpuHit`s per slot).
+pub const MAX_HIT_CAPACITY: usize = 1 << 20;
 
 /// Size of entropy in bytes (256-bit for 24-word mnemonic).
 pub const ENTROPY_SIZE: usize = 32;
@@ -52,6 +55,144 @@ impl GpuHit {
     }
 }
 
+/// Number of in-flight batches the pipeline double-buffers between.
+pub const BATCH_SLOTS: usize = 2;
+
+/// Largest batch whose seed and fully grown hit buffers, for every slot,
+/// fit in half of `global_mem_size`.
+pub fn max_batch_for_memory(global_mem_size: u64) -> usize {
+    let per_item = (BATCH_SLOTS * (SEED_SIZE + std::mem::size_of::<GpuHit>())) as u64;
+    (global_mem_size / 2 / per_item).clamp(1, usize::MAX as u64) as usize
+}
+
+/// Per-batch GPU buffers: one set per in-flight batch.
+pub struct BatchBuffers {
+    /// Hit buffer for matches (write-only from GPU)
+    pub hits: Buffer<GpuHit>,
+    /// Hits `hits` can hold
+    hit_capacity: usize,
+    /// Atomic hit counter (i32 to match kernel's `volatile int*`)
+    pub hit_count: Buffer<i32>,
+    /// PBKDF2 seeds (64 bytes per work item), written by `vanity_seed`
+    pub seeds: Buffer<u8>,
+}
+
+impl BatchBuffers {
+    fn new(queue: &Queue, batch_size: usize, hit_capacity: usize) -> Result<Self, GpuError> {
+        let hits = Self::alloc_hits(queue, hit_capacity)?;
+
+        // Hit counter (i32 to match kernel's `volatile int*`)
+        let hit_count = Buffer::<i32>::builder()
+            .queue(queue.clone())
+            .flags(MemFlags::new().read_write())
+            .len(1)
+            .build()?;
+
+        let seeds = Self::alloc_seeds(queue, batch_size)?;
+
+        Ok(Self {
+            hits,
+            hit_capacity,
+            hit_count,
+            seeds,
+        })
+    }
+
+    fn alloc_seeds(queue: &Queue, batch_size: usize) -> Result<Buffer<u8>, GpuError> {
+        Ok(Buffer::<u8>::builder()
+            .queue(queue.clone())
+            .flags(MemFlags::new().read_write())
+            .len(batch_size.max(1) * SEED_SIZE)
+            .build()?)
+    }
+
+    fn alloc_hits(queue: &Queue, capacity: usize) -> Result<Buffer<GpuHit>, GpuError> {
+        Ok(Buffer::<GpuHit>::builder()
+            .queue(queue.clone())
+            .flags(MemFlags::new().write_only())
+            .len(capacity.max(1))
+            .build()?)
+    }
+
+    /// Hits this slot can store per batch.
+    pub fn hit_capacity(&self) -> usize {
+        self.hit_capacity
+    }
+
+    /// Replace the hit buffer with one holding `capacity` hits.
+    ///
+    /// Only call while no batch is using this slot. The old buffer is wiped
+    /// before it is released. Kernels bound to the old buffer must be
+    /// re-pointed at `hits`.
+    pub fn grow_hits(&mut self, capacity: usize) -> Result<(), GpuError> {
+        let queue = self
+            .hits
+            .default_queue()
+            .cloned()
+            .ok_or_else(|| GpuError::Other("hit buffer has no queue".to_string()))?;
+        let hits = Self::alloc_hits(&queue, capacity)?;
+        self.hits.cmd().fill(GpuHit::default(), None).enq()?;
+        self.hits = hits;
+        self.hit_capacity = capacity;
+        Ok(())
+    }
+
+    /// Replace the seed buffer with one for `batch_size` work items.
+    ///
+    /// Same rules as `grow_hits`: the old buffer is wiped and kernels must
+    /// be re-pointed at `seeds`.
+    fn resize_seeds(&mut self, batch_size: usize) -> Result<(), GpuError> {
+        let queue = self
+            .seeds
+            .default_queue()
+            .cloned()
+            .ok_or_else(|| GpuError::Other("seed buffer has no queue".to_string()))?;
+        self.seeds.cmd().fill(0u8, None).enq()?;
+        // Release the old buffer first: it is what the device ran out of.
+        self.seeds = Self::alloc_seeds(&queue, 1)?;
+        self.seeds = Self::alloc_seeds(&queue, batch_size)?;
+        Ok(())
+    }
+
+    /// Enqueue a reset of the hit counter to 0 on the buffers' queue.
+    ///
+    /// Does not block, so it orders before the next kernels on that queue.
+    pub fn reset_hits(&self) -> Result<(), GpuError> {
+        self.hit_count.cmd().fill(0i32, None).enq()?;
+        Ok(())
+    }
+
+    /// Read the hit count on `queue` once `after` has completed.
+    ///
+    /// The kernel counts every match, including those past the buffer's
+    /// capacity, so this is the batch's true hit count.
+    pub fn read_hit_count(&self, queue: &Queue, after: &Event) -> Result<u32, GpuError> {
+        let mut count = [0i32; 1];
+        self.hit_count
+            .read(&mut count[..])
+            .queue(queue)
+            .ewait(after)
+            .enq()?;
+        Ok(count[0] as u32)
+    }
+
+    /// Read hits on `queue`. Call after `read_hit_count`, which waited for the batch.
+    pub fn read_hits(&self, queue: &Queue, count: usize) -> Result<Vec<GpuHit>, GpuError> {
+        let count = count.min(self.hit_capacity);
+        let mut hits = vec![GpuHit::default(); count];
+        if count > 0 {
+            self.hits.read(&mut hits).queue(queue).enq()?;
+        }
+        Ok(hits)
+    }
+
+    fn wipe(&self) -> Result<(), GpuError> {
+        self.seeds.cmd().fill(0u8, None).enq()?;
+        self.hits.cmd().fill(GpuHit::default(), None).enq()?;
+        Ok(())
+    }
+}
+
 /// Pre-allocated GPU buffers for the vanity pipeline.
 pub struct GpuBuffers {
     /// Salt for entropy derivation (32 bytes, read-only)
@@ -62,19 +203,16 @@ pub struct GpuBuffers {
     pub pattern_offsets: Buffer<u32>,
     /// Length of each pattern
     pub pattern_lens: Buffer<u32>,
-    /// Hit buffer for matches (write-only from GPU)
-    pub hits: Buffer<GpuHit>,
-    /// Atomic hit counter (i32 to match kernel's `volatile int*`)
-    pub hit_count: Buffer<i32>,
-    /// PBKDF2 seeds (64 bytes per work item), written by `vanity_seed`
-    pub seeds: Buffer<u8>,
+    /// Seeds and hits of each in-flight batch
+    pub slots: [BatchBuffers; BATCH_SLOTS],
     /// Batch size this was allocated for
     batch_size: usize,
 }
 
 impl GpuBuffers {
-    /// Allocate buffers for a given batch size.
-    pub fn new(ctx: &GpuContext, batch_size: usize) -> Result<Self, GpuError> {
+    /// Allocate buffers for a given batch size, with room for `hit_capacity`
+    /// hits per in-flight batch.
+    pub fn new(ctx: &GpuContext, batch_size: usize, hit_capacity: usize) -> Result<Self, GpuError> {
         let queue = ctx.queue();
 
         // Salt buffer (32 bytes)
@@ -105,34 +243,17 @@ impl GpuBuffers {
             .len(MAX_PATTERNS)
             .build()?;
 
-        // Hit buffer
-        let hits = Buffer::<GpuHit>::builder()
-            .queue(queue.clone())
-            .flags(MemFlags::new().write_only())
-            .len(MAX_HITS)
-            .build()?;
-
-        // Hit counter (i32 to match kernel's `volatile int*`)
-        let hit_count = Buffer::<i32>::builder()
-            .queue(queue.clone())
-            .flags(MemFlags::new().read_write())
-            .len(1)
-            .build()?;
-
-        let seeds = Buffer::<u8>::builder()
-            .queue(queue.clone())
-            .flags(MemFlags::new().read_write())
-            .len(batch_size * SEED_SIZE)
-            .build()?;
+        let slots = [
+            BatchBuffers::new(queue, batch_size, hit_capacity)?,
+            BatchBuffers::new(queue, batch_size, hit_capacity)?,
+        ];
 
         Ok(Self {
             salt,
             patterns,
             pattern_offsets,
             pattern_lens,
-            hits,
-            hit_count,
-            seeds,
+            slots,
             batch_size,
         })
     }
@@ -200,27 +321,28 @@ impl GpuBuffers {
         Ok(patterns.len())
     }
 
-    /// Reset hit counter to 0.
-    pub fn reset_hits(&self) -> Result<(), GpuError> {
-        self.hit_count.write(&[0i32][..]).enq()?;
+    /// Overwrite the buffers that hold secrets (salt, PBKDF2 seeds, hit
+    /// entropy) with zeros and wait for the writes to land.
+    pub fn wipe_secrets(&self) -> Result<(), GpuError> {
+        self.salt.cmd().fill(0u8, None).enq()?;
+        for slot in &self.slots {
+            slot.wipe()?;
+        }
+        if let Some(queue) = self.salt.default_queue() {
+            queue.finish()?;
+        }
         Ok(())
     }
 
-    /// Read hit count from GPU.
-    pub fn read_hit_count(&self) -> Result<u32, GpuError> {
-        let mut count = [0i32; 1];
-        self.hit_count.read(&mut count[..]).enq()?;
-        Ok(count[0] as u32)
-    }
-
-    /// Read hits from GPU.
-    pub fn read_hits(&self, count: usize) -> Result<Vec<GpuHit>, GpuError> {
-        let count = count.min(MAX_HITS);
-        let mut hits = vec![GpuHit::default(); count];
-        if count > 0 {
-            self.hits.read(&mut hits).enq()?;
+    /// Reallocate every slot's seeds for a smaller `batch_size`. Only call
+    /// while no batch is in flight; kernels must be re-pointed at the new
+    /// seed buffers.
+    pub fn shrink(&mut self, batch_size: usize) -> Result<(), GpuError> {
+        for slot in &mut self.slots {
+            slot.resize_seeds(batch_size)?;
         }
-        Ok(hits)
+        self.batch_size = batch_size;
+        Ok(())
     }
 
     /// Get the batch size these buffers were allocated for.
@@ -233,6 +355,13 @@ impl GpuBuffers {
 mod tests {
     use super::*;
 
+    #[test]
+    fn batch_limit_leaves_half_of_memory() {
+        // 4 GiB: 2 slots x (64-byte seed + 64-byte hit) per item in 2 GiB
+        assert_eq!(max_batch_for_memory(4 << 30), 1 << 23);
+        assert_eq!(max_batch_for_memory(0), 1);
+    }
+
     #[test]
     fn test_gpu_hit_size() {
         // Ensure the struct is properly sized for GPU (64 bytes, aligned)
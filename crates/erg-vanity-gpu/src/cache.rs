@@ -0,0 +1,260 @@
+//! On-disk cache of compiled OpenCL program binaries.
+//!
+//! Building the full vanity program from source takes from a few seconds to
+//! over a minute depending on the driver. The binary returned by
+//! `clGetProgramInfo(CL_PROGRAM_BINARIES)` is stored under a key that hashes
+//! the source, device, driver version and build options, so a change to any of
+//! them misses the cache instead of loading a stale binary.
+//!
+//! File layout: 8-byte magic, Blake2b-256 of the binary, then the binary.
+//! A file that fails either check is deleted and the program is rebuilt.
+
+use erg_vanity_crypto::blake2b;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Overrides the cache directory. `off` disables the cache.
+pub const KERNEL_CACHE_ENV: &str = "ERG_VANITY_KERNEL_CACHE";
+
+const MAGIC: &[u8; 8] = b"ERGVKC01";
+const HEADER_LEN: usize = MAGIC.len() + 32;
+const EXTENSION: &str = "clbin";
+
+/// What a cached binary was built from.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheKey<'a> {
+    pub source: &'a str,
+    pub platform: &'a str,
+    pub device: &'a str,
+    pub driver_version: &'a str,
+    pub options: &'a str,
+}
+
+impl CacheKey<'_> {
+    /// Hex Blake2b-256 of every field, NUL-separated.
+    pub fn digest(&self) -> String {
+        let mut data = Vec::with_capacity(self.source.len() + 256);
+        data.extend_from_slice(MAGIC);
+        for field in [
+            self.platform,
+            self.device,
+            self.driver_version,
+            self.options,
+            self.source,
+        ] {
+            data.extend_from_slice(field.as_bytes());
+            data.push(0);
+        }
+        blake2b::digest(&data)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+/// Directory of cached program binaries.
+#[derive(Debug, Clone)]
+pub struct KernelCache {
+    dir: PathBuf,
+}
+
+impl KernelCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$ERG_VANITY_KERNEL_CACHE`, else `erg-vanity/kernels` in the per-user
+    /// cache dir. `None` when disabled or no home directory is known.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Ok(p) = std::env::var(KERNEL_CACHE_ENV) {
+            if p.eq_ignore_ascii_case("off") {
+                return None;
+            }
+            if !p.is_empty() {
+                return Some(PathBuf::from(p));
+            }
+        }
+        let dir = if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            PathBuf::from(local)
+        } else if let Ok(xdg) = std::env::var("XDG_CACHE_HOME") {
+            PathBuf::from(xdg)
+        } else {
+            PathBuf::from(std::env::var("HOME").ok()?).join(".cache")
+        };
+        Some(dir.join("erg-vanity").join("kernels"))
+    }
+
+    /// Cache at [`KernelCache::default_dir`].
+    pub fn open_default() -> Option<Self> {
+        Self::default_dir().map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.{EXTENSION}"))
+    }
+
+    /// The binary stored under `key`, if present and intact.
+    ///
+    /// A truncated or corrupted file is removed so the next build replaces it.
+    pub fn load(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        let data = fs::read(&path).ok()?;
+        match unwrap_entry(&data) {
+            Some(binary) => Some(binary.to_vec()),
+            None => {
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Store `binary` under `key`, replacing any previous entry.
+    ///
+    /// Writes to a temporary file and renames it, so a concurrent reader sees
+    /// either the old entry or the complete new one.
+    pub fn store(&self, key: &str, binary: &[u8]) -> Result<(), String> {
+        if binary.is_empty() {
+            return Err("empty program binary".into());
+        }
+        fs::create_dir_all(&self.dir).map_err(|e| format!("create {}: {e}", self.dir.display()))?;
+        let tmp = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+        let write = || -> std::io::Result<()> {
+            let mut f = fs::File::create(&tmp)?;
+            f.write_all(MAGIC)?;
+            f.write_all(&blake2b::digest(binary))?;
+            f.write_all(binary)?;
+            f.sync_all()?;
+            fs::rename(&tmp, self.path(key))
+        };
+        write().map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("write {}: {e}", self.path(key).display())
+        })
+    }
+
+    /// Drop the entry for `key` (e.g. after the driver rejected it).
+    pub fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+
+    /// Delete every cached binary. Returns how many were removed.
+    pub fn clear(&self) -> Result<usize, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("read {}: {e}", self.dir.display())),
+        };
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let ours = path
+                .extension()
+                .is_some_and(|ext| ext == EXTENSION || ext == "tmp");
+            if ours {
+                fs::remove_file(&path).map_err(|e| format!("remove {}: {e}", path.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// The binary inside a cache file, if the magic and checksum match.
+fn unwrap_entry(data: &[u8]) -> Option<&[u8]> {
+    if data.len() <= HEADER_LEN || &data[..MAGIC.len()] != MAGIC {
+        return None;
+    }
+    let (checksum, binary) = data[MAGIC.len()..].split_at(32);
+    (blake2b::digest(binary)[..] == *checksum).then_some(binary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> KernelCache {
+        let dir = std::env::temp_dir().join(format!(
+            "erg-vanity-kernel-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        KernelCache::new(dir)
+    }
+
+    fn key() -> CacheKey<'static> {
+        CacheKey {
+            source: "__kernel void k() {}",
+            platform: "Test Platform",
+            device: "Test GPU",
+            driver_version: "1.0",
+            options: "-cl-std=CL1.2",
+        }
+    }
+
+    #[test]
+    fn key_changes_with_every_field() {
+        let base = key().digest();
+        assert_eq!(base.len(), 64);
+        assert_eq!(base, key().digest());
+        let variants = [
+            CacheKey {
+                source: "__kernel void k2() {}",
+                ..key()
+            },
+            CacheKey {
+                device: "Other GPU",
+                ..key()
+            },
+            CacheKey {
+                driver_version: "1.1",
+                ..key()
+            },
+            CacheKey {
+                options: "-cl-std=CL2.0",
+                ..key()
+            },
+            CacheKey {
+                platform: "Other Platform",
+                ..key()
+            },
+        ];
+        for v in variants {
+            assert_ne!(v.digest(), base);
+        }
+    }
+
+    #[test]
+    fn store_load_and_clear() {
+        let cache = temp_cache("roundtrip");
+        let k = key().digest();
+        assert_eq!(cache.load(&k), None);
+        cache.store(&k, b"binary blob").unwrap();
+        assert_eq!(cache.load(&k).as_deref(), Some(&b"binary blob"[..]));
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.load(&k), None);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn corrupted_entry_is_dropped() {
+        let cache = temp_cache("corrupt");
+        let k = key().digest();
+        cache.store(&k, b"binary blob").unwrap();
+        let path = cache.path(&k);
+        let mut data = fs::read(&path).unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        fs::write(&path, &data).unwrap();
+
+        assert_eq!(cache.load(&k), None);
+        assert!(!path.exists());
+
+        fs::write(&path, &data[..HEADER_LEN - 1]).unwrap();
+        assert_eq!(cache.load(&k), None);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+}
@@ -1,18 +1,14 @@
-//! Host-side 8-bit fixed-base comb table for k·G.
+//! Host-side upload of the 8-bit fixed-base comb table for k·G.
 //!
-//! Layout: 32 windows × 256 entries × 16 little-endian u32 limbs (affine X||Y).
-//! Window 0 is the MSB of `sc_to_bytes`. Entry 0 is unused (infinity).
-//!
-//! Regenerate with: cargo run -p erg-vanity-gpu --bin gen_g_table
+//! The table lives in `erg_vanity_crypto::secp256k1::comb`, which the CPU
+//! `mul_generator` also uses. Window 0 is the MSB of `sc_to_bytes`.
 
 use crate::context::GpuError;
 use ocl::{flags::MemFlags, Buffer, Queue};
 
-pub const COMB_WINDOWS: usize = 32;
-pub const COMB_ENTRIES: usize = 256;
-pub const COMB_XY_LIMBS: usize = 16;
-pub const COMB_TABLE_U32S: usize = COMB_WINDOWS * COMB_ENTRIES * COMB_XY_LIMBS;
-pub const COMB_TABLE_BYTES: &[u8] = include_bytes!("../kernels/comb_table.bin");
+pub use erg_vanity_crypto::secp256k1::comb::{
+    COMB_ENTRIES, COMB_TABLE_BYTES, COMB_TABLE_U32S, COMB_WINDOWS, COMB_XY_LIMBS,
+};
 
 /// Uploaded `__global` comb table (512 KiB).
 pub struct CombTableBuffer {
@@ -20,15 +16,7 @@ pub struct CombTableBuffer {
 }
 
 pub fn load_comb_table() -> Vec<u32> {
-    assert_eq!(
-        COMB_TABLE_BYTES.len(),
-        COMB_TABLE_U32S * 4,
-        "comb_table.bin size"
-    );
-    COMB_TABLE_BYTES
-        .chunks_exact(4)
-        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
-        .collect()
+    erg_vanity_crypto::secp256k1::comb::comb_table_words()
 }
 
 impl CombTableBuffer {
@@ -14,27 +14,88 @@ pub enum GpuError {
     NoDevices,
     #[error("Device index {0} out of range (found {1} devices)")]
     DeviceIndexOutOfRange(usize, usize),
+    #[error(
+        "{device} (driver {driver}) failed the GPU self-test: {detail}; \
+         its kernels give wrong results, so it will not be used"
+    )]
+    SelfTest {
+        device: String,
+        driver: String,
+        detail: String,
+    },
     #[error("OpenCL error: {0}")]
     Ocl(#[from] ocl::Error),
     #[error("{0}")]
     Other(String),
 }
 
-/// Information about an available GPU device.
+impl GpuError {
+    /// Whether the device ran out of memory or other resources, which a
+    /// smaller batch may avoid.
+    pub fn is_out_of_resources(&self) -> bool {
+        use ocl::core::Status;
+        match self {
+            GpuError::Ocl(e) => matches!(
+                e.api_status(),
+                Some(Status::CL_OUT_OF_RESOURCES | Status::CL_MEM_OBJECT_ALLOCATION_FAILURE)
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Kind of OpenCL device. Only GPUs are used unless a caller opts in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceKind {
+    Gpu,
+    /// CPU runtime such as POCL or the Intel CPU runtime.
+    Cpu,
+    /// FPGA or other accelerator.
+    Accelerator,
+}
+
+impl DeviceKind {
+    /// Enumeration order: GPUs first, so their indices do not depend on
+    /// which CPU runtimes are installed.
+    const ALL: [DeviceKind; 3] = [DeviceKind::Gpu, DeviceKind::Cpu, DeviceKind::Accelerator];
+
+    /// Short label for device lists.
+    pub fn label(self) -> &'static str {
+        match self {
+            DeviceKind::Gpu => "GPU",
+            DeviceKind::Cpu => "CPU",
+            DeviceKind::Accelerator => "accelerator",
+        }
+    }
+
+    fn ocl_type(self) -> DeviceType {
+        match self {
+            DeviceKind::Gpu => DeviceType::GPU,
+            DeviceKind::Cpu => DeviceType::CPU,
+            DeviceKind::Accelerator => DeviceType::ACCELERATOR,
+        }
+    }
+}
+
+/// Information about an available OpenCL device.
 #[derive(Clone, Debug)]
 pub struct DeviceInfo {
     /// Global index across all platforms (for CLI selection)
     pub global_idx: usize,
     /// Platform index
     pub platform_idx: usize,
-    /// Device index within GPU list for that platform
+    /// Device index within the platform's list of devices of this kind
     pub device_idx: usize,
+    /// GPU, CPU or accelerator
+    pub kind: DeviceKind,
     /// Platform name
     pub platform_name: String,
     /// Device name
     pub device_name: String,
     /// Device vendor
     pub vendor: String,
+    /// OpenCL driver version string
+    pub driver_version: String,
     /// Max compute units
     pub compute_units: u32,
     /// Max clock (MHz) when the OpenCL runtime reports it
@@ -48,6 +109,7 @@ pub struct DeviceInfo {
 }
 
 /// OpenCL GPU context.
+#[derive(Clone)]
 pub struct GpuContext {
     /// The OpenCL context
     context: Context,
@@ -69,7 +131,8 @@ impl GpuContext {
         Self::with_device(0)
     }
 
-    /// Create a GPU context with a specific device index (global index).
+    /// Create a context on a specific device by global index, as listed by
+    /// `enumerate_all_devices` (the GPUs keep the same indices in both lists).
     pub fn with_device(global_selection: usize) -> Result<Self, GpuError> {
         Self::with_device_impl(global_selection, false)
     }
@@ -81,7 +144,7 @@ impl GpuContext {
 
     /// Internal implementation for creating GPU context with optional profiling.
     fn with_device_impl(global_selection: usize, enable_profiling: bool) -> Result<Self, GpuError> {
-        let devices = Self::enumerate_devices()?;
+        let devices = Self::enumerate_all_devices()?;
         let info = devices
             .get(global_selection)
             .ok_or(GpuError::DeviceIndexOutOfRange(
@@ -95,8 +158,8 @@ impl GpuContext {
             .get(info.platform_idx)
             .ok_or(GpuError::NoPlatforms)?;
 
-        let gpus = Device::list(platform, Some(DeviceType::GPU))?;
-        let device = gpus
+        let of_kind = Device::list(platform, Some(info.kind.ocl_type()))?;
+        let device = of_kind
             .get(info.device_idx)
             .cloned()
             .ok_or(GpuError::NoDevices)?;
@@ -123,6 +186,16 @@ impl GpuContext {
 
     /// Enumerate all available GPU devices.
     pub fn enumerate_devices() -> Result<Vec<DeviceInfo>, GpuError> {
+        Self::enumerate_kinds(&[DeviceKind::Gpu])
+    }
+
+    /// Enumerate every OpenCL device: the GPUs of `enumerate_devices`, then
+    /// CPU and accelerator devices (e.g. POCL) with the following indices.
+    pub fn enumerate_all_devices() -> Result<Vec<DeviceInfo>, GpuError> {
+        Self::enumerate_kinds(&DeviceKind::ALL)
+    }
+
+    fn enumerate_kinds(kinds: &[DeviceKind]) -> Result<Vec<DeviceInfo>, GpuError> {
         let platforms = Platform::list();
         if platforms.is_empty() {
             return Err(GpuError::NoPlatforms);
@@ -131,15 +204,18 @@ impl GpuContext {
         let mut out = Vec::new();
         let mut global_idx = 0usize;
 
-        for (platform_idx, platform) in platforms.iter().enumerate() {
+        for (kind, (platform_idx, platform)) in kinds
+            .iter()
+            .flat_map(|&kind| platforms.iter().enumerate().map(move |p| (kind, p)))
+        {
             let platform_name = platform
                 .info(ocl::enums::PlatformInfo::Name)
                 .map(|i| i.to_string())
                 .unwrap_or_else(|_| "Unknown".into());
 
-            let gpus = Device::list(*platform, Some(DeviceType::GPU)).unwrap_or_default();
+            let of_kind = Device::list(*platform, Some(kind.ocl_type())).unwrap_or_default();
 
-            for (device_idx, device) in gpus.iter().enumerate() {
+            for (device_idx, device) in of_kind.iter().enumerate() {
                 let device_name = device
                     .info(ocl::enums::DeviceInfo::Name)
                     .map(|i| i.to_string())
@@ -150,6 +226,11 @@ impl GpuContext {
                     .map(|i| i.to_string())
                     .unwrap_or_else(|_| "Unknown".into());
 
+                let driver_version = device
+                    .info(ocl::enums::DeviceInfo::DriverVersion)
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|_| "Unknown".into());
+
                 let compute_units = device
                     .info(ocl::enums::DeviceInfo::MaxComputeUnits)
                     .map(|i| match i {
@@ -194,9 +275,11 @@ impl GpuContext {
                     global_idx,
                     platform_idx,
                     device_idx,
+                    kind,
                     platform_name: platform_name.clone(),
                     device_name,
                     vendor,
+                    driver_version,
                     compute_units,
                     max_clock_mhz,
                     max_work_group_size,
@@ -233,18 +316,30 @@ impl GpuContext {
 
     /// Get recommended work group size for this device.
     pub fn recommended_work_group_size(&self) -> usize {
-        let max = self.info.max_work_group_size.max(1);
-        std::cmp::min(256, max)
+        self.info.recommended_work_group_size()
     }
 
     /// Get recommended global work size (batch size).
+    pub fn recommended_batch_size(&self) -> usize {
+        self.info.recommended_batch_size()
+    }
+}
+
+impl DeviceInfo {
+    /// Recommended work group size for this device.
+    pub fn recommended_work_group_size(&self) -> usize {
+        let max = self.max_work_group_size.max(1);
+        std::cmp::min(256, max)
+    }
+
+    /// Recommended global work size (batch size) before autotuning.
     ///
     /// Based on compute units and a multiplier for good occupancy.
     pub fn recommended_batch_size(&self) -> usize {
         // Aim for good occupancy: compute_units * waves_per_cu * work_group_size
         let work_group_size = self.recommended_work_group_size();
         let waves_per_cu = 8; // Keep a 3080-class GPU near 256k work items
-        let batch = (self.info.compute_units as usize).max(1) * waves_per_cu * work_group_size;
+        let batch = (self.compute_units as usize).max(1) * waves_per_cu * work_group_size;
 
         // Round up to nice power of 2, cap at 1M
         let batch = batch.next_power_of_two();
@@ -256,8 +351,9 @@ impl std::fmt::Display for DeviceInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[{}] {} - {} ({} CUs, {} MB global, {} KB local)",
+            "[{}] {} · {} - {} ({} CUs, {} MB global, {} KB local)",
             self.global_idx,
+            self.kind.label(),
             self.vendor.trim(),
             self.device_name.trim(),
             self.compute_units,
@@ -278,14 +374,20 @@ pub(crate) fn gpu_tests_enabled() -> bool {
 /// Try to create a GPU context, returning None if tests are opted out or no device.
 ///
 /// GPU kernel tests are opt-in: set `ERG_RUN_GPU_TESTS=1` so CI stays explicit.
-/// Also catches panics from the OpenCL library (e.g., no ICD installed).
+/// Without a GPU the first CPU or accelerator device (e.g. POCL) is used, so
+/// the kernels can be tested on headless machines. Also catches panics from
+/// the OpenCL library (e.g., no ICD installed).
 #[cfg(test)]
 pub(crate) fn try_ctx() -> Option<GpuContext> {
     if !gpu_tests_enabled() {
         eprintln!("Skipping GPU test (set ERG_RUN_GPU_TESTS=1 to run OpenCL tests)");
         return None;
     }
-    match std::panic::catch_unwind(GpuContext::new) {
+    let first_device = || match GpuContext::enumerate_all_devices()?.first() {
+        Some(info) => GpuContext::with_device(info.global_idx),
+        None => Err(GpuError::NoDevices),
+    };
+    match std::panic::catch_unwind(first_device) {
         Ok(Ok(ctx)) => Some(ctx),
         Ok(Err(e)) => {
             eprintln!("Skipping GPU test (no OpenCL device available): {e}");
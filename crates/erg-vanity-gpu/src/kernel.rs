@@ -1,7 +1,11 @@
 //! OpenCL kernel compilation and execution.
 
+use crate::cache::{CacheKey, KernelCache};
 use crate::context::{GpuContext, GpuError};
+use crate::specialize::KernelSpec;
+use ocl::enums::{ProgramInfo, ProgramInfoResult};
 use ocl::Program;
+use std::ffi::CString;
 
 /// Kernel source files embedded at compile time.
 pub mod sources {
@@ -44,16 +48,23 @@ pub mod sources {
 }
 
 /// Compiled OpenCL program with kernels.
+#[derive(Clone)]
 pub struct GpuProgram {
     program: Program,
 }
 
 impl GpuProgram {
-    /// Compile a program from source.
+    /// Build a program from source, or load it from the kernel binary cache.
     ///
     /// Set `ERG_CL_VERBOSE=1` to enable NVIDIA compile diagnostics (-cl-nv-verbose).
-    /// This prints register usage, spills, and occupancy hints to stderr.
+    /// This prints register usage, spills, and occupancy hints to stderr, and
+    /// bypasses the cache so the build log is always produced.
     pub fn from_source(ctx: &GpuContext, source: &str) -> Result<Self, GpuError> {
+        Self::build(ctx, source, None)
+    }
+
+    /// `from_source`, reporting progress on stderr as the `name` kernel.
+    fn build(ctx: &GpuContext, source: &str, name: Option<&str>) -> Result<Self, GpuError> {
         let is_nvidia = ctx.info().vendor.to_uppercase().contains("NVIDIA");
         let verbose = std::env::var("ERG_CL_VERBOSE")
             .map(|v| v == "1")
@@ -65,19 +76,58 @@ impl GpuProgram {
             eprintln!("[diag] NVIDIA verbose mode enabled");
         }
 
+        let cache = if verbose {
+            None
+        } else {
+            KernelCache::open_default()
+        };
+        let info = ctx.info();
+        let key = CacheKey {
+            source,
+            platform: &info.platform_name,
+            device: &info.device_name,
+            driver_version: &info.driver_version,
+            options: &opts,
+        }
+        .digest();
+
+        if let Some(binary) = cache.as_ref().and_then(|c| c.load(&key)) {
+            match Self::from_binary(ctx, &binary, &opts) {
+                Ok(program) => {
+                    if let Some(name) = name {
+                        eprintln!("OpenCL {name} kernel loaded from cache.");
+                    }
+                    return Ok(program);
+                }
+                Err(e) => {
+                    eprintln!("Warning: cached OpenCL binary rejected ({e}); rebuilding");
+                    if let Some(cache) = &cache {
+                        cache.remove(&key);
+                    }
+                }
+            }
+        }
+
+        if let Some(name) = name {
+            eprintln!(
+                "Compiling OpenCL {name} kernel (first run after a kernel or driver change can take a minute)..."
+            );
+        }
+
         // NVIDIA's OpenCL compiler can overflow the default thread stack on the
         // full vanity program (tests already use 16 MiB).
-        let source = source.to_owned();
+        let owned = source.to_owned();
         let device = ctx.device();
         let cl_ctx = ctx.context().clone();
+        let compile_opts = opts.clone();
         let program = std::thread::Builder::new()
             .name("cl-compile".into())
             .stack_size(16 * 1024 * 1024)
             .spawn(move || {
                 Program::builder()
-                    .src(source)
+                    .src(owned)
                     .devices(device)
-                    .cmplr_opt(&opts)
+                    .cmplr_opt(&compile_opts)
                     .build(&cl_ctx)
             })
             .map_err(|e| GpuError::Other(format!("failed to spawn OpenCL compile thread: {e}")))?
@@ -96,6 +146,31 @@ impl GpuProgram {
             }
         }
 
+        if let Some(cache) = &cache {
+            let stored = match program.info(ProgramInfo::Binaries) {
+                Ok(ProgramInfoResult::Binaries(bins)) => bins
+                    .first()
+                    .ok_or_else(|| "driver returned no binary".to_string())
+                    .and_then(|bin| cache.store(&key, bin)),
+                Ok(_) => Err("unexpected program info".to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = stored {
+                eprintln!("Warning: could not cache OpenCL binary: {e}");
+            }
+        }
+
+        if let Some(name) = name {
+            eprintln!("OpenCL {name} kernel ready.");
+        }
+        Ok(Self { program })
+    }
+
+    /// Load a program from a binary produced by the same device and driver.
+    fn from_binary(ctx: &GpuContext, binary: &[u8], opts: &str) -> Result<Self, GpuError> {
+        let opts = CString::new(opts).map_err(|e| GpuError::Other(e.to_string()))?;
+        let program =
+            Program::with_binary(ctx.context().as_core(), &[ctx.device()], &[binary], &opts)?;
         Ok(Self { program })
     }
 
@@ -189,6 +264,19 @@ impl GpuProgram {
     /// Concatenates all required kernels in dependency order:
     /// sha256 → sha512 → hmac_sha512 → pbkdf2 → secp256k1_fe/scalar → g_table → secp256k1_point → blake2b → base58 → bip39 → bip32 → vanity
     pub fn vanity(ctx: &GpuContext) -> Result<Self, GpuError> {
+        Self::build(ctx, &Self::vanity_source(None), Some("vanity"))
+    }
+
+    /// Compile the vanity program with `spec`'s patterns baked into
+    /// `vanity_search`. Cached like any other program; the pattern set is
+    /// part of the source, so each set has its own entry.
+    pub fn vanity_specialized(ctx: &GpuContext, spec: &KernelSpec) -> Result<Self, GpuError> {
+        let source = Self::vanity_source(Some(&spec.prelude()));
+        Self::build(ctx, &source, Some("pattern-specialized vanity"))
+    }
+
+    /// Vanity program source, with an optional prelude just before vanity.cl.
+    fn vanity_source(prelude: Option<&str>) -> String {
         // Pre-allocate to avoid reallocations on multi-hundred-KB source blob.
         let mut combined = String::with_capacity(
             sources::SHA256.len()
@@ -204,6 +292,7 @@ impl GpuProgram {
                 + sources::BIP39.len()
                 + sources::BIP32.len()
                 + sources::VANITY.len()
+                + prelude.map_or(0, str::len)
                 + 1024, // comment separators + newlines
         );
 
@@ -237,18 +326,15 @@ impl GpuProgram {
         combined.push_str("\n\n// === bip32.cl ===\n");
         combined.push_str(sources::BIP32);
 
+        if let Some(prelude) = prelude {
+            combined.push_str("\n\n");
+            combined.push_str(prelude);
+        }
         combined.push_str("\n\n// === vanity.cl ===\n");
         combined.push_str(sources::VANITY);
         combined.push('\n');
 
-        eprintln!(
-            "Compiling OpenCL vanity kernel (first run after a kernel change can take a minute)..."
-        );
-        let built = Self::from_source(ctx, &combined);
-        if built.is_ok() {
-            eprintln!("OpenCL vanity kernel ready.");
-        }
-        built
+        combined
     }
 
     /// Compile the benchmark program with separate kernels for each component.
@@ -1332,7 +1418,8 @@ mod tests {
                 let cpu_seed = mnemonic_to_seed(&mnemonic, "");
                 println!("CPU seed (first 16 bytes): {:02x?}", &cpu_seed[..16]);
 
-                let master = ExtendedPrivateKey::from_seed(&cpu_seed).expect("master from seed");
+                let master =
+                    ExtendedPrivateKey::from_seed(&cpu_seed[..]).expect("master from seed");
                 let ergo_key = derive_ergo_first_key(&master).expect("derive ergo key");
                 let cpu_private_key = *ergo_key.private_key(); // Copy to owned array
                 println!(
@@ -1410,7 +1497,7 @@ mod tests {
                     "GPU derivation returned error for {}",
                     description
                 );
-                assert_eq!(gpu_seed, cpu_seed, "Seed mismatch for {}", description);
+                assert_eq!(gpu_seed, *cpu_seed, "Seed mismatch for {}", description);
                 assert_eq!(
                     gpu_private_key, cpu_private_key,
                     "Private key mismatch for {}",
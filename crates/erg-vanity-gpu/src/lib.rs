@@ -2,8 +2,13 @@
 
 pub mod bench;
 pub mod buffers;
+pub mod cache;
 pub mod comb;
 pub mod context;
 pub mod kernel;
 pub mod pipeline;
+pub mod selftest;
+pub mod specialize;
+pub mod tune;
+mod verify;
 pub mod wordlist;
@@ -1,36 +1,67 @@
 //! GPU pipeline orchestration for vanity address search.
 
-use crate::buffers::{GpuBuffers, GpuHit, MAX_HITS};
+use crate::buffers::{
+    max_batch_for_memory, GpuBuffers, BATCH_SLOTS, DEFAULT_HIT_CAPACITY, MAX_HIT_CAPACITY,
+};
 use crate::comb::CombTableBuffer;
 use crate::context::{GpuContext, GpuError};
 use crate::kernel::GpuProgram;
+use crate::selftest;
+use crate::specialize::KernelSpec;
+use crate::tune::{self, TuneProfile};
+use crate::verify::{HitVerifier, VerifyQueue, MAX_VERIFY_BACKLOG};
 use crate::wordlist::WordlistBuffers;
-use erg_vanity_cpu::{MatchType, Pattern};
-use ocl::enums::{KernelWorkGroupInfo, KernelWorkGroupInfoResult};
-use ocl::Kernel;
+use erg_vanity_core::{Secret, Zeroize};
+use erg_vanity_cpu::MatchType;
+use ocl::enums::{KernelWorkGroupInfo, KernelWorkGroupInfoResult, ProfilingInfo};
+use ocl::{Event, Kernel, Queue};
 use rand::RngCore;
 use std::fmt;
+use std::time::Duration;
+
+/// `VANITY_MAX_WG` in vanity.cl: size of the search kernel's local
+/// batch-inversion scratch, so its work-groups cannot be larger.
+const VANITY_MAX_WORK_GROUP: usize = 256;
+
+/// Smallest batch the pipeline halves down to after the device runs out of
+/// resources; below this the error is returned.
+const MIN_RECOVERY_BATCH: usize = 256;
 
 /// Configuration for vanity search.
 #[derive(Debug, Clone)]
 pub struct VanityConfig {
-    /// Number of work items per batch (tune for your GPU).
-    pub batch_size: usize,
+    /// Number of work items per batch. `None` uses the device's saved
+    /// autotune result, else a heuristic from its compute units.
+    pub batch_size: Option<usize>,
     /// Case-insensitive matching.
     pub ignore_case: bool,
     /// Number of BIP44 address indices to check per seed (m/44'/429'/0'/0/{0..N-1}).
     pub num_indices: u32,
     /// CPU verify match mode. The OpenCL kernel still searches prefixes.
     pub match_type: MatchType,
+    /// Interactive mode: keep each `vanity_seed`/`vanity_search` launch under
+    /// this device time by resizing batches, so a GPU driving a display stays
+    /// responsive. `batch_size` (or its default) becomes the upper bound.
+    pub kernel_latency: Option<Duration>,
+    /// Hits each batch can store. `None` starts at `DEFAULT_HIT_CAPACITY`;
+    /// either way the buffers grow after a batch overflows them.
+    pub hit_capacity: Option<usize>,
+    /// Compile `vanity_search` with the patterns baked in when there are at
+    /// most `MAX_SPECIALIZED_PATTERNS`. Each new pattern set costs one extra
+    /// build (then cached); the generic kernel is used if it fails.
+    pub specialize: bool,
 }
 
 impl Default for VanityConfig {
     fn default() -> Self {
         Self {
-            batch_size: 1 << 18, // 262,144 - conservative default
+            batch_size: None,
             ignore_case: false,
             num_indices: 1,
             match_type: MatchType::Prefix,
+            kernel_latency: None,
+            hit_capacity: None,
+            specialize: true,
         }
     }
 }
@@ -68,11 +99,12 @@ pub(crate) fn prepare_patterns_for_gpu(
     }
 }
 
-/// Result of a successful vanity search.
+/// Result of a successful vanity search. Secrets are wiped on drop and kept
+/// out of swap where permitted.
 #[derive(Clone)]
 pub struct VanityResult {
     /// The entropy that produced the matching address.
-    pub entropy: [u8; 32],
+    pub entropy: Secret<[u8; 32]>,
     /// The work item ID that found this hit.
     pub work_item_id: u32,
     /// The BIP44 address index <i> in m/44'/429'/0'/0/<i>.
@@ -82,7 +114,7 @@ pub struct VanityResult {
     /// The Ergo address (Base58 encoded).
     pub address: String,
     /// The BIP39 mnemonic (24 words).
-    pub mnemonic: String,
+    pub mnemonic: Secret<String>,
 }
 
 impl fmt::Debug for VanityResult {
@@ -109,6 +141,85 @@ pub(crate) fn sort_patterns_longest_first(patterns: &[String]) -> (Vec<String>,
     (sorted, map)
 }
 
+/// Longest-first order of the patterns whose bit is clear in `retired`.
+///
+/// Bit `i` refers to `patterns[i]`. The returned map points back to original indices.
+pub(crate) fn active_patterns_longest_first(
+    patterns: &[String],
+    retired: u64,
+) -> (Vec<String>, Vec<u32>) {
+    let active: Vec<usize> = (0..patterns.len())
+        .filter(|&i| retired & (1u64 << i) == 0)
+        .collect();
+    let subset: Vec<String> = active.iter().map(|&i| patterns[i].clone()).collect();
+    let (sorted, map) = sort_patterns_longest_first(&subset);
+    let map = map.iter().map(|&j| active[j as usize] as u32).collect();
+    (sorted, map)
+}
+
+/// First batch in interactive mode; it grows from here once timed.
+const INTERACTIVE_START_BATCH: usize = 1 << 14;
+
+/// Interactive batches aim this fraction below the latency target, so normal
+/// jitter does not push a kernel over it.
+const LATENCY_HEADROOM: f64 = 0.9;
+
+/// Batch size for the next interactive launch, given the slower kernel of the
+/// last one took `kernel_ns` at `current` work items.
+///
+/// Sizes stay a multiple of `granule` within `[granule, capacity]`. A kernel
+/// between 80% and 100% of the target keeps its size; otherwise the batch is
+/// scaled toward the target by at most 2× per step.
+pub(crate) fn next_batch_size(
+    current: usize,
+    kernel_ns: u64,
+    target_ns: u64,
+    granule: usize,
+    capacity: usize,
+) -> usize {
+    let granule = granule.max(1);
+    let max = (capacity / granule).max(1) * granule;
+    if kernel_ns == 0 || (kernel_ns <= target_ns && kernel_ns * 5 >= target_ns * 4) {
+        return current.clamp(granule, max);
+    }
+    let scale = (target_ns as f64 * LATENCY_HEADROOM / kernel_ns as f64).clamp(0.5, 2.0);
+    let scaled = (current as f64 * scale) as usize;
+    ((scaled / granule).max(1) * granule).min(max)
+}
+
+/// Hit capacity after a batch reported `raw_hits` matches into `current`
+/// slots: twice the count, rounded up to a power of two, within `limit`.
+pub(crate) fn grown_hit_capacity(raw_hits: usize, current: usize, limit: usize) -> usize {
+    raw_hits
+        .saturating_mul(2)
+        .checked_next_power_of_two()
+        .unwrap_or(usize::MAX)
+        .min(limit)
+        .max(current)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    x
+}
+
+/// Smallest batch granule both kernels' local sizes divide.
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b).max(1) * b
+}
+
+/// `batch` halved until it is at most `limit`.
+fn halve_to_fit(batch: usize, limit: usize) -> usize {
+    let mut batch = batch;
+    while batch > limit.max(1) {
+        batch /= 2;
+    }
+    batch
+}
+
 fn local_size_for(batch: usize, recommended: usize) -> usize {
     let mut ls = recommended.min(batch).max(1);
     while !batch.is_multiple_of(ls) {
@@ -120,6 +231,24 @@ fn local_size_for(batch: usize, recommended: usize) -> usize {
     ls
 }
 
+/// Start-to-end device time of a finished, profiled event.
+fn event_ns(event: &Event) -> Result<u64, GpuError> {
+    use ocl::enums::ProfilingInfoResult;
+    let ns = |info| match event.profiling_info(info)? {
+        ProfilingInfoResult::Queued(ns)
+        | ProfilingInfoResult::Submit(ns)
+        | ProfilingInfoResult::Start(ns)
+        | ProfilingInfoResult::End(ns) => Ok::<u64, GpuError>(ns),
+    };
+    let (start, end) = (ns(ProfilingInfo::Start)?, ns(ProfilingInfo::End)?);
+    if start == 0 || end <= start {
+        return Err(GpuError::Other(
+            "profiling timestamps invalid; is CL_QUEUE_PROFILING_ENABLE set?".into(),
+        ));
+    }
+    Ok(end - start)
+}
+
 fn kernel_work_group_limit(kernel: &Kernel, device: ocl::Device, fallback: usize) -> usize {
     match kernel.wg_info(device, KernelWorkGroupInfo::WorkGroupSize) {
         Ok(KernelWorkGroupInfoResult::WorkGroupSize(n)) if n > 0 => n,
@@ -127,30 +256,146 @@ fn kernel_work_group_limit(kernel: &Kernel, device: ocl::Device, fallback: usize
     }
 }
 
+/// The `vanity_seed` and `vanity_search` kernels bound to one slot's buffers.
+struct SlotKernels {
+    seed: Kernel,
+    search: Kernel,
+}
+
+impl SlotKernels {
+    /// Largest local work sizes the seed and search kernels accept on
+    /// `device`, within `max_wg` and the search kernel's compiled limit.
+    fn work_group_limits(&self, device: ocl::Device, max_wg: usize) -> (usize, usize) {
+        let seed = kernel_work_group_limit(&self.seed, device, max_wg).min(max_wg);
+        let search = kernel_work_group_limit(&self.search, device, max_wg)
+            .min(max_wg)
+            .min(VANITY_MAX_WORK_GROUP);
+        (seed, search)
+    }
+}
+
+/// Buffers and settings the search kernels are bound to.
+struct KernelBindings<'a> {
+    queue: &'a Queue,
+    buffers: &'a GpuBuffers,
+    wordlist: &'a WordlistBuffers,
+    comb: &'a CombTableBuffer,
+    ignore_case: bool,
+    num_indices: u32,
+}
+
+impl KernelBindings<'_> {
+    /// Both kernels from `program`, bound to `slot`'s seed and hit buffers.
+    fn slot(
+        &self,
+        program: &GpuProgram,
+        slot: usize,
+        batch_size: usize,
+        num_patterns: u32,
+    ) -> Result<SlotKernels, GpuError> {
+        let buffers = self.buffers;
+        let bufs = &buffers.slots[slot];
+
+        // vanity_seed: salt, counter_start, words8, word_lens, seeds
+        let seed = Kernel::builder()
+            .program(program.program())
+            .name("vanity_seed")
+            .queue(self.queue.clone())
+            .global_work_size(batch_size)
+            .arg(&buffers.salt)
+            .arg(0u64)
+            .arg(&self.wordlist.words8)
+            .arg(&self.wordlist.lens)
+            .arg(&bufs.seeds)
+            .build()?;
+
+        // vanity_search: salt, counter_start, seeds, patterns..., hits
+        let search = Kernel::builder()
+            .program(program.program())
+            .name("vanity_search")
+            .queue(self.queue.clone())
+            .global_work_size(batch_size)
+            .arg(&buffers.salt)
+            .arg(0u64)
+            .arg(&bufs.seeds)
+            .arg(&buffers.patterns)
+            .arg(&buffers.pattern_offsets)
+            .arg(&buffers.pattern_lens)
+            .arg(num_patterns)
+            .arg(if self.ignore_case { 1u32 } else { 0u32 })
+            .arg(self.num_indices)
+            .arg(&bufs.hits)
+            .arg(&bufs.hit_count)
+            .arg(bufs.hit_capacity() as u32)
+            .arg(&self.comb.table)
+            .build()?;
+
+        Ok(SlotKernels { seed, search })
+    }
+}
+
+/// A batch enqueued on the device whose hits have not been collected yet.
+struct InFlight {
+    slot: usize,
+    /// First counter and number of work items launched.
+    counter_start: u64,
+    batch_size: usize,
+    /// Completion of the batch's `vanity_seed`, timed in interactive mode.
+    seed_done: Event,
+    /// Completion of the batch's `vanity_search`.
+    done: Event,
+    /// Pattern index map the batch was enqueued with, so retiring patterns
+    /// meanwhile cannot misattribute its hits.
+    pattern_index_map: Vec<u32>,
+}
+
 /// GPU-accelerated vanity address search pipeline.
+///
+/// Double-buffered: each batch gets one of two sets of seed and hit buffers.
+/// Kernels run in order on the context's queue while hits are read back on a
+/// second queue, so the device computes batch N+1 while the host reads batch
+/// N. Hits are verified on a shared CPU pool and returned once checked.
 pub struct VanityPipeline {
     ctx: GpuContext,
-    #[allow(dead_code)]
+    /// Generic vanity program, the fallback when patterns are retired.
     program: GpuProgram,
+    /// Whether the kernels come from a pattern-specialized program.
+    specialized: bool,
     buffers: GpuBuffers,
-    #[allow(dead_code)]
     wordlist: WordlistBuffers,
-    #[allow(dead_code)]
     comb: CombTableBuffer,
-    seed_kernel: Kernel,
-    kernel: Kernel,
+    kernels: [SlotKernels; BATCH_SLOTS],
+    /// Readback queue, separate from the kernel queue so reads of one batch
+    /// do not wait behind the next batch's kernels.
+    transfer: Queue,
+    /// Slot the next batch is enqueued on.
+    next_slot: usize,
+    pending: Option<InFlight>,
     patterns: Vec<String>,
     /// Maps GPU/sorted pattern index back to the caller's original order.
     pattern_index_map: Vec<u32>,
-    #[allow(dead_code)]
     num_patterns: u32,
     ignore_case: bool,
-    match_type: MatchType,
     num_indices: u32,
-    #[allow(dead_code)]
+    /// Hits the next batch's slot should hold; raised after an overflow.
+    hit_capacity: usize,
+    /// Most hits a batch can produce (one per work item), capped at
+    /// `MAX_HIT_CAPACITY` unless configured higher.
+    hit_limit: usize,
+    verify: VerifyQueue,
+    /// Kept only so it can be wiped on drop.
     salt: [u8; 32],
     counter: u64,
-    cfg: VanityConfig,
+    /// Work items in the next batch; at most `capacity`.
+    batch_size: usize,
+    /// Work items the seed and hit buffers were allocated for.
+    capacity: usize,
+    /// Interactive mode target for the slower kernel of each batch.
+    kernel_latency_ns: Option<u64>,
+    /// Local work sizes of (`vanity_seed`, `vanity_search`).
+    local_sizes: (usize, usize),
+    /// Largest local work sizes each kernel accepts on this device.
+    local_limits: (usize, usize),
     addresses_checked: u64,
     hits_dropped_total: u64,
 }
@@ -164,6 +409,12 @@ impl VanityPipeline {
     }
 
     /// Create a new vanity search pipeline on a specific device with a shared salt.
+    ///
+    /// Batch and work-group sizes saved by `--autotune` for this device and
+    /// driver are applied when present. The device must first pass the
+    /// known-answer self-test, so a miscompiled kernel never searches. With
+    /// `cfg.specialize` and few patterns, the search runs a kernel built for
+    /// exactly these patterns.
     pub fn new_with_device_and_salt(
         patterns: &[String],
         cfg: VanityConfig,
@@ -174,13 +425,125 @@ impl VanityPipeline {
             return Err(GpuError::Other("at least one pattern required".to_string()));
         }
 
-        let ctx = GpuContext::with_device(device_index)?;
+        // Interactive mode times kernels with profiling events.
+        let ctx = if cfg.kernel_latency.is_some() {
+            GpuContext::with_device_profiling(device_index)?
+        } else {
+            GpuContext::with_device(device_index)?
+        };
         let program = GpuProgram::vanity(&ctx)?;
+        selftest::run(&ctx, &program)?;
+        let specialized = if cfg.specialize {
+            Self::specialized_program(&ctx, patterns, &cfg)
+        } else {
+            None
+        };
+        let saved = tune::saved_profile(ctx.info(), cfg.num_indices);
+        Self::from_parts(
+            ctx,
+            &program,
+            specialized.as_ref(),
+            patterns,
+            cfg,
+            salt,
+            saved.as_ref(),
+        )
+    }
+
+    /// The pattern-specialized program for `patterns`, if the set is small
+    /// enough, it builds, its search agrees with the CPU on the self-test
+    /// batch, and it finds the known hit with probe patterns of the same shape.
+    fn specialized_program(
+        ctx: &GpuContext,
+        patterns: &[String],
+        cfg: &VanityConfig,
+    ) -> Option<GpuProgram> {
+        let (sorted, _) = sort_patterns_longest_first(patterns);
+        let for_gpu = prepare_patterns_for_gpu(&sorted, cfg.ignore_case).unwrap_or(sorted);
+        let spec = KernelSpec::new(&for_gpu, cfg.ignore_case, cfg.num_indices)?;
+        let program = match GpuProgram::vanity_specialized(ctx, &spec) {
+            Ok(program) => program,
+            Err(e) => {
+                eprintln!(
+                    "Warning: pattern-specialized kernel failed to build ({e}); \
+                     using the generic kernel"
+                );
+                return None;
+            }
+        };
+        let checked =
+            selftest::check_search(ctx, &program, &for_gpu, cfg.ignore_case, cfg.num_indices)
+                .and_then(|()| {
+                    selftest::check_specialized_hit(
+                        ctx,
+                        &program,
+                        &for_gpu,
+                        cfg.ignore_case,
+                        cfg.num_indices,
+                    )
+                });
+        match checked {
+            Ok(()) => Some(program),
+            Err(e) => {
+                eprintln!(
+                    "Warning: pattern-specialized kernel failed its self-test ({e}); \
+                     using the generic kernel"
+                );
+                None
+            }
+        }
+    }
+
+    /// Build a pipeline on an existing context and compiled vanity program.
+    ///
+    /// `specialized`, built for these patterns by `vanity_specialized`, runs
+    /// the search instead of `program` while no pattern is retired. `tuned`
+    /// local sizes are used where they divide the batch and fit the kernels'
+    /// work-group limits; otherwise the heuristic sizes apply.
+    pub(crate) fn from_parts(
+        ctx: GpuContext,
+        program: &GpuProgram,
+        specialized: Option<&GpuProgram>,
+        patterns: &[String],
+        cfg: VanityConfig,
+        salt: [u8; 32],
+        tuned: Option<&TuneProfile>,
+    ) -> Result<Self, GpuError> {
+        if patterns.is_empty() {
+            return Err(GpuError::Other("at least one pattern required".to_string()));
+        }
+
+        let requested = cfg
+            .batch_size
+            .or(tuned.map(|t| t.batch_size))
+            .unwrap_or_else(|| ctx.recommended_batch_size())
+            .max(1);
+        let mut batch_size =
+            halve_to_fit(requested, max_batch_for_memory(ctx.info().global_mem_size));
+        let program = program.clone();
         let queue = ctx.queue();
         let comb = CombTableBuffer::upload(queue)?;
 
-        // Allocate buffers
-        let buffers = GpuBuffers::new(&ctx, cfg.batch_size)?;
+        let hit_capacity = cfg.hit_capacity.unwrap_or(DEFAULT_HIT_CAPACITY).max(1);
+
+        // Allocate buffers, halving the batch while the device is out of resources
+        let buffers = loop {
+            match GpuBuffers::new(&ctx, batch_size, hit_capacity) {
+                Err(e) if e.is_out_of_resources() && batch_size / 2 >= MIN_RECOVERY_BATCH => {
+                    batch_size /= 2;
+                }
+                result => break result?,
+            }
+        };
+        if batch_size < requested {
+            eprintln!(
+                "Warning: {}: batch size {requested} does not fit in device memory; using {batch_size}",
+                ctx.info().device_name.trim()
+            );
+        }
+        // The kernel stops at a seed's first match, so a batch never has more
+        // hits than work items.
+        let hit_limit = batch_size.min(MAX_HIT_CAPACITY).max(hit_capacity);
         let wordlist = WordlistBuffers::upload(queue)?;
 
         buffers.upload_salt(&salt)?;
@@ -192,69 +555,73 @@ impl VanityPipeline {
         let num_patterns = buffers.upload_patterns(patterns_for_gpu)? as u32;
 
         let recommended = ctx.recommended_work_group_size();
-        let local = local_size_for(cfg.batch_size, recommended);
-
-        // vanity_seed: salt, counter_start, words8, word_lens, seeds
-        let mut seed_kernel = Kernel::builder()
-            .program(program.program())
-            .name("vanity_seed")
-            .queue(queue.clone())
-            .global_work_size(cfg.batch_size)
-            .local_work_size(local)
-            .arg(&buffers.salt)
-            .arg(0u64)
-            .arg(&wordlist.words8)
-            .arg(&wordlist.lens)
-            .arg(&buffers.seeds)
-            .build()?;
-
-        // vanity_search: salt, counter_start, seeds, patterns..., hits
-        let mut kernel = Kernel::builder()
-            .program(program.program())
-            .name("vanity_search")
-            .queue(queue.clone())
-            .global_work_size(cfg.batch_size)
-            .local_work_size(local)
-            .arg(&buffers.salt)
-            .arg(0u64)
-            .arg(&buffers.seeds)
-            .arg(&buffers.patterns)
-            .arg(&buffers.pattern_offsets)
-            .arg(&buffers.pattern_lens)
-            .arg(num_patterns)
-            .arg(if cfg.ignore_case { 1u32 } else { 0u32 })
-            .arg(cfg.num_indices)
-            .arg(&buffers.hits)
-            .arg(&buffers.hit_count)
-            .arg(MAX_HITS as u32)
-            .arg(&comb.table)
-            .build()?;
+        let bindings = KernelBindings {
+            queue,
+            buffers: &buffers,
+            wordlist: &wordlist,
+            comb: &comb,
+            ignore_case: cfg.ignore_case,
+            num_indices: cfg.num_indices,
+        };
+        let search_program = specialized.unwrap_or(&program);
+        let mut kernels = [
+            bindings.slot(search_program, 0, batch_size, num_patterns)?,
+            bindings.slot(search_program, 1, batch_size, num_patterns)?,
+        ];
+        let transfer = Queue::new(ctx.context(), ctx.device(), None)?;
 
-        let device = ctx.device();
-        let capped = recommended
-            .min(kernel_work_group_limit(&seed_kernel, device, recommended))
-            .min(kernel_work_group_limit(&kernel, device, recommended));
-        let local = local_size_for(cfg.batch_size, capped);
-        seed_kernel.set_default_local_work_size(local.into());
-        kernel.set_default_local_work_size(local.into());
+        let max_wg = ctx.info().max_work_group_size.max(1);
+        let (seed_limit, search_limit) = kernels[0].work_group_limits(ctx.device(), max_wg);
+        let capped = recommended.min(seed_limit).min(search_limit);
+        let fits = |size: usize, limit: usize| {
+            size > 0 && size <= limit && batch_size.is_multiple_of(size)
+        };
+        let seed_local = tuned
+            .map(|t| t.seed_local)
+            .filter(|&l| fits(l, seed_limit))
+            .unwrap_or_else(|| local_size_for(batch_size, capped));
+        let search_local = tuned
+            .map(|t| t.search_local)
+            .filter(|&l| fits(l, search_limit))
+            .unwrap_or_else(|| local_size_for(batch_size, capped));
+        let kernel_latency_ns = cfg.kernel_latency.map(|d| d.as_nanos() as u64);
+        let granule = lcm(seed_local, search_local);
+        let start_batch = if kernel_latency_ns.is_some() {
+            next_batch_size(INTERACTIVE_START_BATCH, 0, 0, granule, batch_size)
+        } else {
+            batch_size
+        };
+        for k in &mut kernels {
+            k.seed.set_default_local_work_size(seed_local.into());
+            k.search.set_default_local_work_size(search_local.into());
+        }
 
         Ok(Self {
             ctx,
             program,
+            specialized: specialized.is_some(),
             buffers,
             wordlist,
             comb,
-            seed_kernel,
-            kernel,
+            kernels,
+            transfer,
+            next_slot: 0,
+            pending: None,
             patterns: patterns.to_vec(),
             pattern_index_map,
             num_patterns,
             ignore_case: cfg.ignore_case,
-            match_type: cfg.match_type,
             num_indices: cfg.num_indices,
+            hit_capacity,
+            hit_limit,
+            verify: VerifyQueue::new(HitVerifier::new(patterns, cfg.match_type, cfg.ignore_case)),
             salt,
             counter: 0,
-            cfg,
+            batch_size: start_batch,
+            capacity: batch_size,
+            kernel_latency_ns,
+            local_sizes: (seed_local, search_local),
+            local_limits: (seed_limit, search_limit),
             addresses_checked: 0,
             hits_dropped_total: 0,
         })
@@ -265,6 +632,52 @@ impl VanityPipeline {
         self.ctx.info()
     }
 
+    /// Work items in the next batch. Changes between batches in interactive
+    /// mode and after the device runs out of resources, so read it before
+    /// reserving each batch's counter range.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Local work sizes of the seed and search kernels.
+    pub fn local_sizes(&self) -> (usize, usize) {
+        self.local_sizes
+    }
+
+    /// Largest local work sizes the seed and search kernels accept.
+    pub(crate) fn local_limits(&self) -> (usize, usize) {
+        self.local_limits
+    }
+
+    /// Use these local work sizes for the seed and search kernels.
+    pub(crate) fn set_local_sizes(&mut self, seed: usize, search: usize) {
+        for k in &mut self.kernels {
+            k.seed.set_default_local_work_size(seed.into());
+            k.search.set_default_local_work_size(search.into());
+        }
+        self.local_sizes = (seed, search);
+    }
+
+    /// Run one batch and return the device time of (`vanity_seed`,
+    /// `vanity_search`) in nanoseconds. Needs a profiling queue; hits are
+    /// discarded.
+    pub(crate) fn time_batch(&mut self, counter_start: u64) -> Result<(u64, u64), GpuError> {
+        let slot = self.next_slot;
+        self.buffers.slots[slot].reset_hits()?;
+        let k = &mut self.kernels[slot];
+        k.seed.set_arg(1, counter_start)?;
+        k.search.set_arg(1, counter_start)?;
+
+        let mut seed_event = Event::empty();
+        let mut search_event = Event::empty();
+        unsafe {
+            k.seed.cmd().enew(&mut seed_event).enq()?;
+            k.search.cmd().enew(&mut search_event).enq()?;
+        }
+        search_event.wait_for().map_err(ocl::Error::from)?;
+        Ok((event_ns(&seed_event)?, event_ns(&search_event)?))
+    }
+
     /// Get the number of addresses checked so far.
     pub fn addresses_checked(&self) -> u64 {
         self.addresses_checked
@@ -275,80 +688,330 @@ impl VanityPipeline {
         self.hits_dropped_total
     }
 
-    /// Run one batch of the search.
-    /// Returns all verified matches from this batch.
-    pub fn run_batch(&mut self) -> Result<Vec<VanityResult>, GpuError> {
-        // Reset hit counter
-        self.buffers.reset_hits()?;
+    /// Hits each batch will be able to store, after any growth.
+    pub fn hit_capacity(&self) -> usize {
+        self.hit_capacity
+    }
 
-        // Update counter_start (arg index 1) on both kernels
-        self.seed_kernel.set_arg(1, self.counter)?;
-        self.kernel.set_arg(1, self.counter)?;
+    /// Whether the search runs a kernel specialized for its pattern set.
+    pub fn is_specialized(&self) -> bool {
+        self.specialized
+    }
 
-        unsafe {
-            self.seed_kernel.enq()?;
-            self.kernel.enq()?;
+    /// Number of patterns the kernel currently searches.
+    pub fn active_patterns(&self) -> u32 {
+        self.num_patterns
+    }
+
+    /// Stop searching the patterns whose bit is set in `retired` (bit `i` is the
+    /// caller's pattern `i`) and re-upload the remaining ones.
+    ///
+    /// With every pattern retired the kernel still runs but cannot hit. The
+    /// upload waits for the in-flight batch, which keeps its own index map.
+    /// A specialized kernel has the full set compiled in, so the pipeline
+    /// switches to the generic kernel, which reads the uploaded patterns.
+    pub fn set_retired_patterns(&mut self, retired: u64) -> Result<(), GpuError> {
+        if self.specialized {
+            self.use_generic_kernels()?;
+        }
+        let (sorted, map) = active_patterns_longest_first(&self.patterns, retired);
+        let num_patterns = if sorted.is_empty() {
+            0
+        } else {
+            let lowered = prepare_patterns_for_gpu(&sorted, self.ignore_case);
+            let for_gpu: &[String] = lowered.as_deref().unwrap_or(&sorted);
+            self.buffers.upload_patterns(for_gpu)? as u32
+        };
+        // vanity_search arg 6 is num_patterns
+        for k in &mut self.kernels {
+            k.search.set_arg(6, num_patterns)?;
         }
+        self.num_patterns = num_patterns;
+        self.pattern_index_map = map;
+        Ok(())
+    }
+
+    /// Rebind every slot to the generic program.
+    ///
+    /// The generic kernels can accept smaller work groups than the
+    /// specialized ones, so the local sizes are checked against their own
+    /// limits and reduced where they no longer fit.
+    fn use_generic_kernels(&mut self) -> Result<(), GpuError> {
+        let bindings = KernelBindings {
+            queue: self.ctx.queue(),
+            buffers: &self.buffers,
+            wordlist: &self.wordlist,
+            comb: &self.comb,
+            ignore_case: self.ignore_case,
+            num_indices: self.num_indices,
+        };
+        let kernels = [
+            bindings.slot(&self.program, 0, self.capacity, self.num_patterns)?,
+            bindings.slot(&self.program, 1, self.capacity, self.num_patterns)?,
+        ];
+        let max_wg = self.ctx.info().max_work_group_size.max(1);
+        let (seed_limit, search_limit) = kernels[0].work_group_limits(self.ctx.device(), max_wg);
+        let (seed_local, search_local) = self.local_sizes;
+        let capped = self
+            .ctx
+            .recommended_work_group_size()
+            .min(seed_limit)
+            .min(search_limit);
+        let fit = |size: usize, limit: usize| {
+            if size <= limit {
+                size
+            } else {
+                local_size_for(self.capacity, capped)
+            }
+        };
+        let (seed_local, search_local) =
+            (fit(seed_local, seed_limit), fit(search_local, search_limit));
+        self.kernels = kernels;
+        self.specialized = false;
+        self.local_limits = (seed_limit, search_limit);
+        self.set_local_sizes(seed_local, search_local);
+        let granule = lcm(seed_local, search_local);
+        self.batch_size = (self.batch_size.min(self.capacity) / granule).max(1) * granule;
+        Ok(())
+    }
+
+    /// Run one batch of the search.
+    ///
+    /// Pipelined like `run_batch_with_counter`: returns the matches of
+    /// earlier batches that have finished verifying.
+    pub fn run_batch(&mut self) -> Result<Vec<VanityResult>, GpuError> {
+        let counter_start = self.counter;
 
         // Update counter for next batch
         // Counter is per-seed: each work item uses counter_start + gid.
         // Each seed checks num_indices addresses.
-        self.counter = self.counter.wrapping_add(self.cfg.batch_size as u64);
-        self.addresses_checked += (self.cfg.batch_size as u64) * (self.num_indices as u64);
+        self.counter = self.counter.wrapping_add(self.batch_size as u64);
 
-        self.collect_results()
+        self.run_batch_with_counter(counter_start)
     }
 
     /// Run one batch using an externally managed counter.
     /// Counter is per-seed: each work item uses counter_start + gid.
+    ///
+    /// Enqueues the batch for `counter_start`, then reads back the hits of
+    /// the batch enqueued by the previous call while the device works on the
+    /// new one and hands them to the verifier pool. Returns the matches of
+    /// every batch verified since the last call, waiting only if more than
+    /// `MAX_VERIFY_BACKLOG` batches are still being checked. Call `finish`
+    /// after the last batch to collect the rest.
+    ///
+    /// If the device runs out of resources, the buffers are halved and the
+    /// rest of the `batch_size` counters reserved for this call are searched
+    /// in smaller batches; `batch_size` reports the size now in use.
     pub fn run_batch_with_counter(
         &mut self,
         counter_start: u64,
     ) -> Result<Vec<VanityResult>, GpuError> {
-        // Reset hit counter
-        self.buffers.reset_hits()?;
+        self.search_ranges(vec![(counter_start, self.batch_size)])?;
+        Ok(self.verify.collect(MAX_VERIFY_BACKLOG))
+    }
 
-        self.seed_kernel.set_arg(1, counter_start)?;
-        self.kernel.set_arg(1, counter_start)?;
+    /// Wait for the in-flight batch, if any, and for verification of every
+    /// batch so far; return their matches.
+    pub fn finish(&mut self) -> Result<Vec<VanityResult>, GpuError> {
+        while let Some(prev) = self.pending.take() {
+            let mut todo = Vec::new();
+            if let Some(e) = self.collect_or_requeue(prev, &mut todo)? {
+                self.shrink_after(e, &mut todo)?;
+            }
+            self.search_ranges(todo)?;
+        }
+        Ok(self.verify.collect(0))
+    }
 
-        unsafe {
-            self.seed_kernel.enq()?;
-            self.kernel.enq()?;
+    /// Search every `(counter_start, work items)` range in `todo`, in batches
+    /// of at most `batch_size`, collecting each batch while the next one
+    /// runs. The last batch is left in flight.
+    ///
+    /// A batch that fails with out-of-resources, when enqueued or read back,
+    /// shrinks the buffers and goes back on `todo`.
+    fn search_ranges(&mut self, mut todo: Vec<(u64, usize)>) -> Result<(), GpuError> {
+        while let Some((start, len)) = todo.pop() {
+            let size = self.batch_size.min(len);
+            match self.enqueue(start, size) {
+                Ok(batch) => {
+                    if size < len {
+                        todo.push((start.wrapping_add(size as u64), len - size));
+                    }
+                    if let Some(prev) = self.pending.replace(batch) {
+                        if let Some(e) = self.collect_or_requeue(prev, &mut todo)? {
+                            self.shrink_after(e, &mut todo)?;
+                        }
+                    }
+                }
+                Err(e) => {
+                    todo.push((start, len));
+                    self.shrink_after(e, &mut todo)?;
+                }
+            }
         }
+        Ok(())
+    }
 
-        self.addresses_checked += (self.cfg.batch_size as u64) * (self.num_indices as u64);
+    /// Collect `batch`. If reading it back fails with out-of-resources, its
+    /// range goes back on `todo` and the error is returned for shrinking.
+    fn collect_or_requeue(
+        &mut self,
+        batch: InFlight,
+        todo: &mut Vec<(u64, usize)>,
+    ) -> Result<Option<GpuError>, GpuError> {
+        let range = (batch.counter_start, batch.batch_size);
+        match self.collect_hits(batch) {
+            Ok(()) => Ok(None),
+            Err(e) if e.is_out_of_resources() => {
+                self.addresses_checked -= (range.1 as u64) * (self.num_indices as u64);
+                todo.push(range);
+                Ok(Some(e))
+            }
+            Err(e) => Err(e),
+        }
+    }
 
-        self.collect_results()
+    /// Halve the buffers after `err` if it is an out-of-resources error and
+    /// the batch can still shrink; otherwise return `err`.
+    fn shrink_after(
+        &mut self,
+        err: GpuError,
+        todo: &mut Vec<(u64, usize)>,
+    ) -> Result<(), GpuError> {
+        if !err.is_out_of_resources() || self.capacity / 2 < MIN_RECOVERY_BATCH {
+            return Err(err);
+        }
+        self.shrink(todo)?;
+        eprintln!(
+            "Warning: {}: out of device resources ({err}); batch size reduced to {}",
+            self.ctx.info().device_name.trim(),
+            self.batch_size
+        );
+        Ok(())
     }
 
-    fn collect_results(&mut self) -> Result<Vec<VanityResult>, GpuError> {
-        // Check for hits (read raw count, may exceed MAX_HITS)
-        let raw_hit_count = self.buffers.read_hit_count()? as usize;
-        let hit_count = raw_hit_count.min(MAX_HITS);
+    /// Halve the buffers, keeping local sizes that divide every range still
+    /// in `todo`.
+    ///
+    /// Waits for the in-flight batch first, so both slots are free.
+    fn shrink(&mut self, todo: &mut Vec<(u64, usize)>) -> Result<(), GpuError> {
+        let capacity = self.capacity / 2;
+        if let Some(prev) = self.pending.take() {
+            self.collect_or_requeue(prev, todo)?;
+        }
+        self.ctx.queue().finish()?;
 
-        // Track dropped hits (don't spam warnings here - caller can check hits_dropped_total)
-        if raw_hit_count > MAX_HITS {
-            self.hits_dropped_total += (raw_hit_count - MAX_HITS) as u64;
+        self.buffers.shrink(capacity)?;
+        // vanity_seed arg 4 and vanity_search arg 2 are the seeds
+        for (k, bufs) in self.kernels.iter().zip(&self.buffers.slots) {
+            k.seed.set_arg(4, &bufs.seeds)?;
+            k.search.set_arg(2, &bufs.seeds)?;
+        }
+        self.capacity = capacity;
+
+        let sizes = todo.iter().fold(capacity, |g, &(_, len)| gcd(g, len));
+        let (seed_local, search_local) = self.local_sizes;
+        if !sizes.is_multiple_of(lcm(seed_local, search_local)) {
+            self.set_local_sizes(
+                local_size_for(sizes, seed_local),
+                local_size_for(sizes, search_local),
+            );
         }
+        let (seed_local, search_local) = self.local_sizes;
+        let granule = lcm(seed_local, search_local);
+        self.batch_size = (self.batch_size.min(capacity) / granule).max(1) * granule;
+        Ok(())
+    }
 
-        if hit_count == 0 {
-            return Ok(Vec::new());
+    /// Enqueue both kernels for one batch on the next free slot.
+    ///
+    /// The slot was last used two batches ago, whose hits have already been
+    /// read, so its buffers are free to overwrite or regrow.
+    fn enqueue(&mut self, counter_start: u64, batch_size: usize) -> Result<InFlight, GpuError> {
+        let slot = self.next_slot;
+
+        if self.buffers.slots[slot].hit_capacity() < self.hit_capacity {
+            let bufs = &mut self.buffers.slots[slot];
+            bufs.grow_hits(self.hit_capacity)?;
+            // vanity_search args 9 and 11 are hits and max_hits
+            let search = &self.kernels[slot].search;
+            search.set_arg(9, &bufs.hits)?;
+            search.set_arg(11, self.hit_capacity as u32)?;
         }
 
-        let hits = self.buffers.read_hits(hit_count)?;
+        // Reset hit counter
+        self.buffers.slots[slot].reset_hits()?;
 
-        // Verify each hit on CPU
-        let mut results = Vec::new();
-        for hit in hits {
-            if let Some(result) = self.verify_hit(&hit)? {
-                results.push(result);
-            }
+        // Update counter_start (arg index 1) on both kernels
+        let k = &mut self.kernels[slot];
+        k.seed.set_arg(1, counter_start)?;
+        k.search.set_arg(1, counter_start)?;
+
+        let mut seed_done = Event::empty();
+        let mut done = Event::empty();
+        unsafe {
+            k.seed
+                .cmd()
+                .global_work_size(batch_size)
+                .enew(&mut seed_done)
+                .enq()?;
+            k.search
+                .cmd()
+                .global_work_size(batch_size)
+                .enew(&mut done)
+                .enq()?;
         }
+        // Submit now: the host is about to block on the other queue.
+        self.ctx.queue().flush()?;
+
+        self.addresses_checked += (batch_size as u64) * (self.num_indices as u64);
+        self.next_slot = (slot + 1) % BATCH_SLOTS;
+
+        Ok(InFlight {
+            slot,
+            counter_start,
+            batch_size,
+            seed_done,
+            done,
+            pattern_index_map: self.pattern_index_map.clone(),
+        })
+    }
 
-        // Sort for stable output (GPU atomic_inc order is nondeterministic)
-        sort_results_deterministically(&mut results);
+    /// Read back a finished batch's hits and queue them for verification.
+    fn collect_hits(&mut self, batch: InFlight) -> Result<(), GpuError> {
+        let bufs = &self.buffers.slots[batch.slot];
+
+        // Raw count: every match, even past the slot's capacity
+        let raw_hit_count = bufs.read_hit_count(&self.transfer, &batch.done)? as usize;
+
+        if let Some(target_ns) = self.kernel_latency_ns {
+            let kernel_ns = event_ns(&batch.seed_done)?.max(event_ns(&batch.done)?);
+            let (seed_local, search_local) = self.local_sizes;
+            self.batch_size = next_batch_size(
+                batch.batch_size,
+                kernel_ns,
+                target_ns,
+                lcm(seed_local, search_local),
+                self.capacity,
+            );
+        }
+        let capacity = bufs.hit_capacity();
+        let hit_count = raw_hit_count.min(capacity);
+
+        // Track dropped hits (don't spam warnings here - caller can check
+        // hits_dropped_total) and size later batches for the real count.
+        if raw_hit_count > capacity {
+            self.hits_dropped_total += (raw_hit_count - capacity) as u64;
+            self.hit_capacity =
+                grown_hit_capacity(raw_hit_count, self.hit_capacity, self.hit_limit);
+        }
 
-        Ok(results)
+        if hit_count > 0 {
+            let hits = bufs.read_hits(&self.transfer, hit_count)?;
+            self.verify.submit(hits, batch.pattern_index_map);
+        }
+        Ok(())
     }
 
     /// Search until a match is found (blocking).
@@ -356,85 +1019,20 @@ impl VanityPipeline {
         loop {
             let results = self.run_batch()?;
             if let Some(result) = results.into_iter().next() {
+                self.finish()?;
                 return Ok(result);
             }
         }
     }
+}
 
-    /// Verify a hit on CPU and return the result if valid.
-    ///
-    /// Derivation errors are dropped (not fatal) so one bad hit cannot stop
-    /// a multi-GPU run. OpenCL enqueue/read errors stay fatal at the caller.
-    fn verify_hit(&self, hit: &GpuHit) -> Result<Option<VanityResult>, GpuError> {
-        match self.try_verify_hit(hit) {
-            Ok(v) => Ok(v),
-            Err(e) => {
-                eprintln!("Warning: GPU hit failed CPU verify ({e}); dropping");
-                Ok(None)
-            }
-        }
-    }
-
-    fn try_verify_hit(&self, hit: &GpuHit) -> Result<Option<VanityResult>, GpuError> {
-        use erg_vanity_address::encode_p2pk_mainnet;
-        use erg_vanity_bip::bip32::ExtendedPrivateKey;
-        use erg_vanity_bip::bip39::{entropy_to_mnemonic, mnemonic_to_seed};
-        use erg_vanity_bip::bip44::derive_ergo_key;
-        use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
-        use erg_vanity_crypto::secp256k1::scalar::Scalar;
-
-        let entropy = hit.entropy_bytes();
-
-        let mnemonic = entropy_to_mnemonic(&entropy)
-            .map_err(|e| GpuError::Other(format!("mnemonic error: {}", e)))?;
-
-        let seed = mnemonic_to_seed(&mnemonic, "");
-
-        let master = ExtendedPrivateKey::from_seed(&seed)
-            .map_err(|e| GpuError::Other(format!("bip32 error: {:?}", e)))?;
-
-        let ergo_key = derive_ergo_key(&master, 0, 0, hit.address_index)
-            .map_err(|e| GpuError::Other(format!("bip44 error: {:?}", e)))?;
-
-        let privkey = *ergo_key.private_key();
-        let scalar = Scalar::from_bytes(&privkey)
-            .ok_or_else(|| GpuError::Other("invalid scalar".to_string()))?;
-        let pubkey = PublicKey::from_private_key(&scalar)
-            .ok_or_else(|| GpuError::Other("invalid pubkey".to_string()))?;
-
-        let address = encode_p2pk_mainnet(pubkey.as_bytes());
-
-        let sorted_idx = hit.pattern_index as usize;
-        let original_idx = *self
-            .pattern_index_map
-            .get(sorted_idx)
-            .ok_or_else(|| GpuError::Other(format!("pattern_index {} out of range", sorted_idx)))?;
-        let pattern = self.patterns.get(original_idx as usize).ok_or_else(|| {
-            GpuError::Other(format!(
-                "original pattern_index {} out of range",
-                original_idx
-            ))
-        })?;
-
-        let matcher = Pattern::new(pattern.clone(), self.match_type).ignore_case(self.ignore_case);
-        let matches = matcher.matches(&address);
-
-        if matches {
-            Ok(Some(VanityResult {
-                entropy,
-                work_item_id: hit.work_item_id,
-                address_index: hit.address_index,
-                pattern_index: original_idx,
-                address,
-                mnemonic,
-            }))
-        } else {
-            eprintln!(
-                "Warning: GPU hit did not verify on CPU (addr={}, pattern={}, index={}, icase={})",
-                address, pattern, hit.address_index, self.ignore_case
-            );
-            Ok(None)
-        }
+impl Drop for VanityPipeline {
+    /// Overwrite the device buffers holding seeds, hit entropy and the salt,
+    /// so the next process to get this VRAM cannot read them back. Errors are
+    /// ignored: a lost device has nothing left to wipe.
+    fn drop(&mut self) {
+        let _ = self.buffers.wipe_secrets();
+        self.salt.zeroize();
     }
 }
 
@@ -450,6 +1048,44 @@ mod tests {
         assert_eq!(local_size_for(1000, 1), 1);
     }
 
+    #[test]
+    fn oversized_batch_halves_to_fit() {
+        assert_eq!(halve_to_fit(1 << 26, 1 << 23), 1 << 23);
+        assert_eq!(halve_to_fit(3 << 22, 1 << 23), 3 << 21);
+        assert_eq!(halve_to_fit(4096, 1 << 23), 4096);
+        assert_eq!(halve_to_fit(4096, 0), 1);
+    }
+
+    #[test]
+    fn interactive_batch_tracks_latency_target() {
+        let ms = 1_000_000;
+        // 4x over target: halve (max step), rounded to the granule
+        assert_eq!(
+            next_batch_size(65536, 200 * ms, 50 * ms, 256, 1 << 20),
+            32768
+        );
+        // Well under target: double at most
+        assert_eq!(next_batch_size(16384, 5 * ms, 50 * ms, 256, 1 << 20), 32768);
+        // Within 80-100% of target: unchanged
+        assert_eq!(next_batch_size(40000, 45 * ms, 50 * ms, 64, 1 << 20), 40000);
+        // Slightly over: scale to 90% of target
+        assert_eq!(next_batch_size(60000, 60 * ms, 50 * ms, 64, 1 << 20), 44992);
+        // Bounded by capacity and granule
+        assert_eq!(next_batch_size(1 << 20, ms, 50 * ms, 256, 1 << 20), 1 << 20);
+        assert_eq!(next_batch_size(256, 500 * ms, 50 * ms, 256, 1 << 20), 256);
+        assert_eq!(lcm(64, 256), 256);
+        assert_eq!(lcm(8, 12), 24);
+    }
+
+    #[test]
+    fn hit_capacity_grows_to_fit_overflow() {
+        assert_eq!(grown_hit_capacity(1500, 1024, 1 << 20), 4096);
+        assert_eq!(grown_hit_capacity(4096, 1024, 1 << 20), 8192);
+        // Never past the limit (a batch's work items), never shrinks
+        assert_eq!(grown_hit_capacity(3000, 1024, 4000), 4000);
+        assert_eq!(grown_hit_capacity(1500, 8192, 1 << 20), 8192);
+    }
+
     #[test]
     fn test_sort_patterns_longest_first() {
         let patterns = vec!["9e".into(), "9ergo".into(), "9er".into()];
@@ -458,6 +1094,22 @@ mod tests {
         assert_eq!(map, vec![1, 2, 0]);
     }
 
+    #[test]
+    fn test_active_patterns_skip_retired() {
+        let patterns = vec!["9e".into(), "9ergo".into(), "9er".into()];
+        let (sorted, map) = active_patterns_longest_first(&patterns, 0);
+        assert_eq!(sorted, vec!["9ergo", "9er", "9e"]);
+        assert_eq!(map, vec![1, 2, 0]);
+
+        let (sorted, map) = active_patterns_longest_first(&patterns, 0b010);
+        assert_eq!(sorted, vec!["9er", "9e"]);
+        assert_eq!(map, vec![2, 0]);
+
+        let (sorted, map) = active_patterns_longest_first(&patterns, 0b111);
+        assert!(sorted.is_empty());
+        assert!(map.is_empty());
+    }
+
     #[test]
     fn test_vanity_result_debug_redacts_secrets() {
         let result = dummy_result(1, 0, 0);
@@ -474,25 +1126,152 @@ mod tests {
         };
 
         let cfg = VanityConfig {
-            batch_size: 1024,
+            batch_size: Some(1024),
             ignore_case: false,
             num_indices: 1,
             match_type: MatchType::Prefix,
+            kernel_latency: None,
+            hit_capacity: None,
+            specialize: true,
         };
 
         let pipe = VanityPipeline::new(&["9".to_string()], cfg).expect("pipeline creation failed");
         println!("Pipeline created: {}", pipe.device_info());
     }
 
+    #[test]
+    fn test_pipelined_batches_return_late() {
+        let Some(_ctx) = crate::context::try_ctx() else {
+            return;
+        };
+
+        // Every mainnet P2PK address starts with '9', so each seed hits.
+        let cfg = VanityConfig {
+            batch_size: Some(256),
+            ..VanityConfig::default()
+        };
+        let mut pipe = VanityPipeline::new(&["9".to_string()], cfg).expect("pipeline");
+
+        assert!(pipe.run_batch_with_counter(0).unwrap().is_empty());
+        let mut results = pipe.run_batch_with_counter(256).unwrap();
+        results.extend(pipe.finish().unwrap());
+        assert_eq!(results.len(), 512);
+        let mut entropies: Vec<_> = results.iter().map(|r| r.entropy[..].to_vec()).collect();
+        entropies.sort();
+        entropies.dedup();
+        assert_eq!(entropies.len(), 512);
+        assert!(pipe.finish().unwrap().is_empty());
+        assert_eq!(pipe.addresses_checked(), 512);
+        assert_eq!(pipe.hits_dropped_total(), 0);
+    }
+
+    #[test]
+    fn test_shrink_searches_rest_of_range() {
+        let Some(_ctx) = crate::context::try_ctx() else {
+            return;
+        };
+
+        let cfg = VanityConfig {
+            batch_size: Some(1024),
+            ..VanityConfig::default()
+        };
+        let mut pipe = VanityPipeline::new(&["9".to_string()], cfg).expect("pipeline");
+        let mut results = pipe.run_batch_with_counter(0).unwrap();
+
+        // Only out-of-resources errors shrink.
+        let mut todo = vec![(1024, 1024)];
+        let err = GpuError::Other("not a resource error".into());
+        assert!(pipe.shrink_after(err, &mut todo).is_err());
+        assert_eq!(pipe.batch_size(), 1024);
+
+        // As if enqueueing counters 1024..2048 had run out of resources.
+        pipe.shrink(&mut todo).unwrap();
+        assert_eq!(pipe.batch_size(), 512);
+        pipe.search_ranges(todo).unwrap();
+        results.extend(pipe.finish().unwrap());
+
+        assert_eq!(results.len(), 2048);
+        let mut entropies: Vec<_> = results.iter().map(|r| r.entropy[..].to_vec()).collect();
+        entropies.sort();
+        entropies.dedup();
+        assert_eq!(entropies.len(), 2048);
+        assert_eq!(pipe.addresses_checked(), 2048);
+    }
+
+    #[test]
+    fn test_hit_buffer_grows_after_overflow() {
+        let Some(_ctx) = crate::context::try_ctx() else {
+            return;
+        };
+
+        let cfg = VanityConfig {
+            batch_size: Some(1024),
+            hit_capacity: Some(100),
+            ..VanityConfig::default()
+        };
+        let mut pipe = VanityPipeline::new(&["9".to_string()], cfg).expect("pipeline");
+
+        // Both slots start at 100 hits: the first two batches overflow.
+        let mut found = pipe.run_batch_with_counter(0).unwrap().len();
+        found += pipe.run_batch_with_counter(1024).unwrap().len();
+        found += pipe.run_batch_with_counter(2048).unwrap().len();
+        found += pipe.finish().unwrap().len();
+        assert_eq!(pipe.hit_capacity(), 1024);
+        assert_eq!(pipe.hits_dropped_total(), 2 * (1024 - 100));
+        assert_eq!(found, 100 + 100 + 1024);
+    }
+
+    #[test]
+    fn test_specialized_kernel_matches_generic() {
+        let Some(_ctx) = crate::context::try_ctx() else {
+            return;
+        };
+
+        let patterns = ["9e".to_string(), "9F".to_string()];
+        let run = |specialize: bool| {
+            let cfg = VanityConfig {
+                batch_size: Some(1024),
+                ignore_case: true,
+                num_indices: 2,
+                specialize,
+                ..VanityConfig::default()
+            };
+            let mut pipe =
+                VanityPipeline::new_with_device_and_salt(&patterns, cfg, 0, [5u8; 32]).unwrap();
+            assert_eq!(pipe.is_specialized(), specialize);
+            let mut found = pipe.run_batch_with_counter(0).unwrap();
+            found.extend(pipe.finish().unwrap());
+            sort_results_deterministically(&mut found);
+            (pipe, found)
+        };
+        let key = |r: &VanityResult| (r.work_item_id, r.address_index, r.pattern_index);
+
+        let (_, generic) = run(false);
+        let (mut pipe, specialized) = run(true);
+        assert!(!generic.is_empty());
+        assert_eq!(
+            specialized.iter().map(key).collect::<Vec<_>>(),
+            generic.iter().map(key).collect::<Vec<_>>()
+        );
+
+        // Retiring a pattern falls back to the generic kernel.
+        pipe.set_retired_patterns(1).unwrap();
+        assert!(!pipe.is_specialized());
+        let mut found = pipe.run_batch_with_counter(1024).unwrap();
+        found.extend(pipe.finish().unwrap());
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|r| r.pattern_index == 1));
+    }
+
     /// Helper to create a dummy VanityResult for ordering tests (no GPU needed).
     fn dummy_result(work_item_id: u32, address_index: u32, pattern_index: u32) -> VanityResult {
         VanityResult {
-            entropy: [0u8; 32],
+            entropy: Secret::new([0u8; 32]),
             work_item_id,
             address_index,
             pattern_index,
             address: "9test".into(),
-            mnemonic: "secret-words-here".into(),
+            mnemonic: Secret::new("secret-words-here".into()),
         }
     }
 
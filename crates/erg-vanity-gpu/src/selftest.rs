@@ -0,0 +1,535 @@
+//! Known-answer self-test run on each device before it joins a search.
+//!
+//! A miscompiled kernel on a buggy driver produces well-formed but wrong
+//! addresses, which would only surface later as hits that fail CPU verify.
+//! `vanity_derive_address` is run on fixed entropies and the seed, private
+//! key, pubkey and address bytes are compared against the CPU reference
+//! (`generate_address_from_entropy_at`). Then `vanity_seed` and
+//! `vanity_search` run one small batch at a fixed salt and counter whose hits
+//! the CPU knows in advance. A pattern-specialized program is checked the same
+//! way with its own patterns, then with probe patterns of the same shape
+//! uploaded in their place, which must find the known hit.
+
+use crate::buffers::GpuBuffers;
+use crate::comb::CombTableBuffer;
+use crate::context::{GpuContext, GpuError};
+use crate::kernel::GpuProgram;
+use crate::wordlist::WordlistBuffers;
+use erg_vanity_address::{p2pk_address_bytes, Network, P2PK_ADDRESS_BYTES};
+use erg_vanity_bip::bip39::mnemonic_to_seed;
+use erg_vanity_cpu::{generate_address_from_entropy_at, ExternalChain, Pattern};
+use erg_vanity_crypto::entropy::from_salt_counter;
+use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
+use erg_vanity_crypto::secp256k1::scalar::Scalar;
+use ocl::{Buffer, Event, Kernel, MemFlags};
+
+/// Fixed entropies covering both BIP39 HMAC key paths: a mnemonic longer than
+/// the 128-byte SHA-512 block (hashed first) and a short one (used raw).
+const VECTORS: [([u8; 32], &str); 3] = [
+    ([0x00; 32], "all-zero entropy (long mnemonic)"),
+    ([0xff; 32], "all-ones entropy (short mnemonic)"),
+    (
+        [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff, 0x0f, 0x1e, 0x2d, 0x3c, 0x4b, 0x5a, 0x69, 0x78, 0x87, 0x96, 0xa5, 0xb4,
+            0xc3, 0xd2, 0xe1, 0xf0,
+        ],
+        "mixed entropy",
+    ),
+];
+
+/// Work items in the known-hit `vanity_search` batch.
+const SEARCH_ITEMS: usize = 32;
+/// Salt and first counter of that batch.
+const SEARCH_SALT: [u8; 32] = [0x5a; 32];
+const SEARCH_COUNTER: u64 = 1 << 40;
+/// Address indices each work item checks; more than one, so the index loop runs.
+const SEARCH_INDICES: u32 = 2;
+/// (work item, address index) whose address prefix is the search pattern.
+const SEARCH_TARGET: (u32, u32) = (5, 1);
+/// Length of that prefix, long enough that no other address in the batch has it.
+const SEARCH_PATTERN_LEN: usize = 12;
+/// Filler for the probe's other slots. Mainnet addresses start with '9', so
+/// a run of '1's never matches.
+const PROBE_FILLER: char = '1';
+
+/// One `vanity_search` hit, comparable with the CPU's expectation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SearchHit {
+    work_item_id: u32,
+    address_index: u32,
+    pattern_index: u32,
+    entropy: [u8; 32],
+}
+
+/// Bytes produced at each stage of deriving m/44'/429'/0'/0/0.
+#[derive(Debug, PartialEq)]
+struct Derivation {
+    seed: [u8; 64],
+    private_key: [u8; 32],
+    pubkey: [u8; 33],
+    addr_bytes: [u8; P2PK_ADDRESS_BYTES],
+}
+
+impl Derivation {
+    /// First stage where `self` differs from `expected`, in pipeline order.
+    fn first_mismatch(&self, expected: &Derivation) -> Option<&'static str> {
+        if self.seed != expected.seed {
+            Some("BIP39 seed")
+        } else if self.private_key != expected.private_key {
+            Some("BIP32 private key")
+        } else if self.pubkey != expected.pubkey {
+            Some("public key")
+        } else if self.addr_bytes != expected.addr_bytes {
+            Some("address bytes")
+        } else {
+            None
+        }
+    }
+}
+
+fn cpu_reference(entropy: &[u8; 32]) -> Result<Derivation, GpuError> {
+    let generated = generate_address_from_entropy_at(entropy, Network::Mainnet, 0)
+        .map_err(|e| GpuError::Other(format!("self-test reference failed: {e}")))?;
+    let seed = mnemonic_to_seed(&generated.mnemonic, "");
+    let private_key = *generated.private_key;
+    let pubkey = Scalar::from_bytes(&private_key)
+        .and_then(|k| PublicKey::from_private_key(&k))
+        .ok_or_else(|| GpuError::Other("self-test reference key invalid".into()))?
+        .to_bytes();
+    Ok(Derivation {
+        seed: *seed,
+        private_key,
+        pubkey,
+        addr_bytes: p2pk_address_bytes(&pubkey, Network::Mainnet),
+    })
+}
+
+/// Output buffers of `vanity_derive_address`, reused across vectors.
+struct DeriveBuffers {
+    entropy: Buffer<u8>,
+    seed: Buffer<u8>,
+    private_key: Buffer<u8>,
+    pubkey: Buffer<u8>,
+    addr_bytes: Buffer<u8>,
+    error: Buffer<i32>,
+}
+
+impl DeriveBuffers {
+    fn new(ctx: &GpuContext) -> Result<Self, GpuError> {
+        let buf = |flags: MemFlags, len: usize| {
+            Buffer::<u8>::builder()
+                .queue(ctx.queue().clone())
+                .flags(flags)
+                .len(len)
+                .build()
+        };
+        Ok(Self {
+            entropy: buf(MemFlags::new().read_only(), 32)?,
+            seed: buf(MemFlags::new().write_only(), 64)?,
+            private_key: buf(MemFlags::new().write_only(), 32)?,
+            pubkey: buf(MemFlags::new().write_only(), 33)?,
+            addr_bytes: buf(MemFlags::new().write_only(), P2PK_ADDRESS_BYTES)?,
+            error: Buffer::<i32>::builder()
+                .queue(ctx.queue().clone())
+                .flags(MemFlags::new().write_only())
+                .len(1)
+                .build()?,
+        })
+    }
+
+    /// Run the kernel on one entropy. `Err(code)` when the kernel reports failure.
+    fn derive(
+        &self,
+        kernel: &Kernel,
+        entropy: &[u8; 32],
+    ) -> Result<Result<Derivation, i32>, GpuError> {
+        self.entropy.write(&entropy[..]).enq()?;
+        unsafe {
+            kernel.enq()?;
+        }
+
+        let mut error = [0i32; 1];
+        self.error.read(&mut error[..]).enq()?;
+        if error[0] != 0 {
+            return Ok(Err(error[0]));
+        }
+
+        let mut out = Derivation {
+            seed: [0; 64],
+            private_key: [0; 32],
+            pubkey: [0; 33],
+            addr_bytes: [0; P2PK_ADDRESS_BYTES],
+        };
+        self.seed.read(&mut out.seed[..]).enq()?;
+        self.private_key.read(&mut out.private_key[..]).enq()?;
+        self.pubkey.read(&mut out.pubkey[..]).enq()?;
+        self.addr_bytes.read(&mut out.addr_bytes[..]).enq()?;
+        Ok(Ok(out))
+    }
+}
+
+/// Hits the search kernels should report on the fixed batch: for each work
+/// item, the first index whose address matches, and the first pattern there.
+fn expected_hits(
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<Vec<SearchHit>, GpuError> {
+    let matchers: Vec<Pattern> = patterns
+        .iter()
+        .map(|p| Pattern::prefix(p.as_str()).ignore_case(ignore_case))
+        .collect();
+    let reference = |e: &str| GpuError::Other(format!("self-test reference failed: {e}"));
+    let mut hits = Vec::new();
+    for work_item_id in 0..SEARCH_ITEMS as u32 {
+        let entropy = from_salt_counter(&SEARCH_SALT, SEARCH_COUNTER + work_item_id as u64);
+        let chain = ExternalChain::from_entropy(&entropy).map_err(reference)?;
+        for address_index in 0..num_indices {
+            let address = chain
+                .address_at(address_index, Network::Mainnet)
+                .map_err(reference)?;
+            if let Some(p) = matchers.iter().position(|m| m.matches(&address)) {
+                hits.push(SearchHit {
+                    work_item_id,
+                    address_index,
+                    pattern_index: p as u32,
+                    entropy,
+                });
+                break;
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// The address of the `SEARCH_TARGET` work item at `address_index`.
+fn target_address(address_index: u32) -> Result<String, GpuError> {
+    let work_item_id = SEARCH_TARGET.0;
+    let entropy = from_salt_counter(&SEARCH_SALT, SEARCH_COUNTER + work_item_id as u64);
+    Ok(
+        generate_address_from_entropy_at(&entropy, Network::Mainnet, address_index)
+            .map_err(|e| GpuError::Other(format!("self-test reference failed: {e}")))?
+            .address,
+    )
+}
+
+/// Pattern for the known-hit batch: a prefix of the target address.
+fn search_pattern() -> Result<String, GpuError> {
+    Ok(target_address(SEARCH_TARGET.1)?[..SEARCH_PATTERN_LEN].to_string())
+}
+
+/// Known-hit stand-in for a specialized pattern set: as many patterns, of the
+/// same lengths, in the same order, so it fits the offsets and lengths the
+/// program has compiled in. The first (longest) slot is a prefix of the
+/// target work item's address at the last index the program searches, up to
+/// `SEARCH_TARGET.1`; the rest are filler.
+fn probe_patterns(
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<Vec<String>, GpuError> {
+    let mut target = target_address(SEARCH_TARGET.1.min(num_indices.max(1) - 1))?;
+    if ignore_case {
+        target.make_ascii_lowercase();
+    }
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match i {
+            0 => target
+                .get(..p.len())
+                .map(str::to_string)
+                .ok_or_else(|| GpuError::Other("pattern longer than an address".into())),
+            _ => Ok(PROBE_FILLER.to_string().repeat(p.len())),
+        })
+        .collect()
+}
+
+/// Run `vanity_seed` and `vanity_search` from `program` on the fixed batch
+/// and return the hits in sorted order. `patterns` are in GPU order, already
+/// lowercased for `ignore_case`.
+fn search_hits(
+    ctx: &GpuContext,
+    program: &GpuProgram,
+    wordlist: &WordlistBuffers,
+    comb: &CombTableBuffer,
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<Vec<SearchHit>, GpuError> {
+    let queue = ctx.queue();
+    let buffers = GpuBuffers::new(ctx, SEARCH_ITEMS, SEARCH_ITEMS)?;
+    buffers.upload_salt(&SEARCH_SALT)?;
+    let num_patterns = buffers.upload_patterns(patterns)? as u32;
+    let bufs = &buffers.slots[0];
+    bufs.reset_hits()?;
+
+    let seed = Kernel::builder()
+        .program(program.program())
+        .name("vanity_seed")
+        .queue(queue.clone())
+        .global_work_size(SEARCH_ITEMS)
+        .arg(&buffers.salt)
+        .arg(SEARCH_COUNTER)
+        .arg(&wordlist.words8)
+        .arg(&wordlist.lens)
+        .arg(&bufs.seeds)
+        .build()?;
+    let search = Kernel::builder()
+        .program(program.program())
+        .name("vanity_search")
+        .queue(queue.clone())
+        .global_work_size(SEARCH_ITEMS)
+        .arg(&buffers.salt)
+        .arg(SEARCH_COUNTER)
+        .arg(&bufs.seeds)
+        .arg(&buffers.patterns)
+        .arg(&buffers.pattern_offsets)
+        .arg(&buffers.pattern_lens)
+        .arg(num_patterns)
+        .arg(if ignore_case { 1u32 } else { 0u32 })
+        .arg(num_indices)
+        .arg(&bufs.hits)
+        .arg(&bufs.hit_count)
+        .arg(bufs.hit_capacity() as u32)
+        .arg(&comb.table)
+        .build()?;
+
+    let mut done = Event::empty();
+    unsafe {
+        seed.enq()?;
+        search.cmd().enew(&mut done).enq()?;
+    }
+    let count = bufs.read_hit_count(queue, &done)? as usize;
+    let mut hits: Vec<SearchHit> = bufs
+        .read_hits(queue, count)?
+        .iter()
+        .map(|hit| SearchHit {
+            work_item_id: hit.work_item_id,
+            address_index: hit.address_index,
+            pattern_index: hit.pattern_index,
+            entropy: hit.entropy_bytes(),
+        })
+        .collect();
+    hits.sort();
+    Ok(hits)
+}
+
+/// Check the compiled vanity program against the CPU on every known-answer
+/// vector, then on the known-hit search batch. Returns `GpuError::SelfTest`
+/// naming the first wrong stage.
+pub fn run(ctx: &GpuContext, program: &GpuProgram) -> Result<(), GpuError> {
+    let queue = ctx.queue();
+    let wordlist = WordlistBuffers::upload(queue)?;
+    let comb = CombTableBuffer::upload(queue)?;
+    let bufs = DeriveBuffers::new(ctx)?;
+
+    let kernel = Kernel::builder()
+        .program(program.program())
+        .name("vanity_derive_address")
+        .queue(queue.clone())
+        .global_work_size(1)
+        .arg(&bufs.entropy)
+        .arg(&wordlist.words8)
+        .arg(&wordlist.lens)
+        .arg(&bufs.seed)
+        .arg(&bufs.private_key)
+        .arg(&bufs.pubkey)
+        .arg(&bufs.addr_bytes)
+        .arg(&bufs.error)
+        .arg(&comb.table)
+        .build()?;
+
+    let fail = |detail: String| failure(ctx, detail);
+
+    for (entropy, name) in &VECTORS {
+        let expected = cpu_reference(entropy)?;
+        let got = bufs
+            .derive(&kernel, entropy)?
+            .map_err(|code| fail(format!("kernel error code {code} on {name}")))?;
+        if let Some(stage) = got.first_mismatch(&expected) {
+            return Err(fail(format!("{stage} mismatch on {name}")));
+        }
+    }
+
+    let patterns = [search_pattern()?];
+    check_search(ctx, program, &patterns, false, SEARCH_INDICES)
+}
+
+/// Run `vanity_search` from `program` on the fixed batch with `patterns` (in
+/// GPU order, already lowercased for `ignore_case`) and check its hits
+/// against the CPU. Used on pattern-specialized programs, which have
+/// `patterns` compiled in.
+pub(crate) fn check_search(
+    ctx: &GpuContext,
+    program: &GpuProgram,
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<(), GpuError> {
+    compare_search(ctx, program, patterns, ignore_case, num_indices).map(|_| ())
+}
+
+/// Check that `program`, specialized for `patterns`, finds a known hit.
+///
+/// With realistic patterns the fixed batch has no hits, so `check_search`
+/// alone only rules out false positives. This uploads `probe_patterns` in
+/// their place, runs the same program with the same `ignore_case` and
+/// `num_indices`, and requires a pattern 0 hit on the `SEARCH_TARGET` item.
+pub(crate) fn check_specialized_hit(
+    ctx: &GpuContext,
+    program: &GpuProgram,
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<(), GpuError> {
+    let probe = probe_patterns(patterns, ignore_case, num_indices)?;
+    let hits = compare_search(ctx, program, &probe, ignore_case, num_indices)?;
+    if !hits
+        .iter()
+        .any(|h| h.work_item_id == SEARCH_TARGET.0 && h.pattern_index == 0)
+    {
+        return Err(failure(
+            ctx,
+            "specialized vanity_search missed the known hit".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// `check_search`, returning the hits once they agree with the CPU.
+fn compare_search(
+    ctx: &GpuContext,
+    program: &GpuProgram,
+    patterns: &[String],
+    ignore_case: bool,
+    num_indices: u32,
+) -> Result<Vec<SearchHit>, GpuError> {
+    let queue = ctx.queue();
+    let wordlist = WordlistBuffers::upload(queue)?;
+    let comb = CombTableBuffer::upload(queue)?;
+    let expected = expected_hits(patterns, ignore_case, num_indices)?;
+    let got = search_hits(
+        ctx,
+        program,
+        &wordlist,
+        &comb,
+        patterns,
+        ignore_case,
+        num_indices,
+    )?;
+    if got != expected {
+        return Err(failure(
+            ctx,
+            format!(
+                "vanity_search reported {} hit(s) on the known batch, expected {}",
+                got.len(),
+                expected.len()
+            ),
+        ));
+    }
+    Ok(got)
+}
+
+fn failure(ctx: &GpuContext, detail: String) -> GpuError {
+    GpuError::SelfTest {
+        device: ctx.info().device_name.trim().to_string(),
+        driver: ctx.info().driver_version.trim().to_string(),
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specialize::KernelSpec;
+
+    #[test]
+    fn reference_matches_known_address() {
+        // BIP39 "abandon ×23 art" at m/44'/429'/0'/0/0, as ergo-lib derives it
+        let reference = cpu_reference(&VECTORS[0].0).unwrap();
+        assert_eq!(
+            erg_vanity_crypto::base58::encode(&reference.addr_bytes),
+            "9ecbd6yTXYZKjV76A7Dya4cFQX86pWAg6v3arcEikePo6oKnUkH"
+        );
+    }
+
+    #[test]
+    fn known_batch_has_exactly_the_target_hit() {
+        let pattern = search_pattern().unwrap();
+        let hits = expected_hits(&[pattern], false, SEARCH_INDICES).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            (
+                hits[0].work_item_id,
+                hits[0].address_index,
+                hits[0].pattern_index
+            ),
+            (SEARCH_TARGET.0, SEARCH_TARGET.1, 0)
+        );
+    }
+
+    #[test]
+    fn probe_keeps_shape_and_hits_target_only() {
+        let sets: [&[&str]; 4] = [
+            &["9err"],
+            &["9"],
+            &["9fABCDEFGH", "9err", "9e"],
+            &["9hKFJxXF9raSEPa6f9WX", "9i", "9g", "9f"],
+        ];
+        for patterns in sets {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            for ignore_case in [false, true] {
+                for num_indices in [1, SEARCH_INDICES, 5] {
+                    let probe = probe_patterns(&patterns, ignore_case, num_indices).unwrap();
+                    assert_eq!(probe.len(), patterns.len());
+                    for (i, (p, q)) in probe.iter().zip(&patterns).enumerate() {
+                        assert_eq!(p.len(), q.len(), "slot {i}");
+                    }
+
+                    let hits = expected_hits(&probe, ignore_case, num_indices).unwrap();
+                    assert!(hits.iter().all(|h| h.pattern_index == 0));
+                    let target = hits
+                        .iter()
+                        .find(|h| h.work_item_id == SEARCH_TARGET.0)
+                        .expect("target work item hits");
+                    assert!(target.address_index < num_indices);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn first_mismatch_reports_earliest_stage() {
+        let expected = cpu_reference(&VECTORS[1].0).unwrap();
+        let mut got = cpu_reference(&VECTORS[1].0).unwrap();
+        got.addr_bytes[37] ^= 1;
+        assert_eq!(got.first_mismatch(&expected), Some("address bytes"));
+        got.pubkey[1] ^= 1;
+        assert_eq!(got.first_mismatch(&expected), Some("public key"));
+        got.seed[0] ^= 1;
+        assert_eq!(got.first_mismatch(&expected), Some("BIP39 seed"));
+    }
+
+    #[test]
+    fn gpu_passes_self_test() {
+        let Some(ctx) = crate::context::try_ctx() else {
+            return;
+        };
+        let program = GpuProgram::vanity(&ctx).expect("compile vanity program");
+        run(&ctx, &program).expect("self-test");
+    }
+
+    #[test]
+    fn gpu_specialized_probe_finds_known_hit() {
+        let Some(ctx) = crate::context::try_ctx() else {
+            return;
+        };
+        let patterns = vec!["9err".to_string(), "9e".to_string()];
+        let spec = KernelSpec::new(&patterns, true, 1).unwrap();
+        let program = GpuProgram::vanity_specialized(&ctx, &spec).expect("compile");
+        check_search(&ctx, &program, &patterns, true, 1).expect("specialized search");
+        check_specialized_hit(&ctx, &program, &patterns, true, 1).expect("specialized probe");
+    }
+}
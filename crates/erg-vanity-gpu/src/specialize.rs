@@ -0,0 +1,115 @@
+//! Pattern-specialized builds of the vanity search kernel.
+//!
+//! The generic `vanity_search` reads patterns from global memory and loops
+//! over a runtime count for every address. For a handful of patterns the
+//! host instead prepends their lengths to the source as constants, together
+//! with the count, `ignore_case` and `num_indices`, so the compiler can unroll
+//! the compares and each address is Base58-converted once. The pattern bytes
+//! are still read from the uploaded buffer, so sets of the same shape share
+//! one entry in the kernel cache and the self-test can run a known-hit probe
+//! on the same program.
+
+use std::fmt::Write;
+
+/// Most patterns a specialized kernel is generated for. Larger sets use the
+/// generic kernel, where unrolling no longer pays for a separate build.
+pub const MAX_SPECIALIZED_PATTERNS: usize = 4;
+
+/// What a specialized `vanity_search` has compiled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelSpec {
+    /// Pattern lengths in kernel order (longest first).
+    lens: Vec<usize>,
+    ignore_case: bool,
+    num_indices: u32,
+}
+
+impl KernelSpec {
+    /// Spec for these GPU-ordered patterns, or `None` when there are none or
+    /// more than `MAX_SPECIALIZED_PATTERNS`.
+    pub fn new(patterns: &[String], ignore_case: bool, num_indices: u32) -> Option<Self> {
+        if patterns.is_empty() || patterns.len() > MAX_SPECIALIZED_PATTERNS {
+            return None;
+        }
+        Some(Self {
+            lens: patterns.iter().map(String::len).collect(),
+            ignore_case,
+            num_indices,
+        })
+    }
+
+    /// OpenCL source defining `VANITY_SPECIALIZED` and the pattern constants
+    /// `vanity.cl` reads in that mode.
+    pub fn prelude(&self) -> String {
+        let offsets: Vec<usize> = self
+            .lens
+            .iter()
+            .scan(0, |end, len| {
+                let offset = *end;
+                *end += len;
+                Some(offset)
+            })
+            .collect();
+        let list = |values: &[usize]| {
+            values
+                .iter()
+                .map(|v| format!("{v}u"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut src = String::from("// Pattern-specialized vanity_search (generated)\n");
+        src.push_str("#define VANITY_SPECIALIZED 1\n");
+        let _ = writeln!(src, "#define VANITY_NUM_PATTERNS {}u", self.lens.len());
+        let _ = writeln!(src, "#define VANITY_IGNORE_CASE {}", self.ignore_case as u8);
+        let _ = writeln!(src, "#define VANITY_NUM_INDICES {}u", self.num_indices);
+        let _ = writeln!(
+            src,
+            "__constant uint VANITY_PATTERN_OFFSETS[{}] = {{ {} }};",
+            offsets.len(),
+            list(&offsets)
+        );
+        let _ = writeln!(
+            src,
+            "__constant uint VANITY_PATTERN_LENS[{}] = {{ {} }};",
+            self.lens.len(),
+            list(&self.lens)
+        );
+        src
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prelude_bakes_in_pattern_shape() {
+        let spec = KernelSpec::new(&["9err".into(), "9e".into()], false, 3).unwrap();
+        let src = spec.prelude();
+        assert!(src.contains("#define VANITY_NUM_PATTERNS 2u"));
+        assert!(src.contains("#define VANITY_IGNORE_CASE 0"));
+        assert!(src.contains("#define VANITY_NUM_INDICES 3u"));
+        assert!(!src.contains("0x39"));
+        assert!(src.contains("VANITY_PATTERN_OFFSETS[2] = { 0u, 4u }"));
+        assert!(src.contains("VANITY_PATTERN_LENS[2] = { 4u, 2u }"));
+
+        // Same shape, same source; any other setting is a different cache entry.
+        let same = KernelSpec::new(&["9abc".into(), "9f".into()], false, 3).unwrap();
+        assert_eq!(same.prelude(), src);
+        let other = KernelSpec::new(&["9err".into(), "9e".into()], true, 3).unwrap();
+        assert_ne!(other.prelude(), src);
+        let longer = KernelSpec::new(&["9err".into(), "9er".into()], false, 3).unwrap();
+        assert_ne!(longer.prelude(), src);
+    }
+
+    #[test]
+    fn only_small_sets_are_specialized() {
+        assert!(KernelSpec::new(&[], false, 1).is_none());
+        let many: Vec<String> = (0..=MAX_SPECIALIZED_PATTERNS)
+            .map(|i| format!("9e{i}"))
+            .collect();
+        assert!(KernelSpec::new(&many[..MAX_SPECIALIZED_PATTERNS], false, 1).is_some());
+        assert!(KernelSpec::new(&many, false, 1).is_none());
+    }
+}
@@ -0,0 +1,590 @@
+//! Per-device autotuning of batch size and work-group sizes.
+//!
+//! `autotune_device` sweeps batch sizes around the compute-unit heuristic and,
+//! for each, the local work size of `vanity_seed` and of `vanity_search` on
+//! their own, timing both kernels with profiling events. The best result per
+//! (device, driver, num_indices) is saved and applied by `VanityPipeline` on
+//! creation: the search kernel's work per seed grows with the index count, so
+//! a batch tuned for one count does not carry over to another.
+//!
+//! File format is one tab-separated record per line:
+//! `device  driver  num_indices  batch_size  seed_local  search_local  seeds_per_sec  unix_time`.
+
+use crate::context::{DeviceInfo, GpuContext, GpuError};
+use crate::kernel::GpuProgram;
+use crate::pipeline::{VanityConfig, VanityPipeline};
+use erg_vanity_cpu::{MatchType, NEVER_MATCH_PREFIX};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Overrides the tuning file location.
+pub const TUNING_ENV: &str = "ERG_VANITY_GPU_TUNING";
+
+/// Local work sizes tried for each kernel, capped by the device.
+const LOCAL_CANDIDATES: [usize; 6] = [32, 64, 128, 256, 512, 1024];
+
+/// Batch sizes tried around the heuristic: ÷4 … ×4.
+const BATCH_SPREAD: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+const MIN_BATCH: usize = 1 << 12;
+const MAX_BATCH: usize = 1 << 21;
+
+/// A smaller batch within this fraction of the best rate wins: same
+/// throughput, less latency per batch and less VRAM.
+const RATE_TOLERANCE: f64 = 0.02;
+
+/// Best launch configuration found for one device, driver and index count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneProfile {
+    pub device: String,
+    pub driver: String,
+    /// BIP44 address indices per seed during the sweep.
+    pub num_indices: u32,
+    pub batch_size: usize,
+    /// Local work size of `vanity_seed`.
+    pub seed_local: usize,
+    /// Local work size of `vanity_search`.
+    pub search_local: usize,
+    /// Kernel-time seeds/s at the tuned sizes.
+    pub seeds_per_sec: f64,
+    /// Unix seconds.
+    pub recorded_at: u64,
+}
+
+impl TuneProfile {
+    pub fn new(info: &DeviceInfo, num_indices: u32, trial: &TuneTrial) -> Self {
+        Self {
+            device: clean_field(&info.device_name),
+            driver: clean_field(&info.driver_version),
+            num_indices: num_indices.max(1),
+            batch_size: trial.batch_size,
+            seed_local: trial.seed_local,
+            search_local: trial.search_local,
+            seeds_per_sec: trial.seeds_per_sec(),
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+/// All saved profiles.
+#[derive(Debug, Clone, Default)]
+pub struct TuneDb {
+    entries: Vec<TuneProfile>,
+}
+
+impl TuneDb {
+    /// `$ERG_VANITY_GPU_TUNING`, else `gpu_tuning.tsv` in the per-user config dir.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(p) = std::env::var(TUNING_ENV) {
+            if !p.is_empty() {
+                return Some(PathBuf::from(p));
+            }
+        }
+        let dir = if let Ok(appdata) = std::env::var("APPDATA") {
+            PathBuf::from(appdata)
+        } else if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+            PathBuf::from(xdg)
+        } else {
+            PathBuf::from(std::env::var("HOME").ok()?).join(".config")
+        };
+        Some(dir.join("erg-vanity").join("gpu_tuning.tsv"))
+    }
+
+    /// Load the default file. Missing or unreadable files give an empty database.
+    pub fn load_default() -> Self {
+        Self::default_path()
+            .and_then(|p| Self::load(&p).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load from `path`. A missing file is an empty database.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("cannot read {}: {e}", path.display())),
+        }
+    }
+
+    /// Parse file contents. Malformed lines are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .filter_map(parse_line)
+            .collect();
+        Self { entries }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::from(
+            "# device\tdriver\tnum_indices\tbatch_size\tseed_local\tsearch_local\tseeds_per_sec\tunix_time\n",
+        );
+        for e in &self.entries {
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{:.0}\t{}\n",
+                e.device,
+                e.driver,
+                e.num_indices,
+                e.batch_size,
+                e.seed_local,
+                e.search_local,
+                e.seeds_per_sec,
+                e.recorded_at
+            ));
+        }
+        out
+    }
+
+    /// Writes to a temporary file and renames it, so a crash or a concurrent
+    /// run leaves either the old file or the complete new one.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+            }
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        let tmp = PathBuf::from(tmp);
+        let write = || -> std::io::Result<()> {
+            let mut f = std::fs::File::create(&tmp)?;
+            f.write_all(self.to_text().as_bytes())?;
+            f.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            format!("cannot write {}: {e}", path.display())
+        })
+    }
+
+    pub fn entries(&self) -> &[TuneProfile] {
+        &self.entries
+    }
+
+    /// Insert or replace the profile for the same device, driver and index count.
+    pub fn record(&mut self, entry: TuneProfile) {
+        match self.entries.iter_mut().find(|e| {
+            e.device == entry.device
+                && e.driver == entry.driver
+                && e.num_indices == entry.num_indices
+        }) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Saved profile for this device and driver, tuned at `num_indices`.
+    pub fn lookup(&self, device: &str, driver: &str, num_indices: u32) -> Option<&TuneProfile> {
+        let device = clean_field(device);
+        let driver = clean_field(driver);
+        let num_indices = num_indices.max(1);
+        self.entries
+            .iter()
+            .find(|e| e.device == device && e.driver == driver && e.num_indices == num_indices)
+    }
+}
+
+/// Saved profile for `info`'s device and driver at `num_indices`, if it was
+/// autotuned.
+pub fn saved_profile(info: &DeviceInfo, num_indices: u32) -> Option<TuneProfile> {
+    TuneDb::load_default()
+        .lookup(&info.device_name, &info.driver_version, num_indices)
+        .cloned()
+}
+
+/// Merge `profiles` into the default tuning file.
+pub fn record_profiles(profiles: Vec<TuneProfile>) -> Result<PathBuf, String> {
+    let path = TuneDb::default_path()
+        .ok_or_else(|| "no home directory for the tuning file".to_string())?;
+    let mut db = TuneDb::load(&path)?;
+    for p in profiles {
+        db.record(p);
+    }
+    db.save(&path)?;
+    Ok(path)
+}
+
+/// Autotune configuration.
+#[derive(Debug, Clone)]
+pub struct TuneConfig {
+    /// BIP44 address indices per seed during the sweep.
+    pub num_indices: u32,
+    /// Batch sizes to try. Empty: a spread around the device heuristic.
+    pub batch_sizes: Vec<usize>,
+    /// Timed batches per trial; the fastest counts.
+    pub iters: u32,
+}
+
+impl Default for TuneConfig {
+    fn default() -> Self {
+        Self {
+            num_indices: 1,
+            batch_sizes: Vec::new(),
+            iters: 3,
+        }
+    }
+}
+
+/// One measured launch configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneTrial {
+    pub batch_size: usize,
+    pub seed_local: usize,
+    pub search_local: usize,
+    /// Fastest `vanity_seed` time over the timed batches.
+    pub seed_ns: u64,
+    /// Fastest `vanity_search` time over the timed batches.
+    pub search_ns: u64,
+}
+
+impl TuneTrial {
+    /// Seeds/s with both kernels back to back.
+    pub fn seeds_per_sec(&self) -> f64 {
+        self.batch_size as f64 / ((self.seed_ns + self.search_ns).max(1) as f64 / 1e9)
+    }
+}
+
+/// Result of tuning one device.
+#[derive(Debug, Clone)]
+pub struct TuneResult {
+    pub device_info: DeviceInfo,
+    /// Best trial per batch size, in sweep order.
+    pub per_batch: Vec<TuneTrial>,
+    pub best: TuneProfile,
+}
+
+/// Sweep batch and local work sizes on one device.
+///
+/// `progress` sees every trial as it finishes.
+pub fn autotune_device(
+    device_index: usize,
+    cfg: &TuneConfig,
+    mut progress: impl FnMut(&TuneTrial),
+) -> Result<TuneResult, GpuError> {
+    let ctx = GpuContext::with_device_profiling(device_index)?;
+    let program = GpuProgram::vanity(&ctx)?;
+    let device_info = ctx.info().clone();
+    let batches = if cfg.batch_sizes.is_empty() {
+        batch_candidates(&device_info)
+    } else {
+        cfg.batch_sizes.clone()
+    };
+
+    let mut per_batch = Vec::new();
+    let mut counter = 0u64;
+    for batch_size in batches {
+        let vcfg = VanityConfig {
+            batch_size: Some(batch_size),
+            ignore_case: false,
+            num_indices: cfg.num_indices,
+            match_type: MatchType::Prefix,
+            kernel_latency: None,
+            hit_capacity: None,
+            specialize: false,
+        };
+        let mut pipeline = VanityPipeline::from_parts(
+            ctx.clone(),
+            &program,
+            None,
+            &[NEVER_MATCH_PREFIX.to_string()],
+            vcfg,
+            [0x42; 32],
+            None,
+        )?;
+        let (seed_limit, search_limit) = pipeline.local_limits();
+        let (_, default_search) = pipeline.local_sizes();
+
+        // Warm up caches and clocks once per batch size.
+        pipeline.time_batch(counter)?;
+        counter += batch_size as u64;
+
+        let mut measure = |pipeline: &mut VanityPipeline, seed: usize, search: usize| {
+            pipeline.set_local_sizes(seed, search);
+            let mut trial = TuneTrial {
+                batch_size,
+                seed_local: seed,
+                search_local: search,
+                seed_ns: u64::MAX,
+                search_ns: u64::MAX,
+            };
+            for _ in 0..cfg.iters.max(1) {
+                let (seed_ns, search_ns) = pipeline.time_batch(counter)?;
+                counter += batch_size as u64;
+                trial.seed_ns = trial.seed_ns.min(seed_ns);
+                trial.search_ns = trial.search_ns.min(search_ns);
+            }
+            progress(&trial);
+            Ok::<_, GpuError>(trial)
+        };
+
+        // Seed kernel first, with the search kernel held at its default size.
+        let mut best_seed: Option<TuneTrial> = None;
+        for seed in local_candidates(seed_limit, batch_size) {
+            let trial = measure(&mut pipeline, seed, default_search)?;
+            if best_seed.as_ref().is_none_or(|b| trial.seed_ns < b.seed_ns) {
+                best_seed = Some(trial);
+            }
+        }
+        let seed_local = best_seed.map_or(default_search, |t| t.seed_local);
+
+        let mut best: Option<TuneTrial> = None;
+        for search in local_candidates(search_limit, batch_size) {
+            let trial = measure(&mut pipeline, seed_local, search)?;
+            if best.as_ref().is_none_or(|b| trial.search_ns < b.search_ns) {
+                best = Some(trial);
+            }
+        }
+        per_batch.extend(best);
+    }
+
+    let winner = pick_best(&per_batch)
+        .ok_or_else(|| GpuError::Other("autotune produced no trials".into()))?;
+    let best = TuneProfile::new(&device_info, cfg.num_indices, winner);
+    Ok(TuneResult {
+        device_info,
+        per_batch,
+        best,
+    })
+}
+
+/// Powers of two from ¼ to 4× `DeviceInfo::recommended_batch_size`, limited so the seed
+/// buffer (64 bytes per work item) stays under an eighth of device memory.
+fn batch_candidates(info: &DeviceInfo) -> Vec<usize> {
+    let base = info.recommended_batch_size() as f64;
+    let mem_cap = (info.global_mem_size / 8 / 64).max(MIN_BATCH as u64) as usize;
+    let mut out: Vec<usize> = BATCH_SPREAD
+        .iter()
+        .map(|f| {
+            ((base * f) as usize)
+                .next_power_of_two()
+                .clamp(MIN_BATCH, MAX_BATCH.min(mem_cap))
+        })
+        .collect();
+    out.dedup();
+    out
+}
+
+/// Candidate local sizes that fit `limit` and divide `batch`; never empty.
+fn local_candidates(limit: usize, batch: usize) -> Vec<usize> {
+    let out: Vec<usize> = LOCAL_CANDIDATES
+        .iter()
+        .copied()
+        .filter(|&l| l <= limit && batch.is_multiple_of(l))
+        .collect();
+    if out.is_empty() {
+        vec![limit.clamp(1, batch.max(1))]
+    } else {
+        out
+    }
+}
+
+/// Highest rate, preferring the smallest batch within `RATE_TOLERANCE` of it.
+fn pick_best(trials: &[TuneTrial]) -> Option<&TuneTrial> {
+    let top = trials
+        .iter()
+        .map(TuneTrial::seeds_per_sec)
+        .fold(0.0, f64::max);
+    trials
+        .iter()
+        .filter(|t| t.seeds_per_sec() >= top * (1.0 - RATE_TOLERANCE))
+        .min_by_key(|t| t.batch_size)
+}
+
+/// Print per-batch results and the chosen profile for each device.
+pub fn print_tune_results(results: &[TuneResult]) {
+    for r in results {
+        println!(
+            "\nDevice {}: {} - {}",
+            r.device_info.global_idx,
+            r.device_info.vendor.trim(),
+            r.device_info.device_name.trim()
+        );
+        println!(
+            "{:>9}  {:>10}  {:>12}  {:>10}  {:>10}  {:>12}",
+            "batch", "seed local", "search local", "seed ms", "search ms", "seeds/s"
+        );
+        for t in &r.per_batch {
+            let chosen = t.batch_size == r.best.batch_size;
+            println!(
+                "{:>9}  {:>10}  {:>12}  {:>10.2}  {:>10.2}  {:>12.0}{}",
+                t.batch_size,
+                t.seed_local,
+                t.search_local,
+                t.seed_ns as f64 / 1e6,
+                t.search_ns as f64 / 1e6,
+                t.seeds_per_sec(),
+                if chosen { "  <- chosen" } else { "" }
+            );
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<TuneProfile> {
+    let mut f = line.split('\t');
+    let device = f.next()?.to_string();
+    let driver = f.next()?.to_string();
+    let num_indices: u32 = f.next()?.parse().ok()?;
+    let batch_size: usize = f.next()?.parse().ok()?;
+    let seed_local: usize = f.next()?.parse().ok()?;
+    let search_local: usize = f.next()?.parse().ok()?;
+    let seeds_per_sec: f64 = f.next()?.parse().ok()?;
+    let recorded_at = f.next()?.parse().ok()?;
+    if device.is_empty()
+        || num_indices == 0
+        || batch_size == 0
+        || seed_local == 0
+        || search_local == 0
+    {
+        return None;
+    }
+    Some(TuneProfile {
+        device,
+        driver,
+        num_indices,
+        batch_size,
+        seed_local,
+        search_local,
+        seeds_per_sec,
+        recorded_at,
+    })
+}
+
+fn clean_field(s: &str) -> String {
+    s.trim().replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(compute_units: u32, global_mem_size: u64) -> DeviceInfo {
+        DeviceInfo {
+            global_idx: 0,
+            platform_idx: 0,
+            device_idx: 0,
+            kind: crate::context::DeviceKind::Gpu,
+            platform_name: "Test".into(),
+            device_name: "Test GPU".into(),
+            vendor: "Test".into(),
+            driver_version: "1.0".into(),
+            compute_units,
+            max_clock_mhz: None,
+            max_work_group_size: 1024,
+            global_mem_size,
+            local_mem_size: 48 << 10,
+        }
+    }
+
+    fn trial(batch_size: usize, total_ns: u64) -> TuneTrial {
+        TuneTrial {
+            batch_size,
+            seed_local: 64,
+            search_local: 128,
+            seed_ns: total_ns / 2,
+            search_ns: total_ns / 2,
+        }
+    }
+
+    #[test]
+    fn db_round_trips_and_replaces_same_device() {
+        let dev = info(80, 12 << 30);
+        let mut db = TuneDb::default();
+        db.record(TuneProfile::new(&dev, 1, &trial(1 << 18, 400_000_000)));
+        db.record(TuneProfile::new(&dev, 1, &trial(1 << 19, 700_000_000)));
+        assert_eq!(db.entries().len(), 1);
+
+        let parsed = TuneDb::parse(&db.to_text());
+        let p = parsed.lookup(" Test GPU ", "1.0", 1).unwrap();
+        assert_eq!(p.batch_size, 1 << 19);
+        assert_eq!((p.seed_local, p.search_local), (64, 128));
+        assert!(parsed.lookup("Test GPU", "2.0", 1).is_none());
+    }
+
+    #[test]
+    fn profiles_are_keyed_by_index_count() {
+        let dev = info(80, 12 << 30);
+        let mut db = TuneDb::default();
+        db.record(TuneProfile::new(&dev, 1, &trial(1 << 19, 700_000_000)));
+        db.record(TuneProfile::new(&dev, 100, &trial(1 << 14, 700_000_000)));
+        assert_eq!(db.entries().len(), 2);
+
+        let parsed = TuneDb::parse(&db.to_text());
+        assert_eq!(
+            parsed.lookup("Test GPU", "1.0", 1).unwrap().batch_size,
+            1 << 19
+        );
+        assert_eq!(
+            parsed.lookup("Test GPU", "1.0", 100).unwrap().batch_size,
+            1 << 14
+        );
+        assert!(parsed.lookup("Test GPU", "1.0", 4).is_none());
+    }
+
+    #[test]
+    fn save_replaces_file_and_leaves_no_temp() {
+        let dir = std::env::temp_dir().join(format!("erg-vanity-tune-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("gpu_tuning.tsv");
+        let dev = info(80, 12 << 30);
+        let mut db = TuneDb::default();
+        db.record(TuneProfile::new(&dev, 1, &trial(1 << 18, 400_000_000)));
+        db.save(&path).unwrap();
+        db.record(TuneProfile::new(&dev, 1, &trial(1 << 19, 700_000_000)));
+        db.save(&path).unwrap();
+
+        let loaded = TuneDb::load(&path).unwrap();
+        assert_eq!(
+            loaded.lookup("Test GPU", "1.0", 1).unwrap().batch_size,
+            1 << 19
+        );
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_skips_malformed_lines() {
+        let db = TuneDb::parse(
+            "# header\nGPU\t1.0\t1\t0\t64\t64\t1\t0\nGPU\t1.0\tx\n\nGPU\t1.0\t4096\t64\t64\t1\t0\n\
+             GPU\t1.0\t1\t4096\t64\t64\t1\t0\n",
+        );
+        assert_eq!(db.entries().len(), 1);
+    }
+
+    #[test]
+    fn batch_candidates_bracket_heuristic_and_respect_memory() {
+        let dev = info(80, 12 << 30);
+        let c = batch_candidates(&dev);
+        assert!(c.contains(&dev.recommended_batch_size()));
+        assert!(c.windows(2).all(|w| w[0] < w[1]));
+        assert!(c.iter().all(|b| b.is_power_of_two()));
+
+        // 64 MiB of memory: seeds may use 8 MiB, so at most 2^17 work items.
+        let small = batch_candidates(&info(80, 64 << 20));
+        assert!(small.iter().all(|&b| b <= 1 << 17));
+    }
+
+    #[test]
+    fn local_candidates_fit_limit_and_batch() {
+        assert_eq!(local_candidates(256, 1 << 16), vec![32, 64, 128, 256]);
+        assert_eq!(local_candidates(1024, 96), vec![32]);
+        assert_eq!(local_candidates(16, 1 << 16), vec![16]);
+    }
+
+    #[test]
+    fn pick_best_prefers_smaller_batch_at_equal_rate() {
+        // Rates: 2^16 → 655k/s, 2^17 → 1.31M/s, 2^18 → 1.32M/s (within 2%).
+        let trials = [
+            trial(1 << 16, 100_000_000),
+            trial(1 << 17, 100_000_000),
+            trial(1 << 18, 198_000_000),
+        ];
+        assert_eq!(pick_best(&trials).unwrap().batch_size, 1 << 17);
+        assert!(pick_best(&[]).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! CPU verification of GPU hits, off the pipeline's thread.
+//!
+//! Every hit is re-derived on the CPU (BIP39 → BIP32 → address) before it is
+//! reported. With short patterns and many indices that is thousands of PBKDF2
+//! runs per second, so batches of hits are handed to a shared thread pool and
+//! their results picked up on later calls while the GPU keeps searching.
+
+use crate::buffers::GpuHit;
+use crate::context::GpuError;
+use crate::pipeline::{sort_results_deterministically, VanityResult};
+use erg_vanity_core::{Secret, Zeroize};
+use erg_vanity_cpu::{MatchType, Pattern};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+
+/// Verified batches a pipeline may have outstanding before it waits for one.
+pub(crate) const MAX_VERIFY_BACKLOG: usize = 4;
+
+/// Pool shared by every pipeline in the process. Separate from the global
+/// rayon pool so a CPU search running alongside cannot starve verification.
+fn pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .thread_name(|i| format!("gpu-verify-{i}"))
+            .build()
+            .expect("failed to start the GPU hit verifier pool")
+    })
+}
+
+/// Checks GPU hits against the caller's original patterns.
+pub(crate) struct HitVerifier {
+    patterns: Vec<String>,
+    match_type: MatchType,
+    ignore_case: bool,
+}
+
+impl HitVerifier {
+    pub(crate) fn new(patterns: &[String], match_type: MatchType, ignore_case: bool) -> Self {
+        Self {
+            patterns: patterns.to_vec(),
+            match_type,
+            ignore_case,
+        }
+    }
+
+    /// Verify a hit on CPU and return the result if valid.
+    ///
+    /// Derivation errors are dropped (not fatal) so one bad hit cannot stop
+    /// a multi-GPU run.
+    pub(crate) fn verify(&self, hit: &GpuHit, pattern_index_map: &[u32]) -> Option<VanityResult> {
+        match self.try_verify(hit, pattern_index_map) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Warning: GPU hit failed CPU verify ({e}); dropping");
+                None
+            }
+        }
+    }
+
+    fn try_verify(
+        &self,
+        hit: &GpuHit,
+        pattern_index_map: &[u32],
+    ) -> Result<Option<VanityResult>, GpuError> {
+        use erg_vanity_address::encode_p2pk_mainnet;
+        use erg_vanity_bip::bip32::ExtendedPrivateKey;
+        use erg_vanity_bip::bip39::{entropy_to_mnemonic, mnemonic_to_seed};
+        use erg_vanity_bip::bip44::derive_ergo_key;
+        use erg_vanity_crypto::secp256k1::pubkey::PublicKey;
+        use erg_vanity_crypto::secp256k1::scalar::Scalar;
+
+        let mut raw_entropy = hit.entropy_bytes();
+        let entropy = Secret::locked(raw_entropy);
+        raw_entropy.zeroize();
+
+        let mnemonic = Secret::locked(
+            entropy_to_mnemonic(&entropy[..])
+                .map_err(|e| GpuError::Other(format!("mnemonic error: {}", e)))?,
+        );
+
+        let seed = mnemonic_to_seed(&mnemonic, "");
+
+        let master = ExtendedPrivateKey::from_seed(&seed[..])
+            .map_err(|e| GpuError::Other(format!("bip32 error: {:?}", e)))?;
+
+        let ergo_key = derive_ergo_key(&master, 0, 0, hit.address_index)
+            .map_err(|e| GpuError::Other(format!("bip44 error: {:?}", e)))?;
+
+        let scalar = Scalar::from_bytes(ergo_key.private_key())
+            .ok_or_else(|| GpuError::Other("invalid scalar".to_string()))?;
+        // The key is about to be handed to the user: constant-time derivation.
+        let pubkey = PublicKey::from_private_key_ct(&scalar)
+            .ok_or_else(|| GpuError::Other("invalid pubkey".to_string()))?;
+
+        let address = encode_p2pk_mainnet(pubkey.as_bytes());
+
+        let sorted_idx = hit.pattern_index as usize;
+        let original_idx = *pattern_index_map
+            .get(sorted_idx)
+            .ok_or_else(|| GpuError::Other(format!("pattern_index {} out of range", sorted_idx)))?;
+        let pattern = self.patterns.get(original_idx as usize).ok_or_else(|| {
+            GpuError::Other(format!(
+                "original pattern_index {} out of range",
+                original_idx
+            ))
+        })?;
+
+        let matcher = Pattern::new(pattern.clone(), self.match_type).ignore_case(self.ignore_case);
+        let matches = matcher.matches(&address);
+
+        if matches {
+            Ok(Some(VanityResult {
+                entropy,
+                work_item_id: hit.work_item_id,
+                address_index: hit.address_index,
+                pattern_index: original_idx,
+                address,
+                mnemonic,
+            }))
+        } else {
+            eprintln!(
+                "Warning: GPU hit did not verify on CPU (addr={}, pattern={}, index={}, icase={})",
+                address, pattern, hit.address_index, self.ignore_case
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// One pipeline's queue of hit batches being verified on the shared pool.
+pub(crate) struct VerifyQueue {
+    verifier: Arc<HitVerifier>,
+    tx: Sender<Vec<VanityResult>>,
+    rx: Receiver<Vec<VanityResult>>,
+    /// Batches submitted whose results have not been received.
+    outstanding: usize,
+}
+
+impl VerifyQueue {
+    pub(crate) fn new(verifier: HitVerifier) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            verifier: Arc::new(verifier),
+            tx,
+            rx,
+            outstanding: 0,
+        }
+    }
+
+    /// Verify `hits` in the background, wiping their entropy afterwards.
+    pub(crate) fn submit(&mut self, mut hits: Vec<GpuHit>, pattern_index_map: Vec<u32>) {
+        if hits.is_empty() {
+            return;
+        }
+        let verifier = Arc::clone(&self.verifier);
+        let tx = self.tx.clone();
+        self.outstanding += 1;
+        pool().spawn(move || {
+            let mut results: Vec<VanityResult> = hits
+                .par_iter_mut()
+                .filter_map(|hit| {
+                    let verified = verifier.verify(hit, &pattern_index_map);
+                    hit.entropy_words.zeroize();
+                    verified
+                })
+                .collect();
+            // Sort for stable output (GPU atomic_inc order is nondeterministic)
+            sort_results_deterministically(&mut results);
+            let _ = tx.send(results);
+        });
+    }
+
+    /// Results of the batches verified so far, waiting until at most
+    /// `max_outstanding` batches are still in progress.
+    pub(crate) fn collect(&mut self, max_outstanding: usize) -> Vec<VanityResult> {
+        let mut results = Vec::new();
+        while self.outstanding > 0 {
+            let batch = if self.outstanding > max_outstanding {
+                self.rx.recv().ok()
+            } else {
+                self.rx.try_recv().ok()
+            };
+            let Some(batch) = batch else {
+                break;
+            };
+            self.outstanding -= 1;
+            results.extend(batch);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use erg_vanity_address::Network;
+    use erg_vanity_cpu::generate_address_from_entropy_at;
+
+    fn hit_for(entropy: &[u8; 32], address_index: u32, pattern_index: u32) -> GpuHit {
+        let mut hit = GpuHit {
+            address_index,
+            pattern_index,
+            ..Default::default()
+        };
+        for (w, chunk) in hit.entropy_words.iter_mut().zip(entropy.chunks(4)) {
+            *w = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        hit
+    }
+
+    #[test]
+    fn queue_verifies_and_filters_hits() {
+        let address = generate_address_from_entropy_at(&[7u8; 32], Network::Mainnet, 1)
+            .unwrap()
+            .address;
+        let patterns = vec!["9zzzzz".to_string(), address[..4].to_string()];
+        let mut queue = VerifyQueue::new(HitVerifier::new(&patterns, MatchType::Prefix, false));
+
+        // Sorted index 0 is the original pattern 1; the second hit claims a
+        // pattern its address does not match and is dropped.
+        queue.submit(
+            vec![hit_for(&[7u8; 32], 1, 0), hit_for(&[7u8; 32], 1, 1)],
+            vec![1, 0],
+        );
+        queue.submit(Vec::new(), vec![1, 0]);
+        let results = queue.collect(0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].address, address);
+        assert_eq!(results[0].pattern_index, 1);
+        assert_eq!(results[0].entropy[..], [7u8; 32]);
+        assert!(queue.collect(0).is_empty());
+    }
+}
@@ -1,10 +1,13 @@
 //! Desktop UI for vanity search.
 
 use eframe::egui::{self, Color32, FontData, FontDefinitions, FontFamily, RichText, Stroke};
+use erg_vanity_core::Secret;
 use erg_vanity_cpu::MatchType;
 use erg_vanity_engine::{
-    estimate_pattern, format_rate, format_time, guess_rate_for, list_gpu_device_hints, run_search,
-    Backend, GpuDeviceHint, Hit, RateGuess, SearchEvent, SearchRequest,
+    combined_attempts, estimate_pattern, format_rate, format_time, guess_rate_for,
+    list_opencl_device_hints, load_blocklist, run_search, Backend, CalibrationDb, CpuLimits,
+    DeviceKind, Exclusion, GpuDeviceHint, GpuDuty, Hit, LiveOdds, RateGuess, SearchEvent,
+    SearchRequest, DUTY_PERCENT_RANGE,
 };
 use std::collections::VecDeque;
 use std::io::Write;
@@ -24,7 +27,11 @@ const LIVE: Color32 = Color32::from_rgb(72, 196, 120);
 const WARN: Color32 = Color32::from_rgb(196, 140, 64);
 const ERR: Color32 = Color32::from_rgb(208, 88, 64);
 
-const COMPILE_HINT: &str = "Compiling OpenCL (first run after a kernel change can take a minute)…";
+const COMPILE_HINT: &str =
+    "Preparing OpenCL (first run after a kernel or driver change can take a minute)…";
+
+/// GUI default: the GPU usually drives this display, so keep kernels short.
+const INTERACTIVE_KERNEL_LATENCY: Duration = Duration::from_millis(50);
 
 /// Launch the native window.
 pub fn run() -> Result<(), eframe::Error> {
@@ -54,8 +61,28 @@ struct VanityApp {
     ignore_case: bool,
     num_indices: u32,
     max_results: usize,
+    per_pattern: bool,
+    /// Comma-separated blocklist rules, as for `--exclude`.
+    exclude: String,
+    /// Blocklist file path; read when a search starts.
+    exclude_file: String,
+    /// Reject runs of `exclude_run` look-alike characters.
+    exclude_runs: bool,
+    exclude_run: usize,
     devices: String,
     batch_size: String,
+    /// Keep GPU kernels under `INTERACTIVE_KERNEL_LATENCY` so the desktop stays responsive.
+    interactive: bool,
+    /// Percent of time each GPU runs. Changes apply to a running search
+    /// through `gpu_duty_handle`.
+    gpu_duty: u8,
+    gpu_duty_handle: GpuDuty,
+    /// CPU worker threads; 0 means one per core.
+    cpu_threads: usize,
+    cpu_nice: i32,
+    cpu_duty: u8,
+    /// Save measured rates to the calibration file after long runs.
+    save_rates: bool,
     status: String,
     estimate_text: String,
     running: bool,
@@ -63,13 +90,24 @@ struct VanityApp {
     checked: u64,
     rate: f64,
     found: usize,
+    rejected: u64,
+    odds: Option<LiveOdds>,
     results: Vec<GuiHit>,
     search_patterns: Vec<String>,
+    /// Accepted hits per pattern of the running search, in pattern order.
+    pattern_found: Vec<usize>,
+    /// Patterns that met their quota and are no longer searched.
+    pattern_retired: Vec<bool>,
+    /// Per-pattern quota of the running search.
+    pattern_quota: Option<usize>,
     stop: Option<Arc<AtomicBool>>,
     rx: Option<Receiver<SearchEvent>>,
     worker: Option<JoinHandle<()>>,
     devices_hint: String,
-    gpu_devices: Vec<GpuDeviceHint>,
+    /// Every OpenCL device; the rate guess picks from these per backend.
+    device_hints: Vec<GpuDeviceHint>,
+    /// Measured device rates; reloaded when a search records new ones.
+    calibration: CalibrationDb,
     gpu_present: bool,
     started_at: Option<Instant>,
     elapsed: Duration,
@@ -82,7 +120,7 @@ struct VanityApp {
 struct SaveDraft {
     address: String,
     pattern: String,
-    mnemonic: String,
+    mnemonic: Secret<String>,
     address_index: u32,
     dest: String,
 }
@@ -109,19 +147,16 @@ impl VanityApp {
         style.spacing.button_padding = egui::vec2(12.0, 6.0);
         cc.egui_ctx.set_style(style);
 
-        let (gpu_present, devices_hint, gpu_devices) = match list_gpu_device_hints() {
-            Ok(list) if list.is_empty() => (
-                false,
-                "No OpenCL GPU — prefix search falls back to CPU.".into(),
-                Vec::new(),
-            ),
+        // CPU and accelerator OpenCL devices are listed so their indices can
+        // be picked, but only GPUs count toward "auto" and "all".
+        let (gpu_present, devices_hint, device_hints) = match list_opencl_device_hints() {
             Ok(list) => {
-                let hint = list
-                    .iter()
-                    .map(|d| d.display_line())
-                    .collect::<Vec<_>>()
-                    .join("  ·  ");
-                (true, hint, list)
+                let gpu_present = list.iter().any(|d| d.kind == DeviceKind::Gpu);
+                let mut lines: Vec<String> = list.iter().map(|d| d.display_line()).collect();
+                if !gpu_present {
+                    lines.insert(0, "No OpenCL GPU — prefix search falls back to CPU.".into());
+                }
+                (gpu_present, lines.join("  ·  "), list)
             }
             Err(e) => (false, format!("OpenCL: {e}"), Vec::new()),
         };
@@ -132,8 +167,20 @@ impl VanityApp {
             ignore_case: false,
             num_indices: 1,
             max_results: 1,
+            per_pattern: false,
+            exclude: String::new(),
+            exclude_file: String::new(),
+            exclude_runs: false,
+            exclude_run: 6,
             devices: "auto".into(),
             batch_size: String::new(),
+            interactive: true,
+            gpu_duty: 100,
+            gpu_duty_handle: GpuDuty::default(),
+            cpu_threads: 0,
+            cpu_nice: 0,
+            cpu_duty: 100,
+            save_rates: true,
             status: "Idle — Start searches the default 9err prefix.".into(),
             estimate_text: String::new(),
             running: false,
@@ -141,13 +188,19 @@ impl VanityApp {
             checked: 0,
             rate: 0.0,
             found: 0,
+            rejected: 0,
+            odds: None,
             results: Vec::new(),
             search_patterns: Vec::new(),
+            pattern_found: Vec::new(),
+            pattern_retired: Vec::new(),
+            pattern_quota: None,
             stop: None,
             rx: None,
             worker: None,
             devices_hint,
-            gpu_devices,
+            device_hints,
+            calibration: CalibrationDb::load_default(),
             gpu_present,
             started_at: None,
             elapsed: Duration::ZERO,
@@ -179,6 +232,9 @@ impl VanityApp {
                 devices: Vec::new(),
             });
         }
+        if n == "opencl" {
+            return Ok(Backend::OpenCl);
+        }
         let mut parsed = Vec::new();
         for part in self.devices.split(',') {
             let t = part.trim();
@@ -200,20 +256,33 @@ impl VanityApp {
         if !matches!(self.match_type(), MatchType::Prefix) {
             return false;
         }
-        if !self.gpu_present {
-            return false;
+        match self.backend() {
+            Ok(Backend::Cpu) => false,
+            // Explicit OpenCL choices may name CPU devices (e.g. POCL).
+            Ok(Backend::OpenCl) => true,
+            Ok(Backend::Gpu { devices }) if !devices.is_empty() => true,
+            _ => self.gpu_present,
+        }
+    }
+
+    fn cpu_limits(&self) -> CpuLimits {
+        CpuLimits {
+            threads: (self.cpu_threads > 0).then_some(self.cpu_threads),
+            nice: (self.cpu_nice > 0).then_some(self.cpu_nice),
+            duty_percent: (self.cpu_duty < 100).then_some(self.cpu_duty),
         }
-        !matches!(self.backend(), Ok(Backend::Cpu))
     }
 
     fn rate_guess(&self) -> RateGuess {
         let backend = self.backend().unwrap_or(Backend::Auto);
         guess_rate_for(
-            &self.gpu_devices,
+            &self.device_hints,
             &backend,
             self.match_type(),
             self.num_indices.max(1),
+            &self.calibration,
         )
+        .throttled(&self.cpu_limits())
     }
 
     fn assumed_rate(&self) -> f64 {
@@ -229,6 +298,16 @@ impl VanityApp {
     }
 
     fn engine_label(&self) -> String {
+        let label = self.device_label();
+        let limits = self.cpu_limits().describe();
+        if self.uses_gpu() || limits.is_empty() {
+            label
+        } else {
+            format!("{label} · {limits}")
+        }
+    }
+
+    fn device_label(&self) -> String {
         match self.match_type() {
             MatchType::Suffix => "CPU · suffix".into(),
             MatchType::Contains => "CPU · contains".into(),
@@ -237,12 +316,22 @@ impl VanityApp {
                     format!("GPU · {}", self.devices.trim())
                 }
                 Ok(Backend::Gpu { .. }) => "GPU · all".into(),
+                Ok(Backend::OpenCl) => "OpenCL · all".into(),
                 _ => "GPU · auto".into(),
             },
             MatchType::Prefix => "CPU · prefix".into(),
         }
     }
 
+    fn target(&self) -> usize {
+        let max = self.max_results.max(1);
+        if self.per_pattern {
+            max * self.pattern_list().len().max(1)
+        } else {
+            max
+        }
+    }
+
     fn pattern_list(&self) -> Vec<String> {
         self.patterns
             .split(',')
@@ -251,6 +340,30 @@ impl VanityApp {
             .collect()
     }
 
+    /// Inline rules and the look-alike run rule. The blocklist file is
+    /// added by `start`, so it is not re-read every frame.
+    fn inline_exclusions(&self) -> Result<Vec<Exclusion>, String> {
+        let mut out = Vec::new();
+        for rule in self.exclude.split(',').map(str::trim) {
+            if !rule.is_empty() {
+                out.push(Exclusion::parse(rule)?);
+            }
+        }
+        if self.exclude_runs {
+            out.push(Exclusion::confusable_run(self.exclude_run));
+        }
+        Ok(out)
+    }
+
+    fn exclusions(&self) -> Result<Vec<Exclusion>, String> {
+        let mut out = self.inline_exclusions()?;
+        let path = self.exclude_file.trim();
+        if !path.is_empty() {
+            out.extend(load_blocklist(Path::new(path))?);
+        }
+        Ok(out)
+    }
+
     fn pattern_issue(&self) -> Option<String> {
         let patterns = self.pattern_list();
         if patterns.is_empty() {
@@ -262,6 +375,9 @@ impl VanityApp {
         if let Err(e) = self.parse_batch_size() {
             return Some(e);
         }
+        if let Err(e) = self.inline_exclusions() {
+            return Some(e);
+        }
         let req = SearchRequest {
             patterns,
             match_type: self.match_type(),
@@ -271,6 +387,12 @@ impl VanityApp {
             duration: None,
             backend: Backend::Auto,
             batch_size: None,
+            kernel_latency: None,
+            per_pattern: None,
+            exclude: Vec::new(),
+            cpu_limits: self.cpu_limits(),
+            gpu_duty: GpuDuty::default(),
+            save_rates: false,
         };
         req.validate().err()
     }
@@ -296,9 +418,10 @@ impl VanityApp {
                     est.attempts_needed
                 };
                 lines.push(format!(
-                    "{p}: ~{} attempts · {} ({rate_note})",
+                    "{p}: ~{} attempts · {} · 90% {} ({rate_note})",
                     format_rate(est.attempts_needed),
-                    format_time(left / rate.max(1.0))
+                    format_time(left / rate.max(1.0)),
+                    format_time(est.p90_attempts / rate.max(1.0))
                 ));
             }
         }
@@ -310,27 +433,24 @@ impl VanityApp {
         self.estimate_text = lines.join("\n");
     }
 
-    fn easiest_attempts(&self) -> Option<f64> {
-        let mt = self.match_type();
-        let mut best = f64::INFINITY;
-        for p in self.pattern_list() {
-            let est = estimate_pattern(&p, mt, self.ignore_case);
-            if !est.has_invalid_chars && est.attempts_needed < best {
-                best = est.attempts_needed;
-            }
-        }
-        if best.is_finite() {
-            Some(best)
-        } else {
-            None
-        }
+    /// Mean attempts per hit over all patterns, overlaps counted once.
+    fn combined_attempts(&self) -> Option<f64> {
+        let attempts = combined_attempts(&self.pattern_list(), self.match_type(), self.ignore_case);
+        attempts.is_finite().then_some(attempts)
     }
 
     fn eta_label(&self) -> String {
         if self.pattern_issue().is_some() {
             return "—".into();
         }
-        let Some(attempts) = self.easiest_attempts() else {
+        if let (true, Some(odds)) = (self.running, self.odds) {
+            return format!(
+                "~{} · {:.0}%",
+                format_time(odds.remaining.p50),
+                odds.probability * 100.0
+            );
+        }
+        let Some(attempts) = self.combined_attempts() else {
             return "—".into();
         };
         let remaining = if self.running {
@@ -345,7 +465,7 @@ impl VanityApp {
         if self.pattern_issue().is_some() {
             return None;
         }
-        let attempts = self.easiest_attempts()?;
+        let attempts = self.combined_attempts()?;
         let secs = attempts / self.assumed_rate().max(1.0);
         if secs >= 86_400.0 {
             Some("This pattern is likely days or longer. Shorten it or expect a long run.".into())
@@ -412,6 +532,14 @@ impl VanityApp {
                 return;
             }
         };
+        let exclude = match self.exclusions() {
+            Ok(e) => e,
+            Err(e) => {
+                self.status = e;
+                self.had_error = true;
+                return;
+            }
+        };
         let req = SearchRequest {
             patterns: patterns.clone(),
             match_type: self.match_type(),
@@ -421,6 +549,15 @@ impl VanityApp {
             duration: None,
             backend,
             batch_size,
+            kernel_latency: self.interactive.then_some(INTERACTIVE_KERNEL_LATENCY),
+            per_pattern: self.per_pattern.then_some(self.max_results.max(1)),
+            exclude,
+            cpu_limits: self.cpu_limits(),
+            gpu_duty: {
+                self.gpu_duty_handle.set_all(self.gpu_duty);
+                self.gpu_duty_handle.clone()
+            },
+            save_rates: self.save_rates,
         };
         if let Err(e) = req.validate() {
             self.status = e;
@@ -429,10 +566,15 @@ impl VanityApp {
         }
         self.refresh_estimate();
         self.results.clear();
+        self.pattern_found = vec![0; patterns.len()];
+        self.pattern_retired = vec![false; patterns.len()];
+        self.pattern_quota = req.per_pattern;
         self.search_patterns = patterns;
         self.found = 0;
+        self.rejected = 0;
         self.checked = 0;
         self.rate = 0.0;
+        self.odds = None;
         self.rate_hist.clear();
         self.had_error = false;
         self.stopping = false;
@@ -481,10 +623,16 @@ impl VanityApp {
                     checked,
                     rate,
                     found,
+                    rejected,
+                    per_pattern,
+                    odds,
                 } => {
+                    self.pattern_found = per_pattern;
                     self.checked = checked;
                     self.rate = rate;
                     self.found = found;
+                    self.rejected = rejected;
+                    self.odds = (rate >= 1.0).then_some(odds);
                     self.rate_hist.push_back(rate as f32);
                     if self.rate_hist.len() > 48 {
                         self.rate_hist.pop_front();
@@ -503,6 +651,14 @@ impl VanityApp {
                     });
                     self.found = self.results.len();
                 }
+                SearchEvent::Retired { pattern_index } => {
+                    if let Some(r) = self.pattern_retired.get_mut(pattern_index as usize) {
+                        *r = true;
+                    }
+                }
+                SearchEvent::Calibrated { .. } => {
+                    self.calibration = CalibrationDb::load_default();
+                }
                 SearchEvent::Dropped { count, reason } => {
                     self.status =
                         reason.unwrap_or_else(|| format!("Dropped {count} overflow hits"));
@@ -514,10 +670,12 @@ impl VanityApp {
                 SearchEvent::Done {
                     checked,
                     found,
+                    rejected,
                     elapsed,
                 } => {
                     self.checked = checked;
                     self.found = found;
+                    self.rejected = rejected;
                     self.elapsed = elapsed;
                     if !self.had_error {
                         let verb = if self.stopping { "Stopped" } else { "Done" };
@@ -738,9 +896,42 @@ impl eframe::App for VanityApp {
                         );
                     }
                     ui.add(egui::Slider::new(&mut self.max_results, 1..=20).text("max results"));
+                    ui.checkbox(&mut self.per_pattern, "per pattern")
+                        .on_hover_text("Find max results for each pattern, then stop searching it");
+                    ui.add_space(8.0);
+                    ui.label(RichText::new("EXCLUDE").color(AMBER).size(11.0).strong());
+                    ui.label(
+                        RichText::new("Hits matching a rule are discarded; the search goes on")
+                            .small()
+                            .color(DIM),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.exclude)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("1111, literal:9err…, run:5")
+                            .font(egui::TextStyle::Monospace),
+                    )
+                    .on_hover_text(
+                        "Comma-separated: bare text or contains:<text>, literal:<address>, \
+                         run:<N>[:<chars>]",
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.exclude_file)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("blocklist file (optional)"),
+                    )
+                    .on_hover_text("One rule per line; # starts a comment. Read on Start.");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.exclude_runs, "look-alike runs")
+                            .on_hover_text("Reject addresses with this many 1/i/j/L in a row");
+                        ui.add_enabled(
+                            self.exclude_runs,
+                            egui::Slider::new(&mut self.exclude_run, 2..=12).integer(),
+                        );
+                    });
                     ui.add_space(8.0);
                     ui.label(RichText::new("DEVICES").color(AMBER).size(11.0).strong());
-                    ui.label(RichText::new("auto · 0 · all · cpu").small().color(DIM));
+                    ui.label(RichText::new("auto · 0 · all · opencl · cpu").small().color(DIM));
                     ui.add(
                         egui::TextEdit::singleline(&mut self.devices)
                             .desired_width(f32::INFINITY)
@@ -753,6 +944,51 @@ impl eframe::App for VanityApp {
                             .desired_width(f32::INFINITY)
                             .hint_text("device default"),
                     );
+                    ui.checkbox(&mut self.interactive, "keep desktop responsive")
+                        .on_hover_text(
+                            "Shrink GPU batches so each kernel runs under 50 ms; \
+                             batch becomes the upper bound",
+                        );
+                    let duty = ui
+                        .add(
+                            egui::Slider::new(&mut self.gpu_duty, DUTY_PERCENT_RANGE)
+                                .text("% GPU duty")
+                                .integer(),
+                        )
+                        .on_hover_text("Share of time each GPU runs; applies while searching");
+                    if duty.changed() {
+                        self.gpu_duty_handle.set_all(self.gpu_duty);
+                    }
+                    ui.add_space(8.0);
+                    ui.label(RichText::new("CPU LIMITS").color(AMBER).size(11.0).strong());
+                    ui.label(
+                        RichText::new("CPU searches only · rate shown is after throttling")
+                            .small()
+                            .color(DIM),
+                    );
+                    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+                    ui.add(
+                        egui::Slider::new(&mut self.cpu_threads, 0..=cores)
+                            .text("threads")
+                            .integer(),
+                    )
+                    .on_hover_text("0 = one per core");
+                    if cfg!(target_os = "linux") {
+                        ui.add(egui::Slider::new(&mut self.cpu_nice, 0..=19).text("nice"))
+                            .on_hover_text("Lower priority for the search threads; 19 is lowest");
+                    }
+                    ui.add(
+                        egui::Slider::new(&mut self.cpu_duty, DUTY_PERCENT_RANGE)
+                            .text("% duty")
+                            .integer(),
+                    )
+                    .on_hover_text("Share of time spent searching; the rest is idle");
+                    ui.add_space(8.0);
+                    ui.checkbox(&mut self.save_rates, "remember measured rates")
+                        .on_hover_text(
+                            "After an unthrottled run of 3 s or more, save each device's rate \
+                             to calibration.tsv for future estimates",
+                        );
                 });
                 ui.add_space(14.0);
                 ui.horizontal(|ui| {
@@ -827,21 +1063,37 @@ impl eframe::App for VanityApp {
                     stat_card(
                         ui,
                         "FOUND",
-                        &format!("{}/{}", self.found, self.max_results.max(1)),
+                        &format!("{}/{}", self.found, self.target()),
                         if self.found > 0 { LIVE } else { CREAM },
                     );
                 });
                 ui.add_space(10.0);
                 draw_sparkline(ui, &self.rate_hist, self.running);
+                if self.search_patterns.len() > 1 || self.pattern_quota.is_some() {
+                    ui.add_space(8.0);
+                    self.draw_pattern_progress(ui);
+                }
                 ui.add_space(12.0);
-                ui.label(RichText::new("HITS").color(AMBER).size(11.0).strong());
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("HITS").color(AMBER).size(11.0).strong());
+                    if self.rejected > 0 {
+                        ui.label(
+                            RichText::new(format!(
+                                "{} rejected by exclusions",
+                                format_count(self.rejected)
+                            ))
+                            .small()
+                            .color(WARN),
+                        );
+                    }
+                });
                 ui.add_space(6.0);
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     if self.results.is_empty() {
                         empty_hits(ui, self.running, compiling);
                     }
                     let mut copied = false;
-                    let mut save_req: Option<(String, String, String, u32)> = None;
+                    let mut save_req: Option<(String, String, Secret<String>, u32)> = None;
                     for (i, row) in self.results.iter_mut().enumerate() {
                         egui::Frame::NONE
                             .fill(PANEL)
@@ -895,7 +1147,7 @@ impl eframe::App for VanityApp {
                                 });
                                 if row.revealed {
                                     ui.label(
-                                        RichText::new(&row.hit.mnemonic)
+                                        RichText::new(row.hit.mnemonic.as_str())
                                             .monospace()
                                             .small()
                                             .color(CREAM),
@@ -907,7 +1159,7 @@ impl eframe::App for VanityApp {
                                             row.revealed = false;
                                         }
                                         if ui.button("Copy mnemonic").clicked() {
-                                            ui.ctx().copy_text(row.hit.mnemonic.clone());
+                                            ui.ctx().copy_text(row.hit.mnemonic.to_string());
                                             copied = true;
                                         }
                                     } else if ui
@@ -961,6 +1213,38 @@ impl eframe::App for VanityApp {
 }
 
 impl VanityApp {
+    /// Hits per pattern, with retired patterns struck through.
+    fn draw_pattern_progress(&self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                RichText::new("PER PATTERN")
+                    .color(AMBER)
+                    .size(11.0)
+                    .strong(),
+            );
+            for (i, pattern) in self.search_patterns.iter().enumerate() {
+                let found = self.pattern_found.get(i).copied().unwrap_or(0);
+                let retired = self.pattern_retired.get(i).copied().unwrap_or(false);
+                let count = match self.pattern_quota {
+                    Some(quota) => format!("{found}/{quota}"),
+                    None => found.to_string(),
+                };
+                let mut text = RichText::new(format!("{pattern} {count}")).monospace();
+                text = if retired {
+                    text.color(LIVE).strikethrough()
+                } else if found > 0 {
+                    text.color(CREAM)
+                } else {
+                    text.color(DIM)
+                };
+                let label = ui.label(text);
+                if retired {
+                    label.on_hover_text("Quota met; no longer searched");
+                }
+            }
+        });
+    }
+
     fn draw_save_dialog(&mut self, ctx: &egui::Context) {
         let Some(draft) = self.save_draft.as_mut() else {
             return;
@@ -1165,7 +1449,7 @@ fn write_hit_file(
         }
     }
     let bip44 = format!("m/44'/429'/0'/0/{address_index}");
-    let body = format_hit_file(address, &bip44, pattern, mnemonic);
+    let body = Secret::new(format_hit_file(address, &bip44, pattern, mnemonic));
     let mut opts = std::fs::OpenOptions::new();
     opts.write(true).create_new(true);
     #[cfg(unix)]